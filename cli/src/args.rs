@@ -0,0 +1,156 @@
+use std::{
+    io::{self, IsTerminal},
+    path::PathBuf,
+};
+
+use midi_splitter_core::Mode;
+
+pub const USAGE: &str = "\
+Usage: midi-splitter [OPTIONS] <INPUT>...
+       midi-splitter [OPTIONS] --watch <FOLDER>
+
+Creates a file for each track of every given MIDI file.
+Inputs are files, folders which are searched for MIDI files, or patterns using * and ? in
+the file name such as songs/*.mid.
+Without inputs, or with - as the input, the song is read from the standard input and the
+MIDI file created is written to the standard output, or the archive if there are several
+files, e.g. midi-splitter --emphasize 3 < in.mid > out.mid
+
+Settings are taken from the configuration files, then the settings file, then the
+changes for the song in the configuration files and then --set.
+
+Options:
+  -o, --output <FOLDER>   Folder the files are written to, the current folder by default.
+                          The files of each song go into a folder of their own.
+  -s, --settings <FILE>   Settings saved by the web app
+  -c, --config <FILE>     Configuration file to use instead of midi_splitter.toml in the
+                          current folder or above and in the midi_splitter folder of the
+                          user configuration folder
+      --no-config         Ignore the configuration files
+      --set <NAME=VALUE>  Change a single setting, named as in the saved settings,
+                          e.g. --set mode=Solo or --set tempo_variants=[50,75]
+      --emphasize <TRACK> Only create the file emphasising the track, given by its
+                          position in the MIDI file counting from 0
+      --solo <TRACK>      Only create the file with the track on its own
+      --minus-one <TRACK> Only create the file without the track
+      --zip               Write an archive for each song instead of the separate files
+      --watch <FOLDER>    Keep running and process every MIDI file which is put into the
+                          folder or changed, writing the files next to it. Files already
+                          in the folder and those in its subfolders are left alone.
+      --print-settings    Print the settings which would be used and exit
+  -h, --help              Print this help
+  -V, --version           Print the version
+";
+
+/// Arguments of a run of the tool
+pub struct Args {
+    pub inputs: Vec<String>,
+    pub output: PathBuf,
+    pub settings: Option<PathBuf>,
+    /// Configuration file replacing those found in the usual places
+    pub config: Option<PathBuf>,
+    pub no_config: bool,
+    /// Settings changed on the command line by their name, with their value as given
+    pub overrides: Vec<(String, String)>,
+    /// Mode and the only track files are created for
+    pub only_track: Option<(Mode, usize)>,
+    pub zip: bool,
+    /// Folder whose new and changed MIDI files are processed instead of the inputs
+    pub watch: Option<PathBuf>,
+    pub print_settings: bool,
+}
+
+/// What the command line asks for
+pub enum Command {
+    Run(Args),
+    Help,
+    Version,
+}
+
+/// Read the command line arguments without the name of the program
+pub fn parse(mut arguments: impl Iterator<Item = String>) -> anyhow::Result<Command> {
+    let mut args = Args {
+        inputs: Vec::new(),
+        output: PathBuf::from("."),
+        settings: None,
+        config: None,
+        no_config: false,
+        overrides: Vec::new(),
+        only_track: None,
+        zip: false,
+        watch: None,
+        print_settings: false,
+    };
+    let mut output_given = false;
+    // Everything after -- is an input, even if it starts with a dash
+    let mut only_inputs = false;
+
+    while let Some(argument) = arguments.next() {
+        if only_inputs || argument == "-" || !argument.starts_with('-') {
+            args.inputs.push(argument);
+            continue;
+        }
+        let mut value = || {
+            arguments
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("{} needs a value", argument))
+        };
+        match argument.as_str() {
+            "--" => only_inputs = true,
+            "-o" | "--output" => {
+                args.output = value()?.into();
+                output_given = true;
+            }
+            "-s" | "--settings" => args.settings = Some(value()?.into()),
+            "-c" | "--config" => args.config = Some(value()?.into()),
+            "--no-config" => args.no_config = true,
+            "--set" => {
+                let setting = value()?;
+                let (name, value) = setting.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!("--set needs a setting like NAME=VALUE, not {}", setting)
+                })?;
+                args.overrides
+                    .push((name.trim().to_string(), value.trim().to_string()));
+            }
+            "--emphasize" | "--solo" | "--minus-one" => {
+                let mode = match argument.as_str() {
+                    "--emphasize" => Mode::Emphasize,
+                    "--solo" => Mode::Solo,
+                    _ => Mode::MinusOne,
+                };
+                let track = value()?;
+                let track = track.parse().map_err(|_| {
+                    anyhow::anyhow!("{} needs the number of a track, not {}", argument, track)
+                })?;
+                args.only_track = Some((mode, track));
+            }
+            "--zip" => args.zip = true,
+            "--watch" => args.watch = Some(value()?.into()),
+            "--print-settings" => args.print_settings = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            _ => anyhow::bail!("Unknown option {}", argument),
+        }
+    }
+
+    if args.watch.is_some() {
+        if !args.inputs.is_empty() {
+            anyhow::bail!("Inputs cannot be given together with --watch");
+        }
+        if output_given {
+            anyhow::bail!(
+                "--output cannot be used with --watch, the files are written next to the songs"
+            );
+        }
+    } else if args.inputs.is_empty() && !args.print_settings {
+        // Songs are only piped in if the input is not typed
+        if io::stdin().is_terminal() {
+            anyhow::bail!("No input files given");
+        }
+        args.inputs.push("-".to_string());
+    }
+    if args.inputs.len() > 1 && args.inputs.iter().any(|input| input == "-") {
+        anyhow::bail!("The standard input cannot be read together with other inputs");
+    }
+    Ok(Command::Run(args))
+}
@@ -0,0 +1,104 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the configuration files
+const FILE_NAME: &str = "midi_splitter.toml";
+
+/// Changes to the settings by their name, with values written as in the saved settings
+pub type Changes = serde_json::Map<String, serde_json::Value>;
+
+/// Settings from the configuration files, which are TOML files naming the settings as the saved
+/// settings do, with a table under `songs` for the changes to each song:
+///
+/// ```toml
+/// mode = "Solo"
+/// name_template = "{song} - {track}"
+///
+/// [songs."Ave Maria.mid"]
+/// transpose = -2
+/// ```
+#[derive(Default)]
+pub struct Config {
+    /// Changes for all songs
+    pub settings: Changes,
+    /// Changes for single songs by their file name, with or without the extension
+    pub songs: BTreeMap<String, Changes>,
+}
+
+impl Config {
+    /// Read a configuration file
+    fn read(path: &Path) -> anyhow::Result<Config> {
+        let failed =
+            |detail: String| anyhow::anyhow!("Failed to read {}: {}", path.display(), detail);
+        let text = fs::read_to_string(path).map_err(|e| failed(e.to_string()))?;
+        let table: toml::Table = text
+            .parse()
+            .map_err(|e: toml::de::Error| failed(e.to_string()))?;
+        let serde_json::Value::Object(mut settings) =
+            serde_json::to_value(table).map_err(|e| failed(e.to_string()))?
+        else {
+            unreachable!("A table is written as an object");
+        };
+
+        let songs = match settings.remove("songs") {
+            Some(songs) => serde_json::from_value(songs)
+                .map_err(|_| failed("songs must hold a table for each song".to_string()))?,
+            None => BTreeMap::new(),
+        };
+        Ok(Config { settings, songs })
+    }
+
+    /// Add the changes of another file, which take precedence
+    fn merge(&mut self, other: Config) {
+        self.settings.extend(other.settings);
+        for (song, changes) in other.songs {
+            self.songs.entry(song).or_default().extend(changes);
+        }
+    }
+
+    /// Changes for the song in the file with the given name
+    pub fn song(&self, file_name: &str) -> Option<&Changes> {
+        let song_name = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(song_name, _)| song_name);
+        self.songs
+            .get(file_name)
+            .or_else(|| self.songs.get(song_name))
+    }
+}
+
+/// Configuration file applying to all projects of the user
+fn global_file() -> Option<PathBuf> {
+    let folder = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(folder.join("midi_splitter").join(FILE_NAME))
+}
+
+/// Configuration file of the project, in the current folder or the closest folder above it
+fn project_file() -> Option<PathBuf> {
+    let current = env::current_dir().ok()?;
+    current
+        .ancestors()
+        .map(|folder| folder.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Read the given configuration file, or the global and the project file if there are any
+pub fn load(file: Option<&Path>) -> anyhow::Result<Config> {
+    if let Some(file) = file {
+        return Config::read(file);
+    }
+
+    let mut config = Config::default();
+    for path in [global_file(), project_file()].into_iter().flatten() {
+        if path.is_file() {
+            config.merge(Config::read(&path)?);
+        }
+    }
+    Ok(config)
+}
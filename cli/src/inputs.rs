@@ -0,0 +1,89 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use midi_splitter_core::MIDI_EXTENSIONS;
+
+/// Expand the inputs into the files they name. Folders are searched for MIDI files including
+/// their subfolders, and * and ? in the file name match any characters and any single one.
+pub fn expand(inputs: &[String]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        let pattern = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| name.contains(['*', '?']));
+
+        if let Some(pattern) = pattern {
+            let folder = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            let pattern: Vec<char> = pattern.chars().collect();
+            let mut matched: Vec<PathBuf> = read_folder(folder)?
+                .into_iter()
+                .filter(|file| file.is_file())
+                .filter(|file| {
+                    file.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| matches(&pattern, &name.chars().collect::<Vec<_>>()))
+                })
+                .collect();
+            if matched.is_empty() {
+                anyhow::bail!("No files match {}", input);
+            }
+            matched.sort();
+            files.extend(matched);
+        } else if path.is_dir() {
+            find_midi_files(path, &mut files)?;
+        } else {
+            // Missing files are reported when they get read
+            files.push(path.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+/// Entries of a folder sorted by name
+pub fn read_folder(folder: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(folder)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", folder.display(), e))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+/// Add the MIDI files of the folder and its subfolders
+fn find_midi_files(folder: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    for entry in read_folder(folder)? {
+        if entry.is_dir() {
+            find_midi_files(&entry, files)?;
+        } else if is_midi_file(&entry) {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// Whether the path ends in one of the MIDI extensions
+pub fn is_midi_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| MIDI_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// Whether a file name matches a pattern with * and ? wildcards
+fn matches(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..]))
+        }
+        (Some('?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+        (Some(expected), Some(actual)) if expected == actual => matches(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
@@ -0,0 +1,308 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod args;
+#[cfg(not(target_arch = "wasm32"))]
+mod config;
+#[cfg(not(target_arch = "wasm32"))]
+mod inputs;
+#[cfg(not(target_arch = "wasm32"))]
+mod watch;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::{
+        collections::HashSet,
+        env, fs,
+        io::{self, IsTerminal, Read, Write},
+        path::Path,
+        process::ExitCode,
+    };
+
+    use midi_splitter_core::{
+        deduplicate, preset, riff, sanitize_file_name, validate_name_template, Outputs,
+        ProcessOptions, SplitError,
+    };
+
+    use crate::{
+        args::{self, Args, Command},
+        config::{self, Config},
+        inputs, watch,
+    };
+
+    /// Name the song read from the standard input is given, which the file names are built from
+    const STDIN_FILE_NAME: &str = "song.mid";
+
+    pub fn main() -> ExitCode {
+        let args = match args::parse(env::args().skip(1)) {
+            Ok(Command::Run(args)) => args,
+            Ok(Command::Help) => {
+                print!("{}", args::USAGE);
+                return ExitCode::SUCCESS;
+            }
+            Ok(Command::Version) => {
+                println!("midi-splitter {}", env!("CARGO_PKG_VERSION"));
+                return ExitCode::SUCCESS;
+            }
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, args::USAGE);
+                return ExitCode::from(2);
+            }
+        };
+
+        match run(&args) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ExitCode::FAILURE
+            }
+        }
+    }
+
+    /// Settings from the configuration files, changed by the settings file
+    fn base_options(args: &Args, config: &Config) -> anyhow::Result<ProcessOptions> {
+        let options = preset::apply(&ProcessOptions::default(), config.settings.clone())?;
+        match &args.settings {
+            Some(path) => {
+                let changes =
+                    preset::import_changes(&fs::read(path).map_err(|e| {
+                        anyhow::anyhow!("Failed to read {}: {}", path.display(), e)
+                    })?)?;
+                preset::apply(&options, changes)
+            }
+            None => Ok(options),
+        }
+    }
+
+    /// Settings for the song in the file with the given name, with its changes from the
+    /// configuration files and then the changes given on the command line
+    fn options(
+        base: &ProcessOptions,
+        args: &Args,
+        config: &Config,
+        file_name: Option<&str>,
+    ) -> anyhow::Result<ProcessOptions> {
+        let mut options = base.clone();
+        if let Some(changes) = file_name.and_then(|file_name| config.song(file_name)) {
+            options = preset::apply(&options, changes.clone())
+                .map_err(|e| anyhow::anyhow!("{} in the configuration of the song", e))?;
+        }
+
+        if !args.overrides.is_empty() {
+            let changes = args
+                .overrides
+                .iter()
+                .map(|(name, value)| {
+                    // Values which are not JSON such as --set mode=Solo are taken as text
+                    let value = serde_json::from_str(value)
+                        .unwrap_or_else(|_| serde_json::Value::String(value.clone()));
+                    (name.clone(), value)
+                })
+                .collect();
+            options = preset::apply(&options, changes)?;
+        }
+
+        validate_name_template(&options.name_template).map_err(|e| anyhow::anyhow!(e))?;
+        options.validate()?;
+        if let Some((mode, _)) = args.only_track {
+            options.mode = mode;
+            // The copy with all tracks was not asked for
+            options.all_tracks_suffix = None;
+        }
+        // The files only get written on their own without --zip
+        options.keep_files = !args.zip;
+        Ok(options)
+    }
+
+    /// Process every input, returning whether all of them could be processed
+    fn run(args: &Args) -> anyhow::Result<bool> {
+        let config = if args.no_config {
+            Config::default()
+        } else {
+            config::load(args.config.as_deref())?
+        };
+        let base = base_options(args, &config)?;
+        // Mistakes in the settings are reported before any song gets processed
+        let options = options(&base, args, &config, None)?;
+        if args.print_settings {
+            io::stdout().write_all(&preset::export(&options)?)?;
+            println!();
+            return Ok(true);
+        }
+
+        if args.inputs == ["-"] {
+            pipe(&base, args, &config)?;
+            return Ok(true);
+        }
+
+        let song_options = |path: &Path| {
+            let file_name = path.file_name().and_then(|name| name.to_str());
+            self::options(&base, args, &config, file_name)
+        };
+        if let Some(folder) = &args.watch {
+            println!(
+                "Watching {} for MIDI files, press Ctrl+C to stop",
+                folder.display()
+            );
+            watch::watch(folder, |path| {
+                // Each song is written to the same place every time it changes
+                let output = path.parent().unwrap_or(Path::new("."));
+                let result = song_options(path).and_then(|options| {
+                    process_song(path, options, args, output, &mut HashSet::new())
+                });
+                report(path, result);
+            })?;
+            return Ok(true);
+        }
+
+        let files = inputs::expand(&args.inputs)?;
+        fs::create_dir_all(&args.output)
+            .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", args.output.display(), e))?;
+
+        // Songs from different folders can share a name
+        let mut used_names = HashSet::new();
+        let mut failed = 0;
+        for path in &files {
+            let result = song_options(path).and_then(|options| {
+                process_song(path, options, args, &args.output, &mut used_names)
+            });
+            if !report(path, result) {
+                failed += 1;
+            }
+        }
+
+        if failed > 0 {
+            eprintln!("{} of {} songs could not be processed", failed, files.len());
+        }
+        Ok(failed == 0)
+    }
+
+    /// Process the song read from the standard input, writing the MIDI file created to the
+    /// standard output, or the archive if there are several files
+    fn pipe(base: &ProcessOptions, args: &Args, config: &Config) -> anyhow::Result<()> {
+        let mut stdout = io::stdout();
+        if stdout.is_terminal() {
+            anyhow::bail!(
+                "The files would be written to the terminal, redirect the output instead"
+            );
+        }
+        let mut data = Vec::new();
+        io::stdin().read_to_end(&mut data)?;
+
+        let options = ProcessOptions {
+            keep_files: true,
+            ..options(base, args, config, Some(STDIN_FILE_NAME))?
+        };
+        let outputs = split(STDIN_FILE_NAME, &data, options, args)?;
+        match outputs.files.as_slice() {
+            [file] if !args.zip && inputs::is_midi_file(Path::new(&file.name)) => {
+                stdout.write_all(&file.data)?
+            }
+            _ => stdout.write_all(&outputs.archive)?,
+        }
+        Ok(stdout.flush()?)
+    }
+
+    /// Number of tracks of a MIDI file
+    fn track_count(data: &[u8]) -> anyhow::Result<usize> {
+        let data = if riff::is_riff_midi(data) {
+            riff::unwrap(data)?
+        } else {
+            data
+        };
+        Ok(midly::parse(data)?.1.count())
+    }
+
+    /// Create the files for a song, leaving out all tracks but the one asked for and printing
+    /// what had to be left out or fixed
+    fn split(
+        name: &str,
+        data: &[u8],
+        mut options: ProcessOptions,
+        args: &Args,
+    ) -> anyhow::Result<Outputs> {
+        if let Some((_, track)) = args.only_track {
+            let count = track_count(data)?;
+            if track >= count {
+                anyhow::bail!(
+                    "There is no track {}, the {} tracks are counted from 0",
+                    track,
+                    count
+                );
+            }
+            options.excluded_tracks.insert(
+                name.to_string(),
+                (0..count).filter(|&i| i != track).collect(),
+            );
+        }
+
+        let outputs = midi_splitter_core::process(name, data, &options)?;
+        for skipped in &outputs.skipped {
+            eprintln!("Warning: {}", skipped);
+        }
+        for repair in &outputs.repairs {
+            eprintln!("Repaired: {}", repair);
+        }
+        Ok(outputs)
+    }
+
+    /// Print the outcome of processing a song, returning whether it succeeded
+    fn report(path: &Path, result: anyhow::Result<usize>) -> bool {
+        match result {
+            Ok(count) => {
+                println!("{}: {} files", path.display(), count);
+                true
+            }
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                if let Some(SplitError::TooLarge { .. }) = e.downcast_ref() {
+                    eprintln!("Use --set limits=null to process it anyway");
+                }
+                false
+            }
+        }
+    }
+
+    /// Write the files created for a song into the output folder, returning how many were
+    /// written
+    fn process_song(
+        path: &Path,
+        options: ProcessOptions,
+        args: &Args,
+        output: &Path,
+        used_names: &mut HashSet<String>,
+    ) -> anyhow::Result<usize> {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow::anyhow!("The file name is not valid UTF-8"))?;
+        let data = fs::read(path)?;
+        let archive_format = options.archive_format;
+        let outputs = split(name, &data, options, args)?;
+
+        let song_name = name
+            .rsplit_once('.')
+            .map_or(name, |(song_name, _)| song_name);
+        let song_name = deduplicate(&sanitize_file_name(song_name), used_names);
+        if args.zip {
+            let archive_name = format!("{}.{}", song_name, archive_format.extension());
+            fs::write(output.join(archive_name), &outputs.archive)?;
+            return Ok(1);
+        }
+
+        let folder = output.join(song_name);
+        fs::create_dir_all(&folder)?;
+        for file in &outputs.files {
+            fs::write(folder.join(&file.name), &file.data)?;
+        }
+        Ok(outputs.files.len())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::process::ExitCode {
+    native::main()
+}
+
+// Files cannot be read from the command line in the browser
+#[cfg(target_arch = "wasm32")]
+fn main() {}
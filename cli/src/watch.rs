@@ -0,0 +1,59 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::inputs;
+
+/// Time between two looks at the watched folder
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What tells whether a file changed
+type Stamp = (SystemTime, u64);
+
+/// MIDI files directly in the folder with their stamps. Subfolders hold the created files.
+fn snapshot(folder: &Path) -> anyhow::Result<HashMap<PathBuf, Stamp>> {
+    let mut files = HashMap::new();
+    for path in inputs::read_folder(folder)? {
+        if !inputs::is_midi_file(&path) {
+            continue;
+        }
+        // Files removed in the meantime are no longer of interest
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        if metadata.is_file() {
+            files.insert(path, (metadata.modified()?, metadata.len()));
+        }
+    }
+    Ok(files)
+}
+
+/// Look at the folder until the program is stopped, calling `process` for every MIDI file which
+/// gets added or changed. Files are only processed once they stayed the same for a whole
+/// interval, so files still being copied are not read half way.
+pub fn watch(folder: &Path, mut process: impl FnMut(&Path)) -> anyhow::Result<()> {
+    let mut known = snapshot(folder)?;
+    let mut pending: HashMap<PathBuf, Stamp> = HashMap::new();
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = snapshot(folder)?;
+        for (path, stamp) in &current {
+            if known.get(path) == Some(stamp) {
+                continue;
+            }
+            if pending.get(path) == Some(stamp) {
+                pending.remove(path);
+                known.insert(path.clone(), *stamp);
+                process(path);
+            } else {
+                pending.insert(path.clone(), *stamp);
+            }
+        }
+        known.retain(|path, _| current.contains_key(path));
+        pending.retain(|path, _| current.contains_key(path));
+    }
+}
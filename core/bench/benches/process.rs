@@ -0,0 +1,57 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use midi_splitter_bench::song;
+use midi_splitter_core::{process, split_iter, Mode, ProcessOptions};
+use midly::Smf;
+
+/// Splitting songs with more and more tracks with the default options
+fn track_count(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process");
+    let options = ProcessOptions::new();
+    for tracks in [4, 16, 64] {
+        let data = song(tracks, 64);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(tracks), &data, |b, data| {
+            b.iter(|| process("song.mid", data, &options).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Each mode together with the extra files which take the longest to generate
+fn modes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("modes");
+    let data = song(16, 64);
+    for (name, mode) in [
+        ("emphasize", Mode::Emphasize),
+        ("solo", Mode::Solo),
+        ("minus_one", Mode::MinusOne),
+    ] {
+        let options = ProcessOptions::new()
+            .mode(mode)
+            .tempo_variants(vec![50, 75])
+            .export_musicxml(true)
+            .cue_sheet(true);
+        group.bench_function(name, |b| {
+            b.iter(|| process("song.mid", &data, &options).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Generating the files one track at a time instead of bundling them
+fn one_at_a_time(c: &mut Criterion) {
+    let data = song(16, 64);
+    let smf = Smf::parse(&data).unwrap();
+    let options = ProcessOptions::new();
+    c.bench_function("split_iter", |b| {
+        b.iter(|| {
+            split_iter("song.mid", &smf, &options)
+                .unwrap()
+                .map(|output| output.unwrap().data.len())
+                .sum::<usize>()
+        })
+    });
+}
+
+criterion_group!(benches, track_count, modes, one_at_a_time);
+criterion_main!(benches);
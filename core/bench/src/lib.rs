@@ -0,0 +1,52 @@
+//! Songs for the benchmarks of the processing, generated so no MIDI files have to be kept
+
+use midly::{Format, Header, MetaMessage, MidiMessage, Smf, Timing, TrackEvent, TrackEventKind};
+
+/// Ticks per beat of the generated songs
+const TICKS_PER_BEAT: u32 = 480;
+
+/// Song with a named track per part, each playing a note on every beat for the given number of
+/// bars of 4/4
+pub fn song(tracks: usize, bars: u32) -> Vec<u8> {
+    let names: Vec<String> = (0..tracks)
+        .map(|track| format!("Part {}", track + 1))
+        .collect();
+    let mut smf = Smf::new(Header::new(
+        Format::Parallel,
+        Timing::Metrical((TICKS_PER_BEAT as u16).into()),
+    ));
+    for (track, name) in names.iter().enumerate() {
+        let channel = ((track % 16) as u8).into();
+        let mut events = vec![TrackEvent {
+            delta: 0.into(),
+            kind: TrackEventKind::Meta(MetaMessage::TrackName(name.as_bytes())),
+        }];
+        for beat in 0..bars * 4 {
+            let key = ((48 + (track as u32 * 5 + beat) % 36) as u8).into();
+            let vel = ((60 + beat % 40) as u8).into();
+            events.push(TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Midi {
+                    channel,
+                    message: MidiMessage::NoteOn { key, vel },
+                },
+            });
+            events.push(TrackEvent {
+                delta: TICKS_PER_BEAT.into(),
+                kind: TrackEventKind::Midi {
+                    channel,
+                    message: MidiMessage::NoteOff { key, vel: 0.into() },
+                },
+            });
+        }
+        events.push(TrackEvent {
+            delta: 0.into(),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        });
+        smf.tracks.push(events);
+    }
+
+    let mut data = Vec::new();
+    smf.write_std(&mut data).expect("Failed to write the song");
+    data
+}
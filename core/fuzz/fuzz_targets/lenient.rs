@@ -0,0 +1,11 @@
+//! Broken uploads which the lenient parser and the repairs accept still split without panicking
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use midi_splitter_core::{process, ProcessOptions};
+
+fuzz_target!(|data: &[u8]| {
+    let options = ProcessOptions::new().lenient_parsing(true).repair(true);
+    let _ = process("fuzz.mid", data, &options);
+});
@@ -0,0 +1,10 @@
+//! Any upload either splits or fails with an error, it never panics
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use midi_splitter_core::{process, ProcessOptions};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = process("fuzz.mid", data, &ProcessOptions::new());
+});
@@ -0,0 +1,20 @@
+//! Every file midly parses can be split one track at a time without panicking
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use midi_splitter_core::{split_iter, ProcessOptions};
+use midly::Smf;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(smf) = Smf::parse(data) else {
+        return;
+    };
+    let options = ProcessOptions::new();
+    let Ok(outputs) = split_iter("fuzz.mid", &smf, &options) else {
+        return;
+    };
+    for output in outputs {
+        let _ = output;
+    }
+});
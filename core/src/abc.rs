@@ -0,0 +1,172 @@
+use std::{collections::HashMap, fmt::Write};
+
+use midly::{MetaMessage, Timing, Track, TrackEventKind};
+
+use crate::{
+    score::{self, Score, DIVISIONS},
+    timing::{self, absolute_ticks},
+};
+
+/// Divisions of the default note length of an eighth note
+const UNIT: u64 = DIVISIONS / 2;
+
+/// Major keys by number of sharps, starting at 7 flats
+const MAJOR_KEYS: [&str; 15] = [
+    "Cb", "Gb", "Db", "Ab", "Eb", "Bb", "F", "C", "G", "D", "A", "E", "B", "F#", "C#",
+];
+/// Minor keys by number of sharps, starting at 7 flats
+const MINOR_KEYS: [&str; 15] = [
+    "Abm", "Ebm", "Bbm", "Fm", "Cm", "Gm", "Dm", "Am", "Em", "Bm", "F#m", "C#m", "G#m", "D#m",
+    "A#m",
+];
+/// Order in which sharps are added to key signatures. Flats are added in reverse.
+const SHARP_ORDER: [char; 7] = ['F', 'C', 'G', 'D', 'A', 'E', 'B'];
+
+fn key_name(fifths: i8, minor: bool) -> &'static str {
+    let index = (fifths.clamp(-7, 7) + 7) as usize;
+    if minor {
+        MINOR_KEYS[index]
+    } else {
+        MAJOR_KEYS[index]
+    }
+}
+
+/// Alteration the key signature applies to a step
+fn key_alteration(fifths: i8, step: char) -> i8 {
+    if fifths > 0 && SHARP_ORDER[..fifths.min(7) as usize].contains(&step) {
+        1
+    } else if fifths < 0 && SHARP_ORDER[7 - (-fifths).min(7) as usize..].contains(&step) {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Length of a note in multiples of the default note length
+fn length(divisions: u64) -> String {
+    let divisor = gcd(divisions, UNIT);
+    match (divisions / divisor, UNIT / divisor) {
+        (1, 1) => String::new(),
+        (numerator, 1) => numerator.to_string(),
+        (1, denominator) => format!("/{}", denominator),
+        (numerator, denominator) => format!("{}/{}", numerator, denominator),
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Letter of a step in the given octave, with C4 written as C and C5 as c
+fn pitch_name(step: char, octave: i32) -> String {
+    if octave >= 5 {
+        let mut name = step.to_ascii_lowercase().to_string();
+        name.push_str(&"'".repeat((octave - 5) as usize));
+        name
+    } else {
+        let mut name = step.to_string();
+        name.push_str(&",".repeat((4 - octave) as usize));
+        name
+    }
+}
+
+/// Convert a track of a MIDI file into ABC notation.
+/// Returns None if the track has no notes or plays more than one note at a time.
+pub fn export(
+    tracks: &[Track],
+    index: usize,
+    timing: Timing,
+    title: &str,
+    part_name: &str,
+) -> anyhow::Result<Option<String>> {
+    let score = Score::new(tracks, index, timing)?;
+    if score.notes.is_empty() || score.segments.iter().any(|segment| segment.keys.len() > 1) {
+        return Ok(None);
+    }
+
+    let tempo = tracks
+        .iter()
+        .flat_map(|track| absolute_ticks(track))
+        .find_map(|(tick, event)| match event.kind {
+            TrackEventKind::Meta(MetaMessage::Tempo(tempo)) if tick == 0 => Some(tempo.as_int()),
+            _ => None,
+        })
+        .unwrap_or(timing::DEFAULT_TEMPO);
+
+    let (mut fifths, minor) = score
+        .measures
+        .first()
+        .and_then(|measure| measure.key_signature)
+        .unwrap_or((0, false));
+    let (numerator, denominator_pow2) = score
+        .measures
+        .first()
+        .and_then(|measure| measure.time_signature)
+        .unwrap_or((4, 2));
+
+    let mut abc = String::new();
+    writeln!(abc, "X:1")?;
+    writeln!(abc, "T:{}", title)?;
+    writeln!(abc, "T:{}", part_name)?;
+    writeln!(abc, "M:{}/{}", numerator, 1u32 << denominator_pow2)?;
+    writeln!(abc, "L:1/8")?;
+    writeln!(abc, "Q:1/4={}", (60_000_000.0 / tempo as f64).round())?;
+    writeln!(abc, "K:{}", key_name(fifths, minor))?;
+
+    let mut segments = score.segments.iter().peekable();
+    for (number, measure) in score.measures.iter().enumerate() {
+        if number > 0 {
+            if let Some((numerator, denominator_pow2)) = measure.time_signature {
+                write!(abc, "[M:{}/{}] ", numerator, 1u32 << denominator_pow2)?;
+            }
+            if let Some((key_fifths, minor)) = measure.key_signature {
+                fifths = key_fifths;
+                write!(abc, "[K:{}] ", key_name(fifths, minor))?;
+            }
+        }
+
+        // Accidentals last until the end of the bar
+        let mut alterations: HashMap<(char, i32), i8> = HashMap::new();
+        while let Some(segment) =
+            segments.next_if(|segment| segment.start < measure.start + measure.length)
+        {
+            let Some(&key) = segment.keys.first() else {
+                write!(abc, "z{} ", length(segment.length))?;
+                continue;
+            };
+
+            let (step, alter, octave) = score::spell(key, fifths < 0);
+            let current = *alterations
+                .get(&(step, octave))
+                .unwrap_or(&key_alteration(fifths, step));
+            if alter != current {
+                abc.push_str(match alter {
+                    1 => "^",
+                    -1 => "_",
+                    _ => "=",
+                });
+                alterations.insert((step, octave), alter);
+            }
+            abc.push_str(&pitch_name(step, octave));
+            abc.push_str(&length(segment.length));
+            if segment.tied_to_next {
+                abc.push('-');
+            }
+            abc.push(' ');
+        }
+
+        if number + 1 == score.measures.len() {
+            abc.push_str("|]\n");
+        } else if (number + 1) % 4 == 0 {
+            abc.push_str("|\n");
+        } else {
+            abc.push_str("| ");
+        }
+    }
+
+    Ok(Some(abc))
+}
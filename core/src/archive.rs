@@ -0,0 +1,249 @@
+use std::io::{Cursor, Write};
+
+use flate2::{write::GzEncoder, Compression};
+use midly::{Format, Track};
+use serde::{Deserialize, Serialize};
+use zip::{write::FileOptions, DateTime, ZipWriter};
+
+use crate::{output::Output, stamp, transform, verify::Verifier, File, SplitError};
+
+/// File format of the archive bundling all generated files
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "application/zip",
+            ArchiveFormat::TarGz => "application/gzip",
+        }
+    }
+}
+
+/// Archive being written, which the files are added to one after the other
+enum ArchiveWriter {
+    Zip(ZipWriter<Cursor<Vec<u8>>>),
+    TarGz(tar::Builder<GzEncoder<Vec<u8>>>),
+}
+
+impl ArchiveWriter {
+    fn new(format: ArchiveFormat) -> Self {
+        match format {
+            ArchiveFormat::Zip => ArchiveWriter::Zip(ZipWriter::new(Cursor::new(Vec::new()))),
+            ArchiveFormat::TarGz => ArchiveWriter::TarGz(tar::Builder::new(GzEncoder::new(
+                Vec::new(),
+                Compression::default(),
+            ))),
+        }
+    }
+
+    /// Add a file whose contents get written into the archive by the given function.
+    /// Zip entries are written as they are created, while tar needs to know their size first.
+    fn add(
+        &mut self,
+        name: &str,
+        write: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+    ) -> anyhow::Result<()> {
+        match self {
+            // Entries are dated to the earliest time zip files can store so identical files
+            // produce identical archives
+            ArchiveWriter::Zip(zip) => {
+                let options = FileOptions::default().last_modified_time(DateTime::default());
+                zip.start_file(name, options)?;
+                write(zip)?;
+            }
+            // Entries are dated to the Unix epoch for the same reason
+            ArchiveWriter::TarGz(tar) => {
+                let mut data = Vec::new();
+                write(&mut data)?;
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(0);
+                header.set_cksum();
+                tar.append_data(&mut header, name, data.as_slice())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            ArchiveWriter::Zip(mut zip) => zip.finish()?.into_inner(),
+            ArchiveWriter::TarGz(tar) => tar.into_inner()?.finish()?,
+        })
+    }
+}
+
+/// Write the given output as a MIDI file, merged into a single track if asked to, and read it
+/// back while it is written to make sure it holds what was written
+fn write_midi(
+    name: &str,
+    output: &Output,
+    single_track: bool,
+    out: impl Write,
+) -> Result<(), SplitError> {
+    let merged_stamp;
+    let mut merged;
+    let (header, tracks): (_, Vec<&Track>) = if single_track {
+        merged = transform::merge_tracks(&output.tracks);
+        merged_stamp = stamp::merge(&output.tracks);
+        if let Some(merged_stamp) = &merged_stamp {
+            stamp::insert(&mut merged, merged_stamp);
+        }
+        (
+            midly::Header {
+                format: Format::SingleTrack,
+                ..output.header
+            },
+            vec![&merged],
+        )
+    } else {
+        (
+            output.header,
+            output.tracks.iter().map(|track| &**track).collect(),
+        )
+    };
+
+    let mut verifier = Verifier::new(name, &tracks, out);
+    midly::write_std(
+        &header,
+        tracks.iter().map(|track| track.iter()),
+        &mut verifier,
+    )
+    .map_err(|e| failed(e.into()))?;
+    verifier.finish()
+}
+
+/// Encode the given output as a MIDI file, merged into a single track if asked to, and check
+/// that it reads back as what was written
+pub fn encode_midi(name: &str, output: &Output, single_track: bool) -> Result<Vec<u8>, SplitError> {
+    let mut data: Vec<u8> = Vec::new();
+    write_midi(name, output, single_track, &mut data)?;
+    Ok(data)
+}
+
+/// Bundles the generated files, which can also be kept so they can be downloaded on their own
+pub struct Archive {
+    files: Vec<File>,
+    /// Keep the files besides writing them into the archive
+    keep_files: bool,
+    format: ArchiveFormat,
+    /// Archive the files are written into as they are added. Files which get sorted by name
+    /// are only written once all of them are known.
+    writer: Option<ArchiveWriter>,
+    /// Write MIDI files as a single track (Format 0)
+    single_track: bool,
+    /// Folder the files added next are put into
+    folder: Option<String>,
+}
+
+impl Archive {
+    /// Sorting the entries by name makes the archive only depend on the generated files
+    pub fn new(
+        format: ArchiveFormat,
+        sort_entries: bool,
+        keep_files: bool,
+        single_track: bool,
+    ) -> Self {
+        Archive {
+            files: Vec::new(),
+            keep_files,
+            format,
+            writer: (!sort_entries).then(|| ArchiveWriter::new(format)),
+            single_track,
+            folder: None,
+        }
+    }
+
+    /// Put the files added from now on into the given folder
+    pub fn set_folder(&mut self, folder: Option<String>) {
+        self.folder = folder;
+    }
+
+    /// Add the given data as a file
+    pub fn add_file(&mut self, name: String, data: Vec<u8>) -> Result<(), SplitError> {
+        let name = path(&self.folder, name);
+        if let Some(writer) = &mut self.writer {
+            writer
+                .add(&name, |out| out.write_all(&data))
+                .map_err(failed)?;
+        }
+        if self.keep_files || self.writer.is_none() {
+            self.files.push(File { name, data });
+        }
+
+        Ok(())
+    }
+
+    /// Add the given output as a MIDI file, which is written straight into the archive unless
+    /// the file is kept as well
+    pub fn add_midi_file(&mut self, name: String, output: &Output) -> Result<(), SplitError> {
+        let single_track = self.single_track;
+        match &mut self.writer {
+            Some(writer) if !self.keep_files => {
+                let mut written = Ok(());
+                writer
+                    .add(&path(&self.folder, name.clone()), |out| {
+                        written = write_midi(&name, output, single_track, out);
+                        Ok(())
+                    })
+                    .map_err(failed)?;
+                written
+            }
+            _ => {
+                let data = encode_midi(&name, output, single_track)?;
+                self.add_file(name, data)
+            }
+        }
+    }
+
+    /// Finish the archive, returning it together with the files if they were kept
+    pub fn finish(self) -> Result<(Vec<u8>, Vec<File>), SplitError> {
+        let archive = match self.writer {
+            Some(writer) => writer.finish().map_err(failed)?,
+            None => {
+                let mut entries: Vec<&File> = self.files.iter().collect();
+                entries.sort_by(|a, b| a.name.cmp(&b.name));
+                let mut writer = ArchiveWriter::new(self.format);
+                for file in entries {
+                    writer
+                        .add(&file.name, |out| out.write_all(&file.data))
+                        .map_err(failed)?;
+                }
+                writer.finish().map_err(failed)?
+            }
+        };
+
+        let files = if self.keep_files {
+            self.files
+        } else {
+            Vec::new()
+        };
+        Ok((archive, files))
+    }
+}
+
+/// Name of the file in the archive, including its folder
+fn path(folder: &Option<String>, name: String) -> String {
+    match folder {
+        Some(folder) => format!("{}/{}", folder, name),
+        None => name,
+    }
+}
+
+fn failed(error: anyhow::Error) -> SplitError {
+    SplitError::ZipError {
+        detail: error.to_string(),
+    }
+}
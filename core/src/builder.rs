@@ -0,0 +1,299 @@
+//! Setters for building [ProcessOptions] from their defaults one option at a time:
+//!
+//! ```
+//! use midi_splitter_core::{Mode, ProcessOptions, VelocityMapping};
+//!
+//! let options = ProcessOptions::new()
+//!     .mode(Mode::Emphasize)
+//!     .velocity_mapping(VelocityMapping::Reduce {
+//!         amount: 40,
+//!         threshold: 0,
+//!     })
+//!     .keep_drums(true)
+//!     .tempo_variants(vec![50, 75]);
+//! ```
+
+use crate::{
+    archive::ArchiveFormat, manifest::ManifestFormat, ClickTrack, DynamicsMode, HandSplit,
+    Humanize, Limits, Mode, Normalize, ProcessOptions, VelocityMapping,
+};
+
+impl ProcessOptions {
+    /// Options with their defaults, as a start for the setters
+    pub fn new() -> Self {
+        ProcessOptions::default()
+    }
+
+    /// What each generated file contains
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Even out the velocities of each track before the dynamics are changed
+    pub fn normalize(mut self, normalize: impl Into<Option<Normalize>>) -> Self {
+        self.normalize = normalize.into();
+        self
+    }
+
+    /// How the velocities of the tracks which are not emphasised are changed
+    pub fn velocity_mapping(mut self, velocity_mapping: VelocityMapping) -> Self {
+        self.velocity_mapping = velocity_mapping;
+        self
+    }
+
+    /// Amount the note velocities of the emphasised track are raised by
+    pub fn velocity_boost(mut self, velocity_boost: u8) -> Self {
+        self.velocity_boost = velocity_boost;
+        self
+    }
+
+    /// Whether the velocities, the volume controllers or both are changed
+    pub fn dynamics_mode(mut self, dynamics_mode: DynamicsMode) -> Self {
+        self.dynamics_mode = dynamics_mode;
+        self
+    }
+
+    /// Percentage the volume controllers of the other tracks are scaled to
+    pub fn volume_scale(mut self, volume_scale: u8) -> Self {
+        self.volume_scale = volume_scale;
+        self
+    }
+
+    /// Percentage the aftertouch pressure of the other tracks is scaled to
+    pub fn aftertouch_scale(mut self, aftertouch_scale: impl Into<Option<u8>>) -> Self {
+        self.aftertouch_scale = aftertouch_scale.into();
+        self
+    }
+
+    /// Pan the emphasised track hard left and all other tracks hard right
+    pub fn practice_panning(mut self, practice_panning: bool) -> Self {
+        self.practice_panning = practice_panning;
+        self
+    }
+
+    /// Leave the velocities of drum tracks unchanged so the beat stays audible
+    pub fn keep_drums(mut self, keep_drums: bool) -> Self {
+        self.keep_drums = keep_drums;
+        self
+    }
+
+    /// General MIDI program all other tracks are switched to
+    pub fn background_program(mut self, background_program: impl Into<Option<u8>>) -> Self {
+        self.background_program = background_program.into();
+        self
+    }
+
+    /// Random variation of the velocities of the tracks which are not emphasised
+    pub fn humanize(mut self, humanize: impl Into<Option<Humanize>>) -> Self {
+        self.humanize = humanize.into();
+        self
+    }
+
+    /// Tempo percentages of the additional slowed down copies of each generated file
+    pub fn tempo_variants(mut self, tempo_variants: Vec<u8>) -> Self {
+        self.tempo_variants = tempo_variants;
+        self
+    }
+
+    /// Semitones all notes of all generated files are transposed by
+    pub fn transpose(mut self, transpose: i8) -> Self {
+        self.transpose = transpose;
+        self
+    }
+
+    /// First and last bar of the section all generated files are cut down to
+    pub fn bar_range(mut self, bar_range: impl Into<Option<(u32, u32)>>) -> Self {
+        self.bar_range = bar_range.into();
+        self
+    }
+
+    /// Split a piano track into the left and the right hand
+    pub fn hand_split(mut self, hand_split: impl Into<Option<HandSplit>>) -> Self {
+        self.hand_split = hand_split.into();
+        self
+    }
+
+    /// Index of a track whose chords get split into an upper and a lower voice
+    pub fn voice_split(mut self, voice_split: impl Into<Option<usize>>) -> Self {
+        self.voice_split = voice_split.into();
+        self
+    }
+
+    /// Split tracks using multiple channels into one track per channel
+    pub fn split_channels(mut self, split_channels: bool) -> Self {
+        self.split_channels = split_channels;
+        self
+    }
+
+    /// Record the original velocities in every generated file so they can be restored
+    pub fn stamp_original_velocities(mut self, stamp_original_velocities: bool) -> Self {
+        self.stamp_original_velocities = stamp_original_velocities;
+        self
+    }
+
+    /// Add a metronome track to every generated file
+    pub fn click_track(mut self, click_track: impl Into<Option<ClickTrack>>) -> Self {
+        self.click_track = click_track.into();
+        self
+    }
+
+    /// Remove SysEx, sequencer specific and text events from all generated files
+    pub fn strip_non_essential_events(mut self, strip_non_essential_events: bool) -> Self {
+        self.strip_non_essential_events = strip_non_essential_events;
+        self
+    }
+
+    /// Do not create files for tracks without any notes such as conductor tracks
+    pub fn skip_empty_tracks(mut self, skip_empty_tracks: bool) -> Self {
+        self.skip_empty_tracks = skip_empty_tracks;
+        self
+    }
+
+    /// Add a text file with the lyrics of the file to the zip
+    pub fn export_lyrics(mut self, export_lyrics: bool) -> Self {
+        self.export_lyrics = export_lyrics;
+        self
+    }
+
+    /// Pattern the names of the generated per-track files are built from
+    pub fn name_template(mut self, name_template: impl Into<String>) -> Self {
+        self.name_template = name_template.into();
+        self
+    }
+
+    /// Suffix of the file containing all tracks unchanged, which is left out if None
+    pub fn all_tracks_suffix(mut self, all_tracks_suffix: impl Into<Option<String>>) -> Self {
+        self.all_tracks_suffix = all_tracks_suffix.into();
+        self
+    }
+
+    /// Add the uploaded file to the zip exactly as it was uploaded
+    pub fn include_original(mut self, include_original: bool) -> Self {
+        self.include_original = include_original;
+        self
+    }
+
+    /// Add a file describing all files in the zip
+    pub fn manifest(mut self, manifest: impl Into<Option<ManifestFormat>>) -> Self {
+        self.manifest = manifest.into();
+        self
+    }
+
+    /// File format of the archive bundling all generated files
+    pub fn archive_format(mut self, archive_format: ArchiveFormat) -> Self {
+        self.archive_format = archive_format;
+        self
+    }
+
+    /// Add the part of each track as MusicXML
+    pub fn export_musicxml(mut self, export_musicxml: bool) -> Self {
+        self.export_musicxml = export_musicxml;
+        self
+    }
+
+    /// Add a WAV recording of each generated file using the built in instruments
+    pub fn render_audio(mut self, render_audio: bool) -> Self {
+        self.render_audio = render_audio;
+        self
+    }
+
+    /// Add a CSV file listing the notes of each track
+    pub fn export_note_csv(mut self, export_note_csv: bool) -> Self {
+        self.export_note_csv = export_note_csv;
+        self
+    }
+
+    /// Add the part of each track which plays a single line as ABC notation
+    pub fn export_abc(mut self, export_abc: bool) -> Self {
+        self.export_abc = export_abc;
+        self
+    }
+
+    /// Sort the entries of the archive so the same upload always results in the same archive
+    pub fn reproducible_archive(mut self, reproducible_archive: bool) -> Self {
+        self.reproducible_archive = reproducible_archive;
+        self
+    }
+
+    /// Merge all tracks of each generated MIDI file into one (Format 0)
+    pub fn single_track_output(mut self, single_track_output: bool) -> Self {
+        self.single_track_output = single_track_output;
+        self
+    }
+
+    /// Start the names of the files of each track with its position so they sort in track order
+    pub fn number_files(mut self, number_files: bool) -> Self {
+        self.number_files = number_files;
+        self
+    }
+
+    /// Add a printable page listing the tracks and the files they are emphasised in
+    pub fn cue_sheet(mut self, cue_sheet: bool) -> Self {
+        self.cue_sheet = cue_sheet;
+        self
+    }
+
+    /// Record the tool version and these options in a text event of every generated file
+    pub fn stamp_provenance(mut self, stamp_provenance: bool) -> Self {
+        self.stamp_provenance = stamp_provenance;
+        self
+    }
+
+    /// Read files which break the standard in small ways as far as possible instead of failing,
+    /// leaving out the parts which cannot be read
+    pub fn lenient_parsing(mut self, lenient_parsing: bool) -> Self {
+        self.lenient_parsing = lenient_parsing;
+        self
+    }
+
+    /// Fix common mistakes of the uploads before splitting them, such as tracks without an end
+    /// or notes which never end
+    pub fn repair(mut self, repair: bool) -> Self {
+        self.repair = repair;
+        self
+    }
+
+    /// Sizes of uploads which are not processed, or None to process uploads of any size
+    pub fn limits(mut self, limits: impl Into<Option<Limits>>) -> Self {
+        self.limits = limits.into();
+        self
+    }
+
+    /// Give a track of an upload, counting from 0, another name
+    pub fn track_name(
+        mut self,
+        file_name: impl Into<String>,
+        track: usize,
+        name: impl Into<String>,
+    ) -> Self {
+        self.track_names
+            .entry(file_name.into())
+            .or_default()
+            .insert(track, name.into());
+        self
+    }
+
+    /// Create no files of their own for a track of an upload, counting from 0
+    pub fn exclude_track(mut self, file_name: impl Into<String>, track: usize) -> Self {
+        self.excluded_tracks
+            .entry(file_name.into())
+            .or_default()
+            .insert(track);
+        self
+    }
+
+    /// Order the tracks of an upload by their index, which numbers the files and orders the
+    /// tracks of the file containing all of them
+    pub fn track_order(mut self, file_name: impl Into<String>, order: Vec<usize>) -> Self {
+        self.track_orders.insert(file_name.into(), order);
+        self
+    }
+
+    /// Keep each created file on its own besides the archive. Otherwise they are written
+    /// straight into the archive.
+    pub fn keep_files(mut self, keep_files: bool) -> Self {
+        self.keep_files = keep_files;
+        self
+    }
+}
@@ -0,0 +1,95 @@
+use midly::{MetaMessage, MidiMessage, Track, TrackEvent, TrackEventKind};
+
+use crate::{
+    gm,
+    timing::{self, Tick},
+};
+
+/// Percussion key played on the first beat of each bar (Hi Wood Block)
+const DOWNBEAT_KEY: u8 = 76;
+/// Percussion key played on all other beats (Low Wood Block)
+const BEAT_KEY: u8 = 77;
+
+/// Create a metronome track clicking on every beat of the file
+pub fn click_track(
+    tracks: &[Track],
+    ticks_per_beat: Tick,
+    downbeat_velocity: u8,
+    beat_velocity: u8,
+) -> Track<'static> {
+    let note_length = (ticks_per_beat / 16).max(1);
+    let note = |delta: Tick, key: u8, vel: u8| TrackEvent {
+        delta: (delta as u32).into(),
+        kind: TrackEventKind::Midi {
+            channel: gm::PERCUSSION_CHANNEL.into(),
+            message: MidiMessage::NoteOn {
+                key: key.into(),
+                vel: vel.into(),
+            },
+        },
+    };
+
+    let mut track = vec![TrackEvent {
+        delta: 0.into(),
+        kind: TrackEventKind::Meta(MetaMessage::TrackName(b"Click")),
+    }];
+
+    let beats = timing::beats(tracks, ticks_per_beat);
+    let mut previous_tick = 0;
+    for (index, &(tick, downbeat)) in beats.iter().enumerate() {
+        let (key, vel) = if downbeat {
+            (DOWNBEAT_KEY, downbeat_velocity)
+        } else {
+            (BEAT_KEY, beat_velocity)
+        };
+        // Beats of x/64 and x/128 time signatures are shorter than a click, which has to end
+        // before the next one starts
+        let length = beats
+            .get(index + 1)
+            .map_or(note_length, |&(next, _)| note_length.min(next - tick));
+        track.push(note(tick - previous_tick, key, vel));
+        track.push(note(length, key, 0));
+        previous_tick = tick + length;
+    }
+
+    track.push(TrackEvent {
+        delta: 0.into(),
+        kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+    });
+    track
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicks_stay_on_short_beats() {
+        let end = |delta: u32| TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        };
+        // 4/128 at 480 ticks per beat has beats of 15 ticks, shorter than a click of 30
+        let tracks = vec![vec![
+            TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Meta(MetaMessage::TimeSignature(4, 7, 24, 8)),
+            },
+            end(15 * 8),
+        ]];
+        let track = click_track(&tracks, 480, 100, 80);
+        let starts: Vec<Tick> = timing::absolute_ticks(&track)
+            .filter(|(_, event)| {
+                matches!(
+                    event.kind,
+                    TrackEventKind::Midi {
+                        message: MidiMessage::NoteOn { vel, .. },
+                        ..
+                    } if vel > 0
+                )
+            })
+            .map(|(tick, _)| tick)
+            .collect();
+        assert_eq!(starts, (0..8).map(|beat| beat * 15).collect::<Vec<Tick>>());
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt::Write;
+
+use crate::{musicxml::escape, Mode};
+
+/// Track listed on the cue sheet
+pub struct CueEntry {
+    pub track: usize,
+    pub name: String,
+    pub instrument: Option<&'static str>,
+    /// Missing if the file has no bars because it uses timecode timing
+    pub bars: Option<usize>,
+    pub length_seconds: f64,
+    /// Generated file the track is practised with
+    pub file: String,
+}
+
+/// Format seconds as minutes and seconds
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Write a printable HTML page listing the tracks and their files
+pub fn write_html(title: &str, mode: Mode, entries: &[CueEntry]) -> anyhow::Result<String> {
+    let file_heading = match mode {
+        Mode::Emphasize => "Emphasised in",
+        Mode::Solo => "Played alone in",
+        Mode::MinusOne => "Left out of",
+    };
+
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>")?;
+    writeln!(html, r#"<html lang="en">"#)?;
+    writeln!(html, "<head>")?;
+    writeln!(html, r#"<meta charset="utf-8">"#)?;
+    writeln!(html, "<title>{}</title>", escape(title))?;
+    writeln!(
+        html,
+        "<style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} \
+         th, td {{ border: 1px solid #888; padding: 0.25em 0.75em; text-align: left; }}</style>"
+    )?;
+    writeln!(html, "</head>")?;
+    writeln!(html, "<body>")?;
+    writeln!(html, "<h1>{}</h1>", escape(title))?;
+    writeln!(html, "<table>")?;
+    writeln!(
+        html,
+        "<tr><th>Track</th><th>Name</th><th>Instrument</th><th>Bars</th><th>Duration</th><th>{}</th></tr>",
+        file_heading
+    )?;
+    for entry in entries {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            entry.track + 1,
+            escape(&entry.name),
+            escape(entry.instrument.unwrap_or("-")),
+            entry
+                .bars
+                .map_or_else(|| "-".to_string(), |bars| bars.to_string()),
+            format_duration(entry.length_seconds),
+            escape(&entry.file),
+        )?;
+    }
+    writeln!(html, "</table>")?;
+    writeln!(html, "</body>")?;
+    writeln!(html, "</html>")?;
+
+    Ok(html)
+}
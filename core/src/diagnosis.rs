@@ -0,0 +1,155 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{riff, SplitError, MIDI_EXTENSIONS};
+
+/// Reason why an uploaded file cannot be read
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ReadProblem {
+    /// The file name does not end in one of the MIDI extensions
+    InvalidExtension,
+    /// The file does not start like a MIDI file, e.g. audio or a web page saved as .mid
+    NotMidi,
+    /// The file ends in the middle of a chunk
+    Truncated,
+    /// The header asks for a MIDI format or SMPTE frame rate which does not exist
+    UnsupportedFormat,
+    Corrupted,
+}
+
+/// Turn an error reading the given upload into a [SplitError] explaining it
+pub fn explain(file_name: &str, data: &[u8], error: impl Display) -> SplitError {
+    let file_name = file_name.to_string();
+    match diagnose(&file_name, data) {
+        ReadProblem::InvalidExtension => SplitError::InvalidExtension { file_name },
+        ReadProblem::NotMidi => SplitError::NotMidi { file_name },
+        ReadProblem::Truncated => SplitError::Truncated { file_name },
+        ReadProblem::UnsupportedFormat => SplitError::UnsupportedFormat { file_name },
+        ReadProblem::Corrupted => SplitError::ParseError {
+            file_name,
+            offset: error_offset(data),
+            detail: error.to_string(),
+        },
+    }
+}
+
+/// Find out why a file cannot be read by looking at its name and its header
+pub fn diagnose(file_name: &str, data: &[u8]) -> ReadProblem {
+    let valid_extension = file_name
+        .rsplit_once('.')
+        .is_some_and(|(_, extension)| MIDI_EXTENSIONS.contains(&extension.to_lowercase().as_str()));
+    if !valid_extension {
+        return ReadProblem::InvalidExtension;
+    }
+
+    if data.starts_with(b"RIFF") {
+        if !riff::is_riff_midi(data) {
+            return ReadProblem::NotMidi;
+        }
+        return match riff::unwrap(data) {
+            Ok(data) => diagnose_smf(data),
+            Err(_) => ReadProblem::Truncated,
+        };
+    }
+    diagnose_smf(data)
+}
+
+/// Check the header and the chunk lengths of a standard MIDI file
+fn diagnose_smf(data: &[u8]) -> ReadProblem {
+    if !data.starts_with(b"MThd") {
+        return ReadProblem::NotMidi;
+    }
+    let read_u16 = |offset: usize| {
+        Some(u16::from_be_bytes([
+            *data.get(offset)?,
+            *data.get(offset + 1)?,
+        ]))
+    };
+    let (Some(format), Some(timing)) = (read_u16(8), read_u16(12)) else {
+        return ReadProblem::Truncated;
+    };
+    if format > 2 {
+        return ReadProblem::UnsupportedFormat;
+    }
+    // SMPTE timing stores the negated frame rate in the upper byte
+    if timing & 0x8000 != 0
+        && ![24, 25, 29, 30].contains(&((timing >> 8) as u8 as i8).wrapping_neg())
+    {
+        return ReadProblem::UnsupportedFormat;
+    }
+
+    let mut chunks = data;
+    while chunks.len() >= 8 {
+        let size = u32::from_be_bytes([chunks[4], chunks[5], chunks[6], chunks[7]]) as usize;
+        // The length can be as large as usize on wasm32
+        match 8usize.checked_add(size).and_then(|end| chunks.get(end..)) {
+            Some(rest) => chunks = rest,
+            None => return ReadProblem::Truncated,
+        }
+    }
+    if chunks.is_empty() {
+        ReadProblem::Corrupted
+    } else {
+        ReadProblem::Truncated
+    }
+}
+
+/// Offset in the file of the track or event the parser fails at
+fn error_offset(data: &[u8]) -> Option<usize> {
+    let smf = if riff::is_riff_midi(data) {
+        riff::unwrap(data).ok()?
+    } else {
+        data
+    };
+    // Parts of the file are slices of it, so their position tells where they start
+    let offset = |part: &[u8]| part.as_ptr() as usize - data.as_ptr() as usize;
+
+    let Ok((_, mut tracks)) = midly::parse(smf) else {
+        // The header is at fault
+        return Some(offset(smf));
+    };
+    loop {
+        let start = tracks.unread();
+        let mut events = match tracks.next()? {
+            Ok(events) => events,
+            Err(_) => return Some(offset(start)),
+        };
+        loop {
+            let start = events.unread();
+            match events.next() {
+                Some(Ok(_)) => {}
+                Some(Err(_)) => return Some(offset(start)),
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Header of a file with one track and 480 ticks per beat
+    const HEADER: &[u8] = b"MThd\x00\x00\x00\x06\x00\x01\x00\x01\x01\xe0";
+
+    #[test]
+    fn chunk_longer_than_the_file_is_truncated() {
+        let data = [HEADER, b"MTrk\x00\x00\x01\x00\x00\xff\x2f\x00"].concat();
+        assert_eq!(diagnose("song.mid", &data), ReadProblem::Truncated);
+    }
+
+    #[test]
+    fn chunk_length_near_the_maximum_is_truncated() {
+        // 8 more would wrap around to 0 on wasm32, so the chunk would never end
+        let data = [HEADER, b"MTrk\xff\xff\xff\xf8\x00\xff\x2f\x00"].concat();
+        assert_eq!(diagnose("song.mid", &data), ReadProblem::Truncated);
+    }
+
+    #[test]
+    fn complete_chunks_are_corrupted() {
+        let data = [HEADER, b"MTrk\x00\x00\x00\x04\x00\xff\x2f\x00"].concat();
+        assert_eq!(diagnose("song.mid", &data), ReadProblem::Corrupted);
+        assert_eq!(diagnose("song.wav", &data), ReadProblem::InvalidExtension);
+    }
+}
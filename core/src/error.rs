@@ -0,0 +1,268 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::diagnosis::ReadProblem;
+
+/// Size of an upload which is limited
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Limit {
+    Bytes,
+    Tracks,
+    Events,
+    /// Length of the song in seconds
+    Duration,
+    /// Length of the song in quarter notes
+    Beats,
+}
+
+/// Reason why the processing failed. The message is meant for the user, while the variant lets
+/// programs tell the failures apart, e.g. to show guidance or to answer with a status code.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum SplitError {
+    /// The file name does not end in one of the MIDI extensions
+    InvalidExtension {
+        file_name: String,
+    },
+    /// The file does not start like a MIDI file, e.g. audio or a web page saved as .mid
+    NotMidi {
+        file_name: String,
+    },
+    /// The file ends in the middle of a chunk
+    Truncated {
+        file_name: String,
+    },
+    /// The header asks for a MIDI format or SMPTE frame rate which does not exist
+    UnsupportedFormat {
+        file_name: String,
+    },
+    /// The structure of the file is damaged, at the given byte if the parser found where
+    ParseError {
+        file_name: String,
+        offset: Option<usize>,
+        detail: String,
+    },
+    /// The upload is bigger than [crate::ProcessOptions::limits] allows, which can be lifted to
+    /// process it anyway
+    TooLarge {
+        file_name: String,
+        limit: Limit,
+        size: usize,
+        maximum: usize,
+    },
+    /// The name of a track, counting from 0, is not UTF-8 text
+    NonUtf8TrackName {
+        file_name: String,
+        track: usize,
+    },
+    /// The first bar of the range comes after the last bar
+    InvalidBarRange {
+        first: u32,
+        last: u32,
+    },
+    /// The same track is to be split into hands and into voices
+    ConflictingSplits {
+        track: usize,
+    },
+    InvalidNameTemplate {
+        detail: String,
+    },
+    /// A tempo variant is not between 1 and [crate::MAX_TEMPO_PERCENT] percent
+    InvalidTempoVariant {
+        percent: u8,
+    },
+    /// A setting which is written into the files as a MIDI data byte is above 127
+    InvalidMidiValue {
+        setting: String,
+        value: u8,
+    },
+    /// The archive or a file in it could not be written
+    ZipError {
+        detail: String,
+    },
+    /// A generated file does not read back as it was written, so it is not handed out
+    InvalidOutput {
+        output: String,
+        detail: String,
+    },
+    NoFiles,
+    /// A progress report stopped the processing
+    Cancelled,
+    /// Any other failure, naming the file it happened in if there was one
+    Other {
+        file_name: Option<String>,
+        detail: String,
+    },
+}
+
+impl SplitError {
+    /// Name of the variant as in the serialized error, which stays the same across versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            SplitError::InvalidExtension { .. } => "invalid_extension",
+            SplitError::NotMidi { .. } => "not_midi",
+            SplitError::Truncated { .. } => "truncated",
+            SplitError::UnsupportedFormat { .. } => "unsupported_format",
+            SplitError::ParseError { .. } => "parse_error",
+            SplitError::TooLarge { .. } => "too_large",
+            SplitError::NonUtf8TrackName { .. } => "non_utf8_track_name",
+            SplitError::InvalidBarRange { .. } => "invalid_bar_range",
+            SplitError::ConflictingSplits { .. } => "conflicting_splits",
+            SplitError::InvalidNameTemplate { .. } => "invalid_name_template",
+            SplitError::InvalidTempoVariant { .. } => "invalid_tempo_variant",
+            SplitError::InvalidMidiValue { .. } => "invalid_midi_value",
+            SplitError::ZipError { .. } => "zip_error",
+            SplitError::InvalidOutput { .. } => "invalid_output",
+            SplitError::NoFiles => "no_files",
+            SplitError::Cancelled => "cancelled",
+            SplitError::Other { .. } => "other",
+        }
+    }
+
+    /// Name of the uploaded file the error is about
+    pub fn file_name(&self) -> Option<&str> {
+        match self {
+            SplitError::InvalidExtension { file_name }
+            | SplitError::NotMidi { file_name }
+            | SplitError::Truncated { file_name }
+            | SplitError::UnsupportedFormat { file_name }
+            | SplitError::ParseError { file_name, .. }
+            | SplitError::TooLarge { file_name, .. }
+            | SplitError::NonUtf8TrackName { file_name, .. } => Some(file_name),
+            SplitError::Other { file_name, .. } => file_name.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Why the upload could not be read, if that was the failure
+    pub fn read_problem(&self) -> Option<ReadProblem> {
+        match self {
+            SplitError::InvalidExtension { .. } => Some(ReadProblem::InvalidExtension),
+            SplitError::NotMidi { .. } => Some(ReadProblem::NotMidi),
+            SplitError::Truncated { .. } => Some(ReadProblem::Truncated),
+            SplitError::UnsupportedFormat { .. } => Some(ReadProblem::UnsupportedFormat),
+            SplitError::ParseError { .. } => Some(ReadProblem::Corrupted),
+            _ => None,
+        }
+    }
+
+    /// Whether the options rather than the uploaded files are at fault
+    pub fn is_invalid_options(&self) -> bool {
+        matches!(
+            self,
+            SplitError::InvalidBarRange { .. }
+                | SplitError::ConflictingSplits { .. }
+                | SplitError::InvalidNameTemplate { .. }
+                | SplitError::InvalidTempoVariant { .. }
+                | SplitError::InvalidMidiValue { .. }
+        )
+    }
+}
+
+impl Display for SplitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SplitError::InvalidExtension { file_name } => write!(
+                f,
+                "{} cannot be processed, only files ending in .mid, .midi, .kar or .rmi can",
+                file_name
+            ),
+            SplitError::NotMidi { file_name } => {
+                write!(f, "{} does not contain MIDI data", file_name)
+            }
+            SplitError::Truncated { file_name } => {
+                write!(f, "{} ends too early and may be incomplete", file_name)
+            }
+            SplitError::UnsupportedFormat { file_name } => write!(
+                f,
+                "{} uses a MIDI format or frame rate which does not exist",
+                file_name
+            ),
+            SplitError::ParseError {
+                file_name,
+                offset: Some(offset),
+                detail,
+            } => write!(
+                f,
+                "{} could not be read at byte {}: {}",
+                file_name, offset, detail
+            ),
+            SplitError::ParseError {
+                file_name, detail, ..
+            } => write!(f, "{} could not be read: {}", file_name, detail),
+            SplitError::TooLarge {
+                file_name,
+                limit,
+                size,
+                maximum,
+            } => {
+                let unit = match limit {
+                    Limit::Bytes => "bytes",
+                    Limit::Tracks => "tracks",
+                    Limit::Events => "events",
+                    Limit::Duration => "seconds",
+                    Limit::Beats => "beats",
+                };
+                write!(
+                    f,
+                    "{} has {} {}, more than the limit of {}",
+                    file_name, size, unit, maximum
+                )
+            }
+            SplitError::NonUtf8TrackName { file_name, track } => write!(
+                f,
+                "The name of track {} of {} is not valid UTF-8 text",
+                track, file_name
+            ),
+            SplitError::InvalidBarRange { first, last } => write!(
+                f,
+                "The first bar of the range ({}) must not come after the last bar ({})",
+                first, last
+            ),
+            SplitError::ConflictingSplits { track } => write!(
+                f,
+                "Track {} cannot be split into hands and voices at the same time",
+                track
+            ),
+            SplitError::InvalidNameTemplate { detail } => write!(f, "{}", detail),
+            SplitError::InvalidTempoVariant { percent } => write!(
+                f,
+                "Tempo variants must be between 1 and {} percent, not {}",
+                crate::MAX_TEMPO_PERCENT,
+                percent
+            ),
+            SplitError::InvalidMidiValue { setting, value } => {
+                write!(f, "{} must be between 0 and 127, not {}", setting, value)
+            }
+            SplitError::ZipError { detail } => write!(f, "Failed to write the archive: {}", detail),
+            SplitError::InvalidOutput { output, detail } => write!(
+                f,
+                "{} does not read back as it was written: {}",
+                output, detail
+            ),
+            SplitError::NoFiles => write!(f, "No files"),
+            SplitError::Cancelled => write!(f, "The processing was cancelled"),
+            SplitError::Other {
+                file_name: Some(file_name),
+                detail,
+            } => write!(f, "Failed to process {}: {}", file_name, detail),
+            SplitError::Other { detail, .. } => write!(f, "{}", detail),
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+/// Errors of the processing are kept if they already are a [SplitError]
+impl From<anyhow::Error> for SplitError {
+    fn from(error: anyhow::Error) -> SplitError {
+        error
+            .downcast::<SplitError>()
+            .unwrap_or_else(|error| SplitError::Other {
+                file_name: None,
+                detail: error.to_string(),
+            })
+    }
+}
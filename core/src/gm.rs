@@ -0,0 +1,253 @@
+use midly::{
+    num::{u4, u7},
+    MidiMessage, TrackEvent, TrackEventKind,
+};
+
+/// Channel 10 is reserved for percussion in General MIDI
+pub const PERCUSSION_CHANNEL: u8 = 9;
+
+/// Name of the instrument a ProgramChange selects on the given channel
+pub fn instrument_name(channel: u4, program: u7) -> &'static str {
+    if channel == PERCUSSION_CHANNEL {
+        "Percussion"
+    } else {
+        INSTRUMENT_NAMES[program.as_int() as usize]
+    }
+}
+
+/// Name of the drum kit a ProgramChange selects on the percussion channel. General MIDI only
+/// has a single kit, the others are the common ones of GS and General MIDI Level 2.
+fn drum_kit_name(program: u7) -> &'static str {
+    match program.as_int() {
+        0 => "Standard Drum Kit",
+        8 => "Room Drum Kit",
+        16 => "Power Drum Kit",
+        24 => "Electronic Drum Kit",
+        25 => "Analog Drum Kit",
+        32 => "Jazz Drum Kit",
+        40 => "Brush Drum Kit",
+        48 => "Orchestra Drum Kit",
+        56 => "Sound Effects Kit",
+        _ => "Percussion",
+    }
+}
+
+/// Symbol of the family of the instrument a ProgramChange selects on the given channel
+pub fn instrument_icon(channel: u4, program: u7) -> &'static str {
+    if channel == PERCUSSION_CHANNEL {
+        return "🥁";
+    }
+    match program.as_int() {
+        0..=7 => "🎹",
+        8..=15 => "🔔",
+        16..=23 => "🎹",
+        24..=39 => "🎸",
+        40..=51 => "🎻",
+        // Choir Aahs, Voice Oohs and Synth Voice
+        52..=54 => "🎤",
+        55 => "💥",
+        56..=63 => "🎺",
+        64..=79 => "🎷",
+        80..=103 => "🎛️",
+        104..=111 => "🪕",
+        112..=119 => "🥁",
+        _ => "🔊",
+    }
+}
+
+/// Channel and program a track is played with. Tracks without a ProgramChange use the default
+/// program on the channel of their first note.
+fn track_program(track: &[TrackEvent]) -> Option<(u4, u7)> {
+    let mut first_channel = None;
+    for event in track {
+        if let TrackEventKind::Midi { channel, message } = event.kind {
+            match message {
+                MidiMessage::ProgramChange { program } => return Some((channel, program)),
+                MidiMessage::NoteOn { .. } if first_channel.is_none() => {
+                    first_channel = Some(channel)
+                }
+                _ => {}
+            }
+        }
+    }
+    first_channel.map(|channel| (channel, u7::new(0)))
+}
+
+/// Instrument a track is played with, naming the drum kit of percussion tracks
+pub fn track_instrument(track: &[TrackEvent]) -> Option<&'static str> {
+    track_program(track).map(|(channel, program)| {
+        if channel == PERCUSSION_CHANNEL {
+            drum_kit_name(program)
+        } else {
+            instrument_name(channel, program)
+        }
+    })
+}
+
+/// Symbol of the family of the instrument a track is played with
+pub fn track_icon(track: &[TrackEvent]) -> Option<&'static str> {
+    track_program(track).map(|(channel, program)| instrument_icon(channel, program))
+}
+
+/// Whether all notes of the track are played on the percussion channel
+pub fn is_percussion_track(track: &[TrackEvent]) -> bool {
+    let mut channels = track
+        .iter()
+        .filter_map(|event| match event.kind {
+            TrackEventKind::Midi {
+                channel,
+                message: MidiMessage::NoteOn { .. },
+            } => Some(channel),
+            _ => None,
+        })
+        .peekable();
+    channels.peek().is_some() && channels.all(|channel| channel == PERCUSSION_CHANNEL)
+}
+
+/// Names of the General MIDI instruments indexed by program number
+pub const INSTRUMENT_NAMES: [&str; 128] = [
+    // Piano
+    "Acoustic Grand Piano",
+    "Bright Acoustic Piano",
+    "Electric Grand Piano",
+    "Honky-tonk Piano",
+    "Electric Piano 1",
+    "Electric Piano 2",
+    "Harpsichord",
+    "Clavi",
+    // Chromatic Percussion
+    "Celesta",
+    "Glockenspiel",
+    "Music Box",
+    "Vibraphone",
+    "Marimba",
+    "Xylophone",
+    "Tubular Bells",
+    "Dulcimer",
+    // Organ
+    "Drawbar Organ",
+    "Percussive Organ",
+    "Rock Organ",
+    "Church Organ",
+    "Reed Organ",
+    "Accordion",
+    "Harmonica",
+    "Tango Accordion",
+    // Guitar
+    "Acoustic Guitar (nylon)",
+    "Acoustic Guitar (steel)",
+    "Electric Guitar (jazz)",
+    "Electric Guitar (clean)",
+    "Electric Guitar (muted)",
+    "Overdriven Guitar",
+    "Distortion Guitar",
+    "Guitar Harmonics",
+    // Bass
+    "Acoustic Bass",
+    "Electric Bass (finger)",
+    "Electric Bass (pick)",
+    "Fretless Bass",
+    "Slap Bass 1",
+    "Slap Bass 2",
+    "Synth Bass 1",
+    "Synth Bass 2",
+    // Strings
+    "Violin",
+    "Viola",
+    "Cello",
+    "Contrabass",
+    "Tremolo Strings",
+    "Pizzicato Strings",
+    "Orchestral Harp",
+    "Timpani",
+    // Ensemble
+    "String Ensemble 1",
+    "String Ensemble 2",
+    "Synth Strings 1",
+    "Synth Strings 2",
+    "Choir Aahs",
+    "Voice Oohs",
+    "Synth Voice",
+    "Orchestra Hit",
+    // Brass
+    "Trumpet",
+    "Trombone",
+    "Tuba",
+    "Muted Trumpet",
+    "French Horn",
+    "Brass Section",
+    "Synth Brass 1",
+    "Synth Brass 2",
+    // Reed
+    "Soprano Sax",
+    "Alto Sax",
+    "Tenor Sax",
+    "Baritone Sax",
+    "Oboe",
+    "English Horn",
+    "Bassoon",
+    "Clarinet",
+    // Pipe
+    "Piccolo",
+    "Flute",
+    "Recorder",
+    "Pan Flute",
+    "Blown Bottle",
+    "Shakuhachi",
+    "Whistle",
+    "Ocarina",
+    // Synth Lead
+    "Lead 1 (square)",
+    "Lead 2 (sawtooth)",
+    "Lead 3 (calliope)",
+    "Lead 4 (chiff)",
+    "Lead 5 (charang)",
+    "Lead 6 (voice)",
+    "Lead 7 (fifths)",
+    "Lead 8 (bass + lead)",
+    // Synth Pad
+    "Pad 1 (new age)",
+    "Pad 2 (warm)",
+    "Pad 3 (polysynth)",
+    "Pad 4 (choir)",
+    "Pad 5 (bowed)",
+    "Pad 6 (metallic)",
+    "Pad 7 (halo)",
+    "Pad 8 (sweep)",
+    // Synth Effects
+    "FX 1 (rain)",
+    "FX 2 (soundtrack)",
+    "FX 3 (crystal)",
+    "FX 4 (atmosphere)",
+    "FX 5 (brightness)",
+    "FX 6 (goblins)",
+    "FX 7 (echoes)",
+    "FX 8 (sci-fi)",
+    // Ethnic
+    "Sitar",
+    "Banjo",
+    "Shamisen",
+    "Koto",
+    "Kalimba",
+    "Bag pipe",
+    "Fiddle",
+    "Shanai",
+    // Percussive
+    "Tinkle Bell",
+    "Agogo",
+    "Steel Drums",
+    "Woodblock",
+    "Taiko Drum",
+    "Melodic Tom",
+    "Synth Drum",
+    "Reverse Cymbal",
+    // Sound Effects
+    "Guitar Fret Noise",
+    "Breath Noise",
+    "Seashore",
+    "Bird Tweet",
+    "Telephone Ring",
+    "Helicopter",
+    "Applause",
+    "Gunshot",
+];
@@ -0,0 +1,102 @@
+use midly::{MetaMessage, Track, TrackEventKind};
+
+use crate::{gm, notes, timing};
+
+/// Krumhansl-Kessler profile of how strongly each pitch class above the tonic belongs to a
+/// major key
+const MAJOR_PROFILE: [f64; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+
+/// Krumhansl-Kessler profile of how strongly each pitch class above the tonic belongs to a
+/// minor key
+const MINOR_PROFILE: [f64; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+/// Musical key
+#[derive(Clone, Copy, PartialEq)]
+pub struct Key {
+    /// Pitch class of the tonic with C as 0
+    pub tonic: u8,
+    pub minor: bool,
+}
+
+impl Key {
+    /// Key given by the number of sharps, or flats if negative, of a KeySignature event
+    pub fn from_signature(sharps: i8, minor: bool) -> Key {
+        // Every sharp moves the major key up a fifth and minor keys are a minor third lower
+        let major_tonic = (sharps as i32 * 7).rem_euclid(12);
+        let tonic = if minor {
+            (major_tonic + 9) % 12
+        } else {
+            major_tonic
+        };
+        Key {
+            tonic: tonic as u8,
+            minor,
+        }
+    }
+}
+
+/// Key given by the first KeySignature event of the file
+pub fn signature_key(tracks: &[Track]) -> Option<Key> {
+    tracks
+        .iter()
+        .flat_map(|track| timing::absolute_ticks(track))
+        .filter_map(|(tick, event)| match event.kind {
+            TrackEventKind::Meta(MetaMessage::KeySignature(sharps, minor)) => {
+                Some((tick, Key::from_signature(sharps, minor)))
+            }
+            _ => None,
+        })
+        .min_by_key(|(tick, _)| *tick)
+        .map(|(_, key)| key)
+}
+
+/// Estimate the key from how long each pitch class sounds, comparing it with the profile of
+/// every key. Percussion is left out as its keys are drum sounds rather than pitches.
+pub fn estimate_key(tracks: &[Track]) -> Option<Key> {
+    let mut durations = [0.0; 12];
+    for track in tracks {
+        for note in notes::notes(track) {
+            if note.channel != gm::PERCUSSION_CHANNEL {
+                durations[note.key as usize % 12] += (note.end - note.start).max(1) as f64;
+            }
+        }
+    }
+    if durations.iter().all(|duration| *duration == 0.0) {
+        return None;
+    }
+
+    (0..12u8)
+        .flat_map(|tonic| [false, true].map(|minor| Key { tonic, minor }))
+        .map(|key| {
+            let profile = if key.minor {
+                &MINOR_PROFILE
+            } else {
+                &MAJOR_PROFILE
+            };
+            let rotated: Vec<f64> = (0..12)
+                .map(|pitch_class| profile[(pitch_class + 12 - key.tonic as usize) % 12])
+                .collect();
+            (key, correlation(&durations, &rotated))
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(key, _)| key)
+}
+
+/// Pearson correlation of two equally long series
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let mut covariance = 0.0;
+    let mut variance_a = 0.0;
+    let mut variance_b = 0.0;
+    for (a, b) in a.iter().zip(b) {
+        covariance += (a - mean_a) * (b - mean_b);
+        variance_a += (a - mean_a).powi(2);
+        variance_b += (b - mean_b).powi(2);
+    }
+    covariance / (variance_a * variance_b).sqrt().max(f64::EPSILON)
+}
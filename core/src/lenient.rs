@@ -0,0 +1,232 @@
+//! Parsing of MIDI files which break the standard in small ways, keeping what can be read and
+//! reporting what had to be left out
+
+use std::fmt::Display;
+
+use midly::{
+    num::u15, EventIter, Format, Fps, Header, MetaMessage, Smf, Timing, Track, TrackEvent,
+    TrackEventKind,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::SplitError;
+
+/// Why part of a file was left out
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum SkipReason {
+    /// Bytes before the header, such as those added by old Mac file transfers
+    LeadingBytes,
+    /// Bytes after the last chunk, too few to be a chunk or not starting like one
+    TrailingBytes,
+    /// Chunk which is neither the header nor a track
+    UnknownChunk,
+    /// The format the header gives does not exist, so it is taken from the number of tracks
+    InvalidFormat,
+    /// The SMPTE frame rate the header gives does not exist, so 25 frames per second are used
+    InvalidFrameRate,
+    /// Events of a track, counting from 0, which could not be read up to the end of the track
+    MalformedEvents { track: usize },
+    /// A track, counting from 0, is longer than the rest of the file
+    TruncatedTrack { track: usize },
+}
+
+/// Part of an upload the lenient parser left out
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Skipped {
+    pub file_name: String,
+    /// Byte of the upload the part starts at
+    pub offset: usize,
+    /// Number of bytes left out
+    pub length: usize,
+    pub reason: SkipReason,
+}
+
+impl Display for Skipped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (file_name, offset, length) = (&self.file_name, self.offset, self.length);
+        match self.reason {
+            SkipReason::LeadingBytes => write!(
+                f,
+                "{}: ignored {} bytes before the header at byte {}",
+                file_name, length, offset
+            ),
+            SkipReason::TrailingBytes => write!(
+                f,
+                "{}: ignored {} bytes after the last chunk at byte {}",
+                file_name, length, offset
+            ),
+            SkipReason::UnknownChunk => write!(
+                f,
+                "{}: ignored an unknown chunk of {} bytes at byte {}",
+                file_name, length, offset
+            ),
+            SkipReason::InvalidFormat => write!(
+                f,
+                "{}: ignored the invalid format at byte {}",
+                file_name, offset
+            ),
+            SkipReason::InvalidFrameRate => write!(
+                f,
+                "{}: used 25 frames per second instead of the invalid frame rate at byte {}",
+                file_name, offset
+            ),
+            SkipReason::MalformedEvents { track } => write!(
+                f,
+                "{}: ignored {} bytes of malformed events of track {} at byte {}",
+                file_name, length, track, offset
+            ),
+            SkipReason::TruncatedTrack { track } => write!(
+                f,
+                "{}: {} bytes of track {} are missing from the end of the file",
+                file_name, length, track
+            ),
+        }
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([
+        *data.get(offset)?,
+        *data.get(offset + 1)?,
+    ]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// Parse the MIDI file in `data`, which is `upload` itself or the part of it holding the MIDI
+/// file. Broken parts are left out rather than failing, as long as there is a header.
+pub fn parse<'a>(
+    file_name: &str,
+    upload: &[u8],
+    data: &'a [u8],
+) -> Result<(Smf<'a>, Vec<Skipped>), SplitError> {
+    let mut skipped = Vec::new();
+    // Parts of the file are slices of the upload, so their position tells where they start
+    let offset = |part: &[u8]| part.as_ptr() as usize - upload.as_ptr() as usize;
+    let mut skip = |part: &[u8], length: usize, reason: SkipReason| {
+        skipped.push(Skipped {
+            file_name: file_name.to_string(),
+            offset: offset(part),
+            length,
+            reason,
+        })
+    };
+
+    let start = data
+        .windows(4)
+        .position(|id| id == b"MThd")
+        .ok_or_else(|| SplitError::NotMidi {
+            file_name: file_name.to_string(),
+        })?;
+    if start > 0 {
+        skip(data, start, SkipReason::LeadingBytes);
+    }
+    let data = &data[start..];
+
+    let truncated = || SplitError::Truncated {
+        file_name: file_name.to_string(),
+    };
+    let header_length = read_u32(data, 4).ok_or_else(truncated)? as usize;
+    let (Some(format), Some(track_count), Some(timing)) =
+        (read_u16(data, 8), read_u16(data, 10), read_u16(data, 12))
+    else {
+        return Err(truncated());
+    };
+    let format = match format {
+        0 => Format::SingleTrack,
+        1 => Format::Parallel,
+        2 => Format::Sequential,
+        _ => {
+            skip(&data[8..], 2, SkipReason::InvalidFormat);
+            if track_count == 1 {
+                Format::SingleTrack
+            } else {
+                Format::Parallel
+            }
+        }
+    };
+    // SMPTE timing stores the negated frame rate in the upper byte
+    let timing = if timing & 0x8000 == 0 {
+        Timing::Metrical(u15::from(timing))
+    } else {
+        let fps = match ((timing >> 8) as u8 as i8).wrapping_neg() {
+            24 => Fps::Fps24,
+            25 => Fps::Fps25,
+            29 => Fps::Fps29,
+            30 => Fps::Fps30,
+            _ => {
+                skip(&data[12..], 2, SkipReason::InvalidFrameRate);
+                Fps::Fps25
+            }
+        };
+        Timing::Timecode(fps, timing as u8)
+    };
+
+    let mut tracks: Vec<Track<'a>> = Vec::new();
+    // Headers shorter than the standard one still hold the fields read above
+    let mut chunks = 8usize
+        .checked_add(header_length.max(6))
+        .and_then(|start| data.get(start..))
+        .unwrap_or_default();
+    while !chunks.is_empty() {
+        let id = &chunks[..chunks.len().min(4)];
+        let length = match read_u32(chunks, 4) {
+            Some(length) if id.iter().all(u8::is_ascii_alphanumeric) => length as usize,
+            _ => {
+                skip(chunks, chunks.len(), SkipReason::TrailingBytes);
+                break;
+            }
+        };
+        let body = &chunks[8..];
+        let (body, rest) = body.split_at(length.min(body.len()));
+
+        if id == b"MTrk" {
+            let track = tracks.len();
+            if body.len() < length {
+                skip(
+                    rest,
+                    length - body.len(),
+                    SkipReason::TruncatedTrack { track },
+                );
+            }
+            let mut events = EventIter::new(body);
+            let mut events_read = Vec::new();
+            loop {
+                let unread = events.unread();
+                match events.next() {
+                    Some(Ok(event)) => events_read.push(event),
+                    // Reading stops at the first event which cannot be read
+                    _ if !unread.is_empty() => {
+                        skip(unread, unread.len(), SkipReason::MalformedEvents { track });
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+            // Tracks which were cut short still need to end properly
+            let end_of_track = TrackEventKind::Meta(MetaMessage::EndOfTrack);
+            if events_read.last().map(|event| event.kind) != Some(end_of_track) {
+                events_read.push(TrackEvent {
+                    delta: 0.into(),
+                    kind: end_of_track,
+                });
+            }
+            tracks.push(events_read);
+        } else {
+            skip(chunks, 8 + body.len(), SkipReason::UnknownChunk);
+        }
+        chunks = rest;
+    }
+
+    Ok((
+        Smf {
+            header: Header::new(format, timing),
+            tracks,
+        },
+        skipped,
+    ))
+}
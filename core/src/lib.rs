@@ -0,0 +1,1752 @@
+//! Creation of a file for each track of a MIDI file, which does not depend on the browser
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use archive::{Archive, ArchiveFormat};
+use lenient::Skipped;
+use manifest::{FileEntry, Manifest, ManifestFormat, TrackEntry};
+use midly::{Arena, MetaMessage, MidiMessage, Smf, Track, TrackEvent, TrackEventKind};
+use output::Output;
+use repair::Repair;
+use serde::{Deserialize, Serialize};
+
+pub mod abc;
+pub mod archive;
+mod builder;
+pub mod click;
+pub mod cue_sheet;
+pub mod diagnosis;
+mod error;
+pub mod gm;
+pub mod key;
+pub mod lenient;
+pub mod lyrics;
+pub mod manifest;
+pub mod musicxml;
+pub mod notes;
+pub mod output;
+mod parallel;
+pub mod preset;
+pub mod repair;
+pub mod riff;
+pub mod score;
+pub mod stamp;
+pub mod synth;
+pub mod timing;
+pub mod transform;
+pub mod verify;
+
+pub use error::{Limit, SplitError};
+
+/// Name of the tool recorded in the files it generates, which stays the same for all crates
+pub const TOOL_NAME: &str = "midi_splitter";
+
+/// Extensions of the files which can be processed
+pub const MIDI_EXTENSIONS: [&str; 4] = ["mid", "midi", "kar", "rmi"];
+
+/// Highest tempo percentage of a tempo variant
+pub const MAX_TEMPO_PERCENT: u8 = 200;
+
+#[derive(Clone)]
+pub struct File {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// Files created from the uploads
+pub struct Outputs {
+    pub archive_name: String,
+    /// Created files on their own, empty unless [ProcessOptions::keep_files] is set
+    pub files: Vec<File>,
+    pub archive: Vec<u8>,
+    /// Folder holding the files of each upload, empty if there was a single upload
+    pub folders: Vec<String>,
+    /// Parts of the uploads which were left out by [ProcessOptions::lenient_parsing]
+    pub skipped: Vec<Skipped>,
+    /// Mistakes of the uploads which were fixed by [ProcessOptions::repair]
+    pub repairs: Vec<Repair>,
+}
+
+/// What each generated file contains
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Mode {
+    /// All tracks with the dynamics of every track but one reduced
+    Emphasize,
+    /// Only a single track
+    Solo,
+    /// All tracks but one at their original dynamics
+    MinusOne,
+}
+
+impl Mode {
+    /// Suffix appended to the track name in the generated file names
+    pub fn file_name_suffix(&self) -> &'static str {
+        match self {
+            Mode::Emphasize => "",
+            Mode::Solo => "_Solo",
+            Mode::MinusOne => "_Minus_One",
+        }
+    }
+}
+
+/// How the dynamics of the tracks which are not emphasised get reduced
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DynamicsMode {
+    /// Lower the note velocities
+    Velocity,
+    /// Scale the Channel Volume and Expression controllers
+    Controllers,
+    /// Lower the note velocities and scale the controllers
+    Both,
+}
+
+/// How the note velocities of the tracks which are not emphasised are changed
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VelocityMapping {
+    /// Subtract a fixed amount from every velocity above the threshold without going below it
+    Reduce { amount: u8, threshold: u8 },
+    /// Compress the dynamic range toward a target velocity
+    Compress { target: u8, ratio: u8, makeup: i8 },
+}
+
+impl VelocityMapping {
+    /// Velocity of a sounding note after the mapping, 0 if the note gets silenced
+    pub fn map(&self, vel: u8) -> u8 {
+        match *self {
+            VelocityMapping::Reduce { amount, threshold } => {
+                transform::reduced_velocity(vel, amount, threshold)
+            }
+            VelocityMapping::Compress {
+                target,
+                ratio,
+                makeup,
+            } => transform::compressed_velocity(vel, target, ratio, makeup),
+        }
+    }
+}
+
+/// Split of a track into the left and right hand of a piano part
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct HandSplit {
+    /// Index of the track to split
+    pub track: usize,
+    /// Lowest key played by the right hand
+    pub key: u8,
+}
+
+/// Velocities of the metronome clicks added to every generated file
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ClickTrack {
+    pub downbeat_velocity: u8,
+    pub beat_velocity: u8,
+}
+
+/// Normalization of the note velocities of every track before any other changes
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Normalize {
+    /// Velocity the level of each track is scaled to
+    pub target: u8,
+    /// Use the loudest note as the level of the track instead of the average
+    pub peak: bool,
+}
+
+/// Sizes of uploads beyond which the processing stops before it takes long.
+/// Sizes missing from saved settings keep their default.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    pub max_bytes: usize,
+    pub max_tracks: usize,
+    /// Largest number of events of all tracks together
+    pub max_events: usize,
+    /// Longest song in seconds, as the rendered audio grows with it
+    pub max_seconds: usize,
+    /// Longest song in quarter notes, as the click track, the score and the cue sheet grow with it
+    pub max_beats: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_bytes: 16 * 1024 * 1024,
+            max_tracks: 256,
+            max_events: 2_000_000,
+            max_seconds: 30 * 60,
+            max_beats: 20_000,
+        }
+    }
+}
+
+impl Limits {
+    /// Check a size against its limit
+    fn check(&self, file_name: &str, limit: Limit, size: usize) -> Result<(), SplitError> {
+        let maximum = match limit {
+            Limit::Bytes => self.max_bytes,
+            Limit::Tracks => self.max_tracks,
+            Limit::Events => self.max_events,
+            Limit::Duration => self.max_seconds,
+            Limit::Beats => self.max_beats,
+        };
+        if size > maximum {
+            return Err(SplitError::TooLarge {
+                file_name: file_name.to_string(),
+                limit,
+                size,
+                maximum,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Random variation applied to the velocities of the tracks which are not emphasised
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Humanize {
+    /// Maximum amount a velocity gets changed by in either direction
+    pub range: u8,
+    /// Seed of the random numbers so the result can be reproduced
+    pub seed: u64,
+}
+
+/// Options controlling how the per-track files are generated.
+/// Options missing from saved settings keep their default.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessOptions {
+    pub mode: Mode,
+    pub normalize: Option<Normalize>,
+    pub velocity_mapping: VelocityMapping,
+    /// Amount the note velocities of the emphasised track are raised by
+    pub velocity_boost: u8,
+    pub dynamics_mode: DynamicsMode,
+    /// Percentage the volume controllers of the other tracks are scaled to
+    pub volume_scale: u8,
+    /// Percentage the aftertouch pressure of the other tracks is scaled to
+    pub aftertouch_scale: Option<u8>,
+    /// Pan the emphasised track hard left and all other tracks hard right
+    pub practice_panning: bool,
+    /// Leave the velocities of drum tracks unchanged so the beat stays audible
+    pub keep_drums: bool,
+    /// General MIDI program all other tracks are switched to
+    pub background_program: Option<u8>,
+    pub humanize: Option<Humanize>,
+    /// Tempo percentages of the additional slowed down copies of each generated file
+    pub tempo_variants: Vec<u8>,
+    /// Semitones all notes of all generated files are transposed by
+    pub transpose: i8,
+    /// First and last bar of the section all generated files are cut down to
+    pub bar_range: Option<(u32, u32)>,
+    pub hand_split: Option<HandSplit>,
+    /// Index of a track whose chords get split into an upper and a lower voice
+    pub voice_split: Option<usize>,
+    /// Split tracks using multiple channels into one track per channel
+    pub split_channels: bool,
+    /// Record the original velocities in every generated file so they can be restored
+    pub stamp_original_velocities: bool,
+    pub click_track: Option<ClickTrack>,
+    /// Remove SysEx, sequencer specific and text events from all generated files
+    pub strip_non_essential_events: bool,
+    /// Do not create files for tracks without any notes such as conductor tracks
+    pub skip_empty_tracks: bool,
+    /// Add a text file with the lyrics of the file to the zip
+    pub export_lyrics: bool,
+    /// Pattern the names of the generated per-track files are built from
+    pub name_template: String,
+    /// Suffix of the file containing all tracks unchanged, which is left out if None
+    pub all_tracks_suffix: Option<String>,
+    /// Add the uploaded file to the zip exactly as it was uploaded
+    pub include_original: bool,
+    /// Add a file describing all files in the zip
+    pub manifest: Option<ManifestFormat>,
+    pub archive_format: ArchiveFormat,
+    /// Add the part of each track as MusicXML
+    pub export_musicxml: bool,
+    /// Add a WAV recording of each generated file using the built in instruments.
+    /// There is no soundfont or MP3 support, see [synth].
+    pub render_audio: bool,
+    /// Add a CSV file listing the notes of each track
+    pub export_note_csv: bool,
+    /// Add the part of each track which plays a single line as ABC notation
+    pub export_abc: bool,
+    /// Sort the entries of the archive so the same upload always results in the same archive
+    pub reproducible_archive: bool,
+    /// Merge all tracks of each generated MIDI file into one (Format 0)
+    pub single_track_output: bool,
+    /// Start the names of the files of each track with its position so they sort in track order
+    pub number_files: bool,
+    /// Add a printable page listing the tracks and the files they are emphasised in
+    pub cue_sheet: bool,
+    /// Record the tool version and these options in a text event of every generated file
+    pub stamp_provenance: bool,
+    /// Read files which break the standard in small ways as far as possible instead of failing,
+    /// leaving out the parts which cannot be read
+    pub lenient_parsing: bool,
+    /// Fix common mistakes of the uploads before splitting them, such as tracks without an end
+    /// or notes which never end
+    pub repair: bool,
+    /// Sizes of uploads which are not processed, or None to process uploads of any size
+    pub limits: Option<Limits>,
+    /// Names given to the tracks of each upload by their index, which belong to the uploads
+    /// rather than the settings
+    #[serde(skip)]
+    pub track_names: HashMap<String, BTreeMap<usize, String>>,
+    /// Tracks of each upload by their index which do not get files of their own, which belong to
+    /// the uploads rather than the settings
+    #[serde(skip)]
+    pub excluded_tracks: HashMap<String, BTreeSet<usize>>,
+    /// Order of the tracks of each upload by their index, which numbers the files and orders the
+    /// tracks of the file containing all of them
+    #[serde(skip)]
+    pub track_orders: HashMap<String, Vec<usize>>,
+    /// Keep each created file on its own besides the archive, as the web app offers them
+    /// separately. Otherwise they are written straight into the archive.
+    #[serde(skip)]
+    pub keep_files: bool,
+}
+
+impl ProcessOptions {
+    /// Whether any option besides the mode and the velocity reduction differs from its default
+    pub fn uses_advanced_options(&self) -> bool {
+        let mut basic = ProcessOptions {
+            mode: self.mode,
+            ..ProcessOptions::default()
+        };
+        if let VelocityMapping::Reduce { amount, .. } = self.velocity_mapping {
+            basic.velocity_mapping = VelocityMapping::Reduce {
+                amount,
+                threshold: 0,
+            };
+        }
+        serde_json::to_value(self).ok() != serde_json::to_value(&basic).ok()
+    }
+
+    /// Make sure the options can be applied, as they may come from saved settings or other
+    /// programs without going through the checks of the page
+    pub fn validate(&self) -> Result<(), SplitError> {
+        if let Some(&percent) = self
+            .tempo_variants
+            .iter()
+            .find(|&&percent| !(1..=MAX_TEMPO_PERCENT).contains(&percent))
+        {
+            return Err(SplitError::InvalidTempoVariant { percent });
+        }
+
+        // MIDI data bytes only have 7 bits, and larger values would silently lose the top bit
+        let midi_values = [
+            self.click_track
+                .map(|click| ("click_track.downbeat_velocity", click.downbeat_velocity)),
+            self.click_track
+                .map(|click| ("click_track.beat_velocity", click.beat_velocity)),
+            self.background_program
+                .map(|program| ("background_program", program)),
+            self.hand_split
+                .map(|hand_split| ("hand_split.key", hand_split.key)),
+        ];
+        if let Some((setting, value)) = midi_values
+            .into_iter()
+            .flatten()
+            .find(|&(_, value)| value > 127)
+        {
+            return Err(SplitError::InvalidMidiValue {
+                setting: setting.to_string(),
+                value,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProcessOptions {
+    fn default() -> Self {
+        ProcessOptions {
+            mode: Mode::Emphasize,
+            normalize: None,
+            velocity_mapping: VelocityMapping::Reduce {
+                amount: 30,
+                threshold: 0,
+            },
+            velocity_boost: 0,
+            dynamics_mode: DynamicsMode::Velocity,
+            volume_scale: 50,
+            aftertouch_scale: None,
+            practice_panning: false,
+            keep_drums: false,
+            background_program: None,
+            humanize: None,
+            tempo_variants: Vec::new(),
+            transpose: 0,
+            bar_range: None,
+            hand_split: None,
+            voice_split: None,
+            split_channels: false,
+            stamp_original_velocities: false,
+            click_track: None,
+            strip_non_essential_events: false,
+            skip_empty_tracks: true,
+            export_lyrics: false,
+            name_template: DEFAULT_NAME_TEMPLATE.to_string(),
+            all_tracks_suffix: Some("All".to_string()),
+            include_original: false,
+            manifest: None,
+            archive_format: ArchiveFormat::Zip,
+            export_musicxml: false,
+            render_audio: false,
+            export_note_csv: false,
+            export_abc: false,
+            reproducible_archive: false,
+            single_track_output: false,
+            number_files: false,
+            cue_sheet: false,
+            stamp_provenance: true,
+            lenient_parsing: false,
+            repair: false,
+            limits: Some(Limits::default()),
+            track_names: HashMap::new(),
+            excluded_tracks: HashMap::new(),
+            track_orders: HashMap::new(),
+            keep_files: true,
+        }
+    }
+}
+
+/// Name pattern producing the original file names
+pub const DEFAULT_NAME_TEMPLATE: &str = "{song}_{track}{mode}";
+
+/// Fill in the {placeholders} of a file name template.
+/// Literal braces are written as {{ and }}.
+pub fn render_name_template(template: &str, values: &[(&str, &str)]) -> Result<String, String> {
+    let mut name = String::new();
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                name.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                name.push('}');
+            }
+            '{' => {
+                let (placeholder, rest) = chars
+                    .as_str()
+                    .split_once('}')
+                    .ok_or("The file name template has a { without a closing }".to_string())?;
+                let (_, value) = values
+                    .iter()
+                    .find(|(key, _)| *key == placeholder)
+                    .ok_or(format!("Unknown placeholder {{{}}}", placeholder))?;
+                name.push_str(value);
+                chars = rest.chars();
+            }
+            '}' => {
+                return Err("The file name template has a } without an opening {".to_string());
+            }
+            c => name.push(c),
+        }
+    }
+
+    Ok(name)
+}
+
+/// Check a file name template for errors
+pub fn validate_name_template(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("The file name template must not be empty".to_string());
+    }
+    let name = render_name_template(
+        template,
+        &[("song", "x"), ("track", "x"), ("index", "x"), ("mode", "x")],
+    )?;
+    // Without a placeholder the text alone has to make a name, e.g. not just dots
+    if sanitize_file_name(&name).is_empty() {
+        return Err("The file name template does not give a file name".to_string());
+    }
+    Ok(())
+}
+
+/// Replace characters which are not allowed in file names on common operating systems
+pub fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .filter_map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => Some('_'),
+            '\t' | '\n' | '\r' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect::<String>()
+        // Windows does not allow names to end in a dot or a space, and names starting with a dot
+        // are hidden
+        .trim_end_matches(['.', ' '])
+        .trim_start_matches(|c: char| c == '.' || c.is_whitespace())
+        .to_string()
+}
+
+/// Make the name unique among the already used names by appending a counter
+pub fn deduplicate(name: &str, used_names: &mut HashSet<String>) -> String {
+    let mut unique_name = name.to_string();
+    let mut counter = 2;
+    while !used_names.insert(unique_name.clone()) {
+        unique_name = format!("{} ({})", name, counter);
+        counter += 1;
+    }
+    unique_name
+}
+
+/// Find the name of the track, falling back to the instrument of the first ProgramChange
+pub fn find_track_name<'a>(
+    track: &[TrackEvent<'a>],
+) -> Result<Option<&'a str>, std::str::Utf8Error> {
+    let mut instrument_name: Option<&str> = None;
+
+    for event in track {
+        match event.kind {
+            TrackEventKind::Meta(MetaMessage::TrackName(name)) => {
+                return Ok(Some(std::str::from_utf8(name)?));
+            }
+            TrackEventKind::Midi {
+                channel,
+                message: MidiMessage::ProgramChange { program },
+            } if instrument_name.is_none() => {
+                instrument_name = Some(gm::instrument_name(channel, program));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(instrument_name)
+}
+
+/// Whether the track plays any notes
+pub fn has_notes(track: &[TrackEvent]) -> bool {
+    track.iter().any(|event| {
+        matches!(
+            event.kind,
+            TrackEventKind::Midi {
+                channel: _,
+                message: MidiMessage::NoteOn { key: _, vel },
+            } if vel > 0
+        )
+    })
+}
+
+/// Reduce the dynamics of all tracks except the emphasised one which gets boosted instead
+fn emphasize_track(output: &mut Output, emphasized: usize, options: &ProcessOptions) {
+    // Every file gets its own but reproducible variation
+    let mut rng = options
+        .humanize
+        .map(|humanize| transform::Rng::new(humanize.seed.wrapping_add(emphasized as u64)));
+
+    for (index, track) in output.tracks.iter_mut().enumerate() {
+        // Tracks without channel messages such as conductor tracks stay as they are
+        if !transform::used_channels(track).contains(&true) {
+            continue;
+        }
+
+        if index == emphasized {
+            if options.velocity_boost > 0 {
+                transform::boost_velocities(track.to_mut(), options.velocity_boost);
+            }
+            if options.practice_panning {
+                transform::set_pan(track.to_mut(), transform::PAN_LEFT.into());
+            }
+            continue;
+        }
+
+        let track = track.to_mut();
+        if options.practice_panning {
+            transform::set_pan(track, transform::PAN_RIGHT.into());
+        }
+
+        let keep_velocities = options.keep_drums && gm::is_percussion_track(track);
+        if options.dynamics_mode != DynamicsMode::Controllers && !keep_velocities {
+            match options.velocity_mapping {
+                VelocityMapping::Reduce { amount, threshold } => {
+                    transform::reduce_velocities(track, amount, threshold)
+                }
+                VelocityMapping::Compress {
+                    target,
+                    ratio,
+                    makeup,
+                } => transform::compress_velocities(track, target, ratio, makeup),
+            }
+            if let (Some(humanize), Some(rng)) = (options.humanize, &mut rng) {
+                transform::humanize_velocities(track, humanize.range, rng);
+            }
+        }
+        if options.dynamics_mode != DynamicsMode::Velocity {
+            transform::scale_volume_controllers(track, options.volume_scale);
+        }
+        if let Some(program) = options.background_program {
+            transform::set_program(track, program.into());
+        }
+        if let Some(aftertouch_scale) = options.aftertouch_scale {
+            transform::scale_aftertouch(track, aftertouch_scale);
+        }
+    }
+}
+
+/// Name of a track derived from a split track
+fn derived_track_name<'a>(
+    tracks: &[Track],
+    index: usize,
+    suffix: &str,
+    arena: &'a Arena,
+) -> anyhow::Result<&'a [u8]> {
+    let track = tracks
+        .get(index)
+        .ok_or(anyhow::anyhow!("There is no track {} to split", index))?;
+    let name = find_track_name(track)?
+        .map(str::to_string)
+        .unwrap_or(format!("track-{}", index));
+
+    Ok(arena.add(format!("{} {}", name, suffix).as_bytes()))
+}
+
+/// Sort the tracks into the order the user put the uploaded tracks in. Tracks split from the same
+/// track stay together in their order.
+fn reorder_tracks(tracks: &mut Vec<Track>, origins: &mut Vec<usize>, order: &[usize]) {
+    let mut ordered: Vec<_> = origins.drain(..).zip(tracks.drain(..)).collect();
+    ordered.sort_by_key(|(origin, _)| {
+        order
+            .iter()
+            .position(|other| other == origin)
+            .unwrap_or(usize::MAX)
+    });
+    (*origins, *tracks) = ordered.into_iter().unzip();
+}
+
+/// Split tracks into derived tracks before the files get generated and return the index of the
+/// uploaded track each track came from
+fn split_tracks<'a>(
+    tracks: &mut Vec<Track<'a>>,
+    arena: &'a Arena,
+    options: &ProcessOptions,
+) -> anyhow::Result<Vec<usize>> {
+    let mut origins: Vec<usize> = (0..tracks.len()).collect();
+    // The tracks a track is split into replace it
+    let split_origin = |origins: &mut Vec<usize>, index: usize, count: usize| {
+        let origin = origins[index];
+        origins.splice(index..index + 1, std::iter::repeat_n(origin, count));
+    };
+
+    let hand_split = options.hand_split.map(|hand_split| hand_split.track);
+    if hand_split.is_some() && hand_split == options.voice_split {
+        return Err(SplitError::ConflictingSplits {
+            track: options.voice_split.unwrap_or_default(),
+        }
+        .into());
+    }
+
+    // Splitting inserts a track after the split one, so split the later track first to keep the
+    // index of the other one valid
+    let split_hands = |tracks: &mut Vec<Track<'a>>,
+                       origins: &mut Vec<usize>|
+     -> anyhow::Result<()> {
+        if let Some(hand_split) = options.hand_split {
+            let names = [
+                derived_track_name(tracks, hand_split.track, "Right Hand", arena)?,
+                derived_track_name(tracks, hand_split.track, "Left Hand", arena)?,
+            ];
+            transform::split_track_by_key(tracks, hand_split.track, hand_split.key.into(), names);
+            split_origin(origins, hand_split.track, 2);
+        }
+        Ok(())
+    };
+    let split_voices =
+        |tracks: &mut Vec<Track<'a>>, origins: &mut Vec<usize>| -> anyhow::Result<()> {
+            if let Some(track) = options.voice_split {
+                let names = [
+                    derived_track_name(tracks, track, "Upper Voice", arena)?,
+                    derived_track_name(tracks, track, "Lower Voice", arena)?,
+                ];
+                transform::split_track_into_voices(tracks, track, names);
+                split_origin(origins, track, 2);
+            }
+            Ok(())
+        };
+
+    if hand_split > options.voice_split {
+        split_hands(tracks, &mut origins)?;
+        split_voices(tracks, &mut origins)?;
+    } else {
+        split_voices(tracks, &mut origins)?;
+        split_hands(tracks, &mut origins)?;
+    }
+
+    if options.split_channels {
+        for index in (0..tracks.len()).rev() {
+            let channels: Vec<u8> = transform::used_channels(&tracks[index])
+                .iter()
+                .enumerate()
+                .filter(|(_, used)| **used)
+                .map(|(channel, _)| channel as u8)
+                .collect();
+            if channels.len() < 2 {
+                continue;
+            }
+
+            let names = channels
+                .iter()
+                .map(|channel| {
+                    derived_track_name(tracks, index, &format!("Ch {}", channel + 1), arena)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            transform::split_track_by_channel(tracks, index, &channels, &names);
+            split_origin(&mut origins, index, channels.len());
+        }
+    }
+
+    Ok(origins)
+}
+
+/// Bundle the files created for all uploaded files.
+/// The files of each song go into their own folder when multiple files were uploaded.
+/// The progress is reported before every step, which can stop the processing by returning
+/// [SplitError::Cancelled].
+pub async fn process_files(
+    files: Vec<File>,
+    options: &ProcessOptions,
+    progress: impl AsyncFn(Progress) -> Result<(), SplitError>,
+) -> Result<Outputs, SplitError> {
+    options.validate()?;
+    let mut archive = Archive::new(
+        options.archive_format,
+        options.reproducible_archive,
+        options.keep_files,
+        options.single_track_output,
+    );
+    let multiple = files.len() > 1;
+    let mut used_folders = HashSet::new();
+    let mut archive_name = None;
+    let mut folders = Vec::new();
+    let mut skipped = Vec::new();
+    let mut repairs = Vec::new();
+    let total = files.len();
+
+    for (index, file) in files.into_iter().enumerate() {
+        let song_name = file
+            .name
+            .rsplit_once('.')
+            .map_or(file.name.as_str(), |(song_name, _)| song_name)
+            .to_string();
+        if multiple {
+            let folder = deduplicate(&sanitize_file_name(&song_name), &mut used_folders);
+            archive.set_folder(Some(folder.clone()));
+            folders.push(folder);
+        } else {
+            folders.push(String::new());
+        }
+
+        let file_name = file.name.clone();
+        let file_progress = async |phase| {
+            progress(Progress {
+                file: index,
+                files: total,
+                phase,
+            })
+            .await
+        };
+        process_file(
+            file,
+            options,
+            &mut archive,
+            &mut skipped,
+            &mut repairs,
+            &file_progress,
+        )
+        .await
+        .map_err(|e| split_error(&file_name, e))?;
+        archive_name.get_or_insert(song_name);
+    }
+
+    let archive_name = match archive_name {
+        Some(_) if multiple => "Songs".to_string(),
+        Some(song_name) => song_name,
+        None => return Err(SplitError::NoFiles),
+    };
+    progress(Progress {
+        file: total.saturating_sub(1),
+        files: total,
+        phase: Phase::Archiving,
+    })
+    .await?;
+    let (archive, files) = archive.finish()?;
+
+    Ok(Outputs {
+        archive_name: format!("{}.{}", archive_name, options.archive_format.extension()),
+        files,
+        archive,
+        folders,
+        skipped,
+        repairs,
+    })
+}
+
+/// Create the files for a single upload, without any progress being reported
+pub fn process(name: &str, data: &[u8], options: &ProcessOptions) -> Result<Outputs, SplitError> {
+    let files = vec![File {
+        name: name.to_string(),
+        data: data.to_vec(),
+    }];
+    process_all(files, options)
+}
+
+/// Create the files for all uploads, without any progress being reported
+pub fn process_all(files: Vec<File>, options: &ProcessOptions) -> Result<Outputs, SplitError> {
+    // Only the progress reports could wait, so the processing finishes when it is first polled
+    let mut processing = pin!(process_files(files, options, async |_| Ok(())));
+    match processing
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(outputs) => outputs,
+        Poll::Pending => unreachable!("The processing waited without reporting progress"),
+    }
+}
+
+/// Error of the processing of an uploaded file as it is reported
+fn split_error(file_name: &str, e: anyhow::Error) -> SplitError {
+    e.downcast::<SplitError>()
+        .unwrap_or_else(|e| SplitError::Other {
+            file_name: Some(file_name.to_string()),
+            detail: e.to_string(),
+        })
+}
+
+/// File generated for a track by [split_iter]
+#[derive(Clone, Debug)]
+pub struct NamedOutput {
+    /// Name of the file as it would be in the archive
+    pub name: String,
+    /// Index of the track the file was generated for, counting from 0
+    pub track: usize,
+    /// Tempo of the file in percent of the original, if it is a tempo variant
+    pub tempo_percent: Option<u8>,
+    pub data: Vec<u8>,
+}
+
+/// Generate the files of the tracks of a parsed file one track at a time, so only the files of
+/// a single track are held at once.
+/// The files are named as [process_files] names them, but the file containing all tracks, the
+/// lyrics, the cue sheet and the manifest are left out. As the file is already parsed, the
+/// lenient parsing and the repairs are up to the caller.
+pub fn split_iter<'o>(
+    file_name: &str,
+    smf: &Smf,
+    options: &'o ProcessOptions,
+) -> Result<impl Iterator<Item = Result<NamedOutput, SplitError>> + 'o, SplitError> {
+    options.validate()?;
+    let upload_name = file_name.to_string();
+    let (song_name, extension) =
+        file_name
+            .rsplit_once('.')
+            .ok_or_else(|| SplitError::InvalidExtension {
+                file_name: upload_name.clone(),
+            })?;
+    if !MIDI_EXTENSIONS.contains(&extension.to_lowercase().as_str()) {
+        return Err(SplitError::InvalidExtension {
+            file_name: upload_name,
+        });
+    }
+    let (song_name, extension) = (song_name.to_string(), extension.to_string());
+
+    // The transformed file is written out and parsed again once, as the names of the tracks
+    // live in the arena
+    let arena = Arena::new();
+    let mut smf = smf.clone();
+    name_tracks(&upload_name, &mut smf, &arena, options);
+    check_tracks(&upload_name, &smf, options)?;
+    let mut prepare = || -> anyhow::Result<_> {
+        let origins = transform_tracks(&upload_name, &extension, &mut smf, &arena, options)?;
+        let mut used_names = reserved_names(&song_name, options);
+        let planned = plan_tracks(
+            &upload_name,
+            &song_name,
+            &smf,
+            &origins,
+            options,
+            &mut used_names,
+        )?;
+        let stamps = velocity_stamps(&smf, options);
+        let provenance = options
+            .stamp_provenance
+            .then(|| stamp::provenance(options))
+            .transpose()?;
+        if smf.tracks.len() > 1 {
+            smf.header.format = midly::Format::Parallel;
+        }
+        let mut data = Vec::new();
+        midly::write_std(&smf.header, &smf.tracks, &mut data)?;
+        let smf = EncodedSmf::try_new(data, |data| Smf::parse(data))?;
+        let common = Common::new(smf.borrow_dependent(), options)?;
+        Ok((smf, common, planned, stamps, provenance))
+    };
+    let (smf, common, planned, stamps, provenance) =
+        prepare().map_err(|e| split_error(&upload_name, e))?;
+
+    Ok(SplitIter {
+        smf,
+        common,
+        options,
+        upload_name,
+        song_name,
+        extension,
+        stamps,
+        provenance,
+        planned: planned.into_iter(),
+        generated: Vec::new().into_iter(),
+    })
+}
+
+self_cell::self_cell!(
+    /// Transformed file of [split_iter], which owns the data its events point into
+    struct EncodedSmf {
+        owner: Vec<u8>,
+        #[covariant]
+        dependent: Smf,
+    }
+);
+
+/// Iterator of [split_iter], which generates the files of the next track once the files of the
+/// previous one have been taken
+struct SplitIter<'o> {
+    smf: EncodedSmf,
+    common: Common,
+    options: &'o ProcessOptions,
+    upload_name: String,
+    song_name: String,
+    extension: String,
+    stamps: Option<Vec<Vec<u8>>>,
+    provenance: Option<Vec<u8>>,
+    planned: std::vec::IntoIter<PlannedTrack>,
+    /// Files of the current track which have not been taken yet
+    generated: std::vec::IntoIter<Result<NamedOutput, SplitError>>,
+}
+
+impl SplitIter<'_> {
+    fn generate(&self, planned: &PlannedTrack) -> anyhow::Result<Vec<NamedOutput>> {
+        let stamps = self
+            .stamps
+            .as_ref()
+            .map(|stamps| stamps.iter().map(Vec::as_slice).collect());
+        let generation = Generation::new(
+            self.smf.borrow_dependent(),
+            self.options,
+            &self.song_name,
+            &self.extension,
+            stamps,
+            self.provenance.as_deref(),
+            &self.common,
+        );
+        Ok(generation
+            .generate(planned)?
+            .files
+            .into_iter()
+            .map(|(entry, data)| NamedOutput {
+                name: entry.file,
+                track: planned.index,
+                tempo_percent: entry.tempo_percent,
+                data,
+            })
+            .collect())
+    }
+}
+
+impl Iterator for SplitIter<'_> {
+    type Item = Result<NamedOutput, SplitError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(output) = self.generated.next() {
+                return Some(output);
+            }
+            let planned = self.planned.next()?;
+            let generated: Vec<_> = match self.generate(&planned) {
+                Ok(outputs) => outputs.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(split_error(&self.upload_name, e))],
+            };
+            self.generated = generated.into_iter();
+        }
+    }
+}
+
+/// Stage of the processing of an uploaded file
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum Phase {
+    Parsing,
+    Transforming,
+    /// Generating the files of a track, counting the tracks from 0
+    Generating {
+        track: usize,
+        tracks: usize,
+    },
+    Archiving,
+}
+
+/// How far the processing has got
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Progress {
+    /// Index of the uploaded file being processed
+    pub file: usize,
+    pub files: usize,
+    pub phase: Phase,
+}
+
+/// Files generated for a track, in the order they are added to the archive
+struct TrackFiles {
+    files: Vec<(FileEntry, Vec<u8>)>,
+    /// Entry of the track on the cue sheet, if there is one
+    cue_entry: Option<cue_sheet::CueEntry>,
+}
+
+/// Track which gets files of its own, with the name they are given
+struct PlannedTrack {
+    index: usize,
+    /// Name of the files without the extension
+    name: String,
+    /// Name of the track as used in the file names
+    track_name: String,
+    /// Name of the track as it is in the file
+    original_track_name: Option<String>,
+}
+
+/// Give the tracks the names chosen by the user
+fn name_tracks<'a>(
+    upload_name: &str,
+    smf: &mut Smf<'a>,
+    arena: &'a Arena,
+    options: &ProcessOptions,
+) {
+    if let Some(track_names) = options.track_names.get(upload_name) {
+        for (&index, name) in track_names {
+            if let Some(track) = smf.tracks.get_mut(index) {
+                transform::set_track_name(track, arena.add(name.as_bytes()));
+            }
+        }
+    }
+}
+
+/// Make sure the tracks stay within the limits and their names are text
+fn check_tracks(upload_name: &str, smf: &Smf, options: &ProcessOptions) -> Result<(), SplitError> {
+    if let Some(limits) = &options.limits {
+        limits.check(upload_name, Limit::Tracks, smf.tracks.len())?;
+        limits.check(
+            upload_name,
+            Limit::Events,
+            smf.tracks.iter().map(Vec::len).sum(),
+        )?;
+        // Huge delta times make a small file last for days
+        limits.check(
+            upload_name,
+            Limit::Duration,
+            midi_length(smf).ceil() as usize,
+        )?;
+        // Files with timecode timing have no beats
+        if let Ok(ticks_per_beat) = timing::ticks_per_beat(smf.header.timing) {
+            let beats = timing::end_tick(&smf.tracks) / ticks_per_beat;
+            limits.check(
+                upload_name,
+                Limit::Beats,
+                usize::try_from(beats).unwrap_or(usize::MAX),
+            )?;
+        }
+    }
+    // Names given by the user replace names which are not text, so they are checked afterwards
+    for (track, events) in smf.tracks.iter().enumerate() {
+        find_track_name(events).map_err(|_| SplitError::NonUtf8TrackName {
+            file_name: upload_name.to_string(),
+            track,
+        })?;
+    }
+    Ok(())
+}
+
+/// Change the tracks as the options ask before any file is generated, returning the index of
+/// the uploaded track each track came from
+fn transform_tracks<'a>(
+    upload_name: &str,
+    extension: &str,
+    smf: &mut Smf<'a>,
+    arena: &'a Arena,
+    options: &ProcessOptions,
+) -> anyhow::Result<Vec<usize>> {
+    let mut origins = split_tracks(&mut smf.tracks, arena, options)?;
+    if let Some(order) = options.track_orders.get(upload_name) {
+        reorder_tracks(&mut smf.tracks, &mut origins, order);
+    }
+    transform::transpose(&mut smf.tracks, options.transpose);
+    if let (Mode::Emphasize, Some(normalize)) = (options.mode, options.normalize) {
+        for track in &mut smf.tracks {
+            transform::normalize_velocities(track, normalize.target, normalize.peak);
+        }
+    }
+    if options.strip_non_essential_events {
+        transform::strip_non_essential_events(&mut smf.tracks, lyrics::is_karaoke(extension));
+    }
+
+    if let Some((first_bar, last_bar)) = options.bar_range {
+        if first_bar > last_bar {
+            return Err(SplitError::InvalidBarRange {
+                first: first_bar,
+                last: last_bar,
+            }
+            .into());
+        }
+        let ticks_per_beat = timing::ticks_per_beat(smf.header.timing)?;
+        let start = timing::bar_start(&smf.tracks, ticks_per_beat, first_bar);
+        let end = timing::bar_start(&smf.tracks, ticks_per_beat, last_bar.saturating_add(1));
+        transform::trim(&mut smf.tracks, start, end);
+    }
+    Ok(origins)
+}
+
+/// Name of the file containing all tracks without the extension, if there is one
+fn all_tracks_name(song_name: &str, options: &ProcessOptions) -> Option<String> {
+    options
+        .all_tracks_suffix
+        .as_ref()
+        .map(|suffix| sanitize_file_name(&format!("{}_{}", song_name, suffix)))
+}
+
+/// Names the files of the tracks must not take.
+/// Files sharing a name would overwrite each other in the zip, and the file containing all
+/// tracks is written last, so its name has to be reserved.
+fn reserved_names(song_name: &str, options: &ProcessOptions) -> HashSet<String> {
+    let mut names: HashSet<String> = all_tracks_name(song_name, options).into_iter().collect();
+    if options.include_original {
+        names.insert(song_name.to_string());
+    }
+    names
+}
+
+/// Choose the tracks which get files of their own and name their files, in the order of the
+/// tracks
+fn plan_tracks(
+    upload_name: &str,
+    song_name: &str,
+    smf: &Smf,
+    origins: &[usize],
+    options: &ProcessOptions,
+    used_names: &mut HashSet<String>,
+) -> anyhow::Result<Vec<PlannedTrack>> {
+    let excluded_tracks = options.excluded_tracks.get(upload_name);
+    let mut planned = Vec::new();
+    for (i, origin) in origins.iter().enumerate() {
+        // Empty tracks still end up in every file, they just don't get their own
+        if options.skip_empty_tracks && !has_notes(&smf.tracks[i]) {
+            continue;
+        }
+        // So do tracks the user left out
+        if excluded_tracks.is_some_and(|excluded| excluded.contains(origin)) {
+            continue;
+        }
+
+        let original_track_name = find_track_name(&smf.tracks[i])?.map(str::to_string);
+        let track_name = original_track_name
+            .as_deref()
+            .map(sanitize_file_name)
+            .filter(|track_name| !track_name.is_empty())
+            .unwrap_or_else(|| format!("track-{}", i));
+        let name = render_name_template(
+            &options.name_template,
+            &[
+                ("song", song_name),
+                ("track", &track_name),
+                ("index", &i.to_string()),
+                ("mode", options.mode.file_name_suffix()),
+            ],
+        )
+        .map_err(|detail| SplitError::InvalidNameTemplate { detail })?;
+        // Placeholders which are empty can leave nothing of the name, e.g. {mode} when emphasising
+        let name = Some(sanitize_file_name(&name))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("track-{}", i));
+        let name = if options.number_files {
+            let width = smf.tracks.len().to_string().len().max(2);
+            format!("{:0width$}_{}", i + 1, name, width = width)
+        } else {
+            name
+        };
+        let name = deduplicate(&name, used_names);
+        planned.push(PlannedTrack {
+            index: i,
+            name,
+            track_name,
+            original_track_name,
+        });
+    }
+    Ok(planned)
+}
+
+/// Stamps of the original velocities of every track, if they are recorded
+fn velocity_stamps(smf: &Smf, options: &ProcessOptions) -> Option<Vec<Vec<u8>>> {
+    (options.stamp_original_velocities && options.mode == Mode::Emphasize).then(|| {
+        smf.tracks
+            .iter()
+            .map(|track| stamp::create(track))
+            .collect()
+    })
+}
+
+/// Parts of the files which are the same for every track, computed once from the transformed
+/// file
+struct Common {
+    click_track: Option<Track<'static>>,
+    tempo_map: timing::TempoMap,
+    ticks_per_beat: Option<timing::Tick>,
+    time_signatures: Vec<timing::TimeSignature>,
+}
+
+impl Common {
+    fn new(smf: &Smf, options: &ProcessOptions) -> anyhow::Result<Self> {
+        let click_track = options
+            .click_track
+            .map(|click| -> anyhow::Result<Track<'static>> {
+                Ok(click::click_track(
+                    &smf.tracks,
+                    timing::ticks_per_beat(smf.header.timing)?,
+                    click.downbeat_velocity,
+                    click.beat_velocity,
+                ))
+            })
+            .transpose()?;
+
+        Ok(Common {
+            click_track,
+            tempo_map: timing::TempoMap::new(&smf.tracks, smf.header.timing),
+            ticks_per_beat: timing::ticks_per_beat(smf.header.timing).ok(),
+            time_signatures: timing::time_signatures(&smf.tracks),
+        })
+    }
+}
+
+/// What the files of every track are generated from. They only read the transformed file, so
+/// the files of several tracks can be generated at the same time.
+struct Generation<'s, 'a> {
+    smf: &'s Smf<'a>,
+    options: &'s ProcessOptions,
+    song_name: &'s str,
+    extension: &'s str,
+    stamps: Option<Vec<&'a [u8]>>,
+    provenance: Option<&'a [u8]>,
+    common: &'s Common,
+}
+
+impl<'s, 'a> Generation<'s, 'a> {
+    fn new(
+        smf: &'s Smf<'a>,
+        options: &'s ProcessOptions,
+        song_name: &'s str,
+        extension: &'s str,
+        stamps: Option<Vec<&'a [u8]>>,
+        provenance: Option<&'a [u8]>,
+        common: &'s Common,
+    ) -> Self {
+        Generation {
+            smf,
+            options,
+            song_name,
+            extension,
+            stamps,
+            provenance,
+            common,
+        }
+    }
+
+    /// Generate the files of a track
+    fn generate(&self, planned: &PlannedTrack) -> anyhow::Result<TrackFiles> {
+        let Generation { smf, options, .. } = *self;
+        let (i, name, track_name) = (planned.index, &planned.name, &planned.track_name);
+        let original_track_name = &planned.original_track_name;
+        let extension = self.extension;
+        let mut files = Vec::new();
+        let mut add = |file: String, tempo_percent, length_seconds, data| {
+            files.push((
+                FileEntry {
+                    file,
+                    track: Some(i),
+                    track_name: original_track_name.clone(),
+                    tempo_percent,
+                    length_seconds,
+                },
+                data,
+            ))
+        };
+
+        // Only the tracks which change get copied
+        let mut output = Output::new(smf);
+        match options.mode {
+            Mode::Emphasize => emphasize_track(&mut output, i, options),
+            Mode::Solo => {
+                for (index, track) in output.tracks.iter_mut().enumerate() {
+                    if index != i && transform::used_channels(track).contains(&true) {
+                        transform::remove_channel_messages(track.to_mut());
+                    }
+                }
+            }
+            Mode::MinusOne => transform::remove_channel_messages(output.tracks[i].to_mut()),
+        }
+
+        if let Some(stamps) = &self.stamps {
+            for (track, stamp) in output.tracks.iter_mut().zip(stamps) {
+                stamp::insert(track.to_mut(), stamp);
+            }
+        }
+
+        if let Some(click_track) = &self.common.click_track {
+            output.push(Cow::Borrowed(click_track));
+        }
+
+        if let Some((provenance, first_track)) = self.provenance.zip(output.tracks.first_mut()) {
+            stamp::insert_provenance(first_track.to_mut(), provenance);
+        }
+
+        for &percent in &options.tempo_variants {
+            let mut variant = output.reborrow();
+            variant.scale_tempo(percent);
+
+            let variant_name = format!("{}_Tempo_{}.{}", name, percent, extension);
+            let data = archive::encode_midi(&variant_name, &variant, options.single_track_output)?;
+            add(variant_name, Some(percent), Some(variant.length()), data);
+        }
+
+        if options.export_musicxml {
+            let part_name = original_track_name.as_deref().unwrap_or(track_name);
+            let musicxml =
+                musicxml::export(&smf.tracks, i, smf.header.timing, self.song_name, part_name)?;
+            add(
+                format!("{}.musicxml", name),
+                None,
+                None,
+                musicxml.into_bytes(),
+            );
+        }
+
+        if options.export_abc {
+            let part_name = original_track_name.as_deref().unwrap_or(track_name);
+            if let Some(abc) =
+                abc::export(&smf.tracks, i, smf.header.timing, self.song_name, part_name)?
+            {
+                add(format!("{}.abc", name), None, None, abc.into_bytes());
+            }
+        }
+
+        if options.export_note_csv {
+            let csv = notes::notes_csv(
+                &notes::notes(&smf.tracks[i]),
+                &notes::notes(&output.tracks[i]),
+                &self.common.tempo_map,
+            );
+            add(format!("{}_Notes.csv", name), None, None, csv.into_bytes());
+        }
+
+        if options.render_audio {
+            add(
+                format!("{}.wav", name),
+                None,
+                Some(output.length()),
+                synth::render_wav(&output.to_smf()),
+            );
+        }
+
+        let name = format!("{}.{}", name, extension);
+        let cue_entry = options.cue_sheet.then(|| {
+            let end = timing::track_end_tick(&smf.tracks[i]);
+            cue_sheet::CueEntry {
+                track: i,
+                name: original_track_name.clone().unwrap_or(track_name.clone()),
+                instrument: gm::track_instrument(&smf.tracks[i]),
+                bars: self.common.ticks_per_beat.map(|ticks_per_beat| {
+                    timing::bar_count(&self.common.time_signatures, ticks_per_beat, end)
+                }),
+                length_seconds: self.common.tempo_map.seconds(end),
+                file: name.clone(),
+            }
+        });
+        let data = archive::encode_midi(&name, &output, options.single_track_output)?;
+        add(name, None, Some(output.length()), data);
+
+        Ok(TrackFiles { files, cue_entry })
+    }
+}
+
+/// Create a file for each track of a given file according to the processing mode
+async fn process_file(
+    file: File,
+    options: &ProcessOptions,
+    archive: &mut Archive,
+    skipped: &mut Vec<Skipped>,
+    repairs: &mut Vec<Repair>,
+    progress: &impl AsyncFn(Phase) -> Result<(), SplitError>,
+) -> anyhow::Result<()> {
+    progress(Phase::Parsing).await?;
+    if let Some(limits) = &options.limits {
+        limits.check(&file.name, Limit::Bytes, file.data.len())?;
+    }
+    let unreadable = |e| diagnosis::explain(&file.name, &file.data, e);
+    let (file_name, extension) = file
+        .name
+        .rsplit_once('.')
+        .ok_or_else(|| unreadable(anyhow::anyhow!("No file extension")))?;
+    if !MIDI_EXTENSIONS.contains(&extension.to_lowercase().as_str()) {
+        return Err(SplitError::InvalidExtension {
+            file_name: file.name.clone(),
+        }
+        .into());
+    }
+
+    // The generated files are plain MIDI files even if the upload was wrapped in RIFF
+    let (data, extension) = if riff::is_riff_midi(&file.data) {
+        match riff::unwrap(&file.data) {
+            Ok(data) => (data, "mid"),
+            // The lenient parser finds the MIDI file inside the broken RIFF file itself
+            Err(_) if options.lenient_parsing => (file.data.as_slice(), "mid"),
+            Err(e) => return Err(unreadable(e).into()),
+        }
+    } else {
+        (file.data.as_slice(), extension)
+    };
+
+    // Holds the names of derived tracks which have to live as long as the parsed file
+    let arena = Arena::new();
+    let mut smf = if options.lenient_parsing {
+        let (smf, file_skipped) = lenient::parse(&file.name, &file.data, data)?;
+        skipped.extend(file_skipped);
+        smf
+    } else {
+        Smf::parse(data).map_err(|e| unreadable(e.into()))?
+    };
+    if options.repair {
+        repairs.extend(repair::repair(&file.name, data, &mut smf));
+    }
+    name_tracks(&file.name, &mut smf, &arena, options);
+    check_tracks(&file.name, &smf, options)?;
+
+    progress(Phase::Transforming).await?;
+    let origins = transform_tracks(&file.name, extension, &mut smf, &arena, options)?;
+
+    let stamps = velocity_stamps(&smf, options).map(|stamps| {
+        stamps
+            .into_iter()
+            .map(|stamp| &*arena.add_vec(stamp))
+            .collect()
+    });
+
+    let provenance = options
+        .stamp_provenance
+        .then(|| stamp::provenance(options))
+        .transpose()?
+        .map(|provenance| &*arena.add_vec(provenance));
+
+    let common = Common::new(&smf, options)?;
+    let generation = Generation::new(
+        &smf, options, file_name, extension, stamps, provenance, &common,
+    );
+
+    let all_tracks_name = all_tracks_name(file_name, options);
+    let mut used_names = reserved_names(file_name, options);
+    if options.include_original {
+        archive.add_file(file.name.clone(), file.data.clone())?;
+    }
+
+    let mut manifest = Manifest::new(&file.name, options);
+    let tempo_map = &common.tempo_map;
+    manifest.tracks = smf
+        .tracks
+        .iter()
+        .enumerate()
+        .map(|(index, track)| -> anyhow::Result<TrackEntry> {
+            Ok(TrackEntry {
+                index,
+                name: find_track_name(track)?.map(str::to_string),
+                length_seconds: tempo_map.seconds(timing::track_end_tick(track)),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+    if options.include_original {
+        manifest.files.push(FileEntry {
+            file: file.name.clone(),
+            track: None,
+            track_name: None,
+            tempo_percent: None,
+            length_seconds: None,
+        });
+    }
+
+    // The names are given out in the order of the tracks, before the files are generated
+    let planned = plan_tracks(
+        &file.name,
+        file_name,
+        &smf,
+        &origins,
+        options,
+        &mut used_names,
+    )?;
+
+    // As many tracks as there are threads are generated at once, so the progress is still
+    // reported and only their files are held until they are added to the archive
+    let mut cue_entries = Vec::new();
+    for tracks in planned.chunks(parallel::threads()) {
+        progress(Phase::Generating {
+            track: tracks[0].index,
+            tracks: smf.tracks.len(),
+        })
+        .await?;
+
+        for generated in parallel::map(tracks, |planned| generation.generate(planned)) {
+            let generated = generated?;
+            for (entry, data) in generated.files {
+                archive.add_file(entry.file.clone(), data)?;
+                manifest.files.push(entry);
+            }
+            cue_entries.extend(generated.cue_entry);
+        }
+    }
+
+    if options.export_lyrics {
+        let lyrics = lyrics::extract_lyrics(&smf.tracks);
+        if !lyrics.is_empty() {
+            let name = format!("{}_Lyrics.txt", file_name);
+            manifest.files.push(FileEntry {
+                file: name.clone(),
+                track: None,
+                track_name: None,
+                tempo_percent: None,
+                length_seconds: None,
+            });
+            archive.add_file(name, lyrics.into_bytes())?;
+        }
+    }
+
+    if let Some(all_tracks_name) = all_tracks_name {
+        let mut output = Output::new(&smf);
+        if let Some(click_track) = &common.click_track {
+            output.push(Cow::Borrowed(click_track));
+        }
+        if let Some((provenance, first_track)) = provenance.zip(output.tracks.first_mut()) {
+            stamp::insert_provenance(first_track.to_mut(), provenance);
+        }
+
+        let name = format!("{}.{}", all_tracks_name, extension);
+        manifest.files.push(FileEntry {
+            file: name.clone(),
+            track: None,
+            track_name: None,
+            tempo_percent: None,
+            length_seconds: Some(output.length()),
+        });
+        archive.add_midi_file(name, &output)?;
+    }
+
+    if options.cue_sheet {
+        let name = format!("{}_Cue_Sheet.html", file_name);
+        let html = cue_sheet::write_html(file_name, options.mode, &cue_entries)?;
+        manifest.files.push(FileEntry {
+            file: name.clone(),
+            track: None,
+            track_name: None,
+            tempo_percent: None,
+            length_seconds: None,
+        });
+        archive.add_file(name, html.into_bytes())?;
+    }
+
+    if let Some(format) = options.manifest {
+        let name = match format {
+            ManifestFormat::Json => format!("{}_Manifest.json", file_name),
+            ManifestFormat::Csv => format!("{}_Manifest.csv", file_name),
+        };
+        archive.add_file(name, manifest.write(format)?)?;
+    }
+
+    Ok(())
+}
+
+/// Length of a MIDI file in seconds
+pub fn midi_length(smf: &Smf) -> f64 {
+    timing::TempoMap::new(&smf.tracks, smf.header.timing).seconds(timing::end_tick(&smf.tracks))
+}
+
+/// Restore the original velocities of a previously generated file
+pub fn restore_velocities(file: File) -> anyhow::Result<File> {
+    let (file_name, extension) = file
+        .name
+        .rsplit_once('.')
+        .ok_or(anyhow::anyhow!("No file extension"))?;
+
+    let mut smf = Smf::parse(&file.data)?;
+    stamp::restore(&mut smf.tracks)?;
+
+    let mut data = Vec::new();
+    smf.write(&mut data)
+        .map_err(|e| anyhow::anyhow!("Failed to write midi file: {}", e))?;
+
+    Ok(File {
+        name: format!("{}_Restored.{}", file_name, extension),
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use midly::{Format, Header, Timing};
+
+    use super::*;
+
+    /// File with one note which ends after the given number of ticks
+    fn song(ticks_per_beat: u16, ticks: u32) -> Vec<u8> {
+        let mut smf = Smf::new(Header::new(
+            Format::Parallel,
+            Timing::Metrical(ticks_per_beat.into()),
+        ));
+        let note = |delta: u32, vel: u8| TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Midi {
+                channel: 0.into(),
+                message: MidiMessage::NoteOn {
+                    key: 60.into(),
+                    vel: vel.into(),
+                },
+            },
+        };
+        smf.tracks.push(vec![
+            note(0, 64),
+            note(ticks, 0),
+            TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+            },
+        ]);
+        let mut data = Vec::new();
+        smf.write_std(&mut data).unwrap();
+        data
+    }
+
+    fn too_large(result: Result<Outputs, SplitError>) -> Option<Limit> {
+        match result {
+            Err(SplitError::TooLarge { limit, .. }) => Some(limit),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn validate_rejects_values_above_127() {
+        assert!(ProcessOptions::new().validate().is_ok());
+        let options = ProcessOptions::new().click_track(ClickTrack {
+            downbeat_velocity: 127,
+            beat_velocity: 200,
+        });
+        assert_eq!(
+            options.validate(),
+            Err(SplitError::InvalidMidiValue {
+                setting: "click_track.beat_velocity".to_string(),
+                value: 200,
+            })
+        );
+        assert!(ProcessOptions::new()
+            .background_program(128)
+            .validate()
+            .is_err());
+        assert!(ProcessOptions::new()
+            .hand_split(HandSplit { track: 0, key: 255 })
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn scores_of_zero_ticks_per_beat() {
+        let options = ProcessOptions::new()
+            .export_musicxml(true)
+            .export_abc(true)
+            .cue_sheet(true);
+        assert!(process("song.mid", &song(0, 4), &options).is_ok());
+    }
+
+    #[test]
+    fn limits_bound_song_length() {
+        let options = ProcessOptions::new();
+        assert_eq!(
+            too_large(process("song.mid", &song(480, 480 * 16), &options)),
+            None
+        );
+        // A few bytes lasting for days
+        assert_eq!(
+            too_large(process("song.mid", &song(1, 0x0FFF_FFFF), &options)),
+            Some(Limit::Duration)
+        );
+        let options = ProcessOptions::new().limits(Limits {
+            max_seconds: usize::MAX,
+            ..Limits::default()
+        });
+        assert_eq!(
+            too_large(process("song.mid", &song(1, 100_000), &options)),
+            Some(Limit::Beats)
+        );
+    }
+
+    #[test]
+    fn bar_range_up_to_the_last_bar() {
+        let data = song(480, 480 * 16);
+        let smf = Smf::parse(&data).unwrap();
+        let files = |options: &ProcessOptions| -> Vec<Vec<u8>> {
+            split_iter("song.mid", &smf, options)
+                .unwrap()
+                .map(|output| output.unwrap().data)
+                .collect()
+        };
+        let options = ProcessOptions::new().stamp_provenance(false);
+        assert_eq!(
+            files(&options.clone().bar_range(Some((1, u32::MAX)))),
+            files(&options)
+        );
+    }
+
+    #[test]
+    fn sanitize_file_name_replaces_forbidden_characters() {
+        assert_eq!(sanitize_file_name("Bass/Tuba: \"low\""), "Bass_Tuba_ _low_");
+        assert_eq!(sanitize_file_name("Alto\tSax\u{7}"), "Alto Sax");
+    }
+
+    #[test]
+    fn sanitize_file_name_trims_dots_and_spaces() {
+        assert_eq!(sanitize_file_name(" .hidden. "), "hidden");
+        assert_eq!(sanitize_file_name(".. . "), "");
+    }
+
+    #[test]
+    fn deduplicate_appends_counter() {
+        let mut used_names = HashSet::new();
+        assert_eq!(deduplicate("Voice", &mut used_names), "Voice");
+        assert_eq!(deduplicate("Voice", &mut used_names), "Voice (2)");
+        assert_eq!(deduplicate("Voice", &mut used_names), "Voice (3)");
+        assert_eq!(deduplicate("Piano", &mut used_names), "Piano");
+    }
+
+    #[test]
+    fn render_name_template_fills_placeholders() {
+        let name = render_name_template(
+            "{{{song}}} {track}",
+            &[("song", "Ave Maria"), ("track", "Alto")],
+        );
+        assert_eq!(name.as_deref(), Ok("{Ave Maria} Alto"));
+    }
+
+    #[test]
+    fn validate_name_template_rejects_bad_templates() {
+        assert!(validate_name_template(DEFAULT_NAME_TEMPLATE).is_ok());
+        assert!(validate_name_template("Part {index}").is_ok());
+        assert!(validate_name_template(" ").is_err());
+        assert!(validate_name_template("..").is_err());
+        assert!(validate_name_template("{song").is_err());
+        assert!(validate_name_template("song}").is_err());
+        assert!(validate_name_template("{composer}").is_err());
+    }
+}
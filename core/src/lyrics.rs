@@ -0,0 +1,58 @@
+use midly::{MetaMessage, Track, TrackEventKind};
+
+use crate::timing::{absolute_ticks, Tick};
+
+/// Decode the text of a meta event. Karaoke files commonly use Latin-1 instead of UTF-8.
+pub fn decode_text(text: &[u8]) -> String {
+    match std::str::from_utf8(text) {
+        Ok(text) => text.to_string(),
+        Err(_) => text.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+/// Whether the extension belongs to a karaoke file
+pub fn is_karaoke(extension: &str) -> bool {
+    extension.eq_ignore_ascii_case("kar")
+}
+
+/// Collect the lyrics of the file as plain text.
+/// Uses the Lyric events if there are any, otherwise the Text events like karaoke files do.
+pub fn extract_lyrics(tracks: &[Track]) -> String {
+    let mut lyrics: Vec<(Tick, &[u8])> = Vec::new();
+    let mut texts: Vec<(Tick, &[u8])> = Vec::new();
+
+    for track in tracks {
+        for (tick, event) in absolute_ticks(track) {
+            match event.kind {
+                TrackEventKind::Meta(MetaMessage::Lyric(lyric)) => lyrics.push((tick, lyric)),
+                // Karaoke files put information such as the title into text events starting with @
+                TrackEventKind::Meta(MetaMessage::Text(text)) if !text.starts_with(b"@") => {
+                    texts.push((tick, text))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if lyrics.is_empty() {
+        lyrics = texts;
+    }
+    lyrics.sort_by_key(|(tick, _)| *tick);
+
+    let mut text = String::new();
+    for (_, lyric) in lyrics {
+        let lyric = decode_text(lyric).replace('\r', "\n");
+        // Karaoke files start a new paragraph with \ and a new line with /
+        if let Some(lyric) = lyric.strip_prefix('\\') {
+            text.push_str("\n\n");
+            text.push_str(lyric);
+        } else if let Some(lyric) = lyric.strip_prefix('/') {
+            text.push('\n');
+            text.push_str(lyric);
+        } else {
+            text.push_str(&lyric);
+        }
+    }
+
+    text.trim().to_string()
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{ProcessOptions, TOOL_NAME};
+
+/// File format of the manifest
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ManifestFormat {
+    Json,
+    Csv,
+}
+
+/// Track of the uploaded file
+#[derive(Serialize)]
+pub struct TrackEntry {
+    pub index: usize,
+    pub name: Option<String>,
+    pub length_seconds: f64,
+}
+
+/// File in the zip
+#[derive(Serialize)]
+pub struct FileEntry {
+    pub file: String,
+    /// Index of the track the file was generated for
+    pub track: Option<usize>,
+    pub track_name: Option<String>,
+    pub tempo_percent: Option<u8>,
+    /// Length of MIDI files
+    pub length_seconds: Option<f64>,
+}
+
+/// Machine-readable description of everything in the zip
+#[derive(Serialize)]
+pub struct Manifest<'a> {
+    pub tool: &'static str,
+    pub version: &'static str,
+    pub source: &'a str,
+    pub options: &'a ProcessOptions,
+    pub tracks: Vec<TrackEntry>,
+    pub files: Vec<FileEntry>,
+}
+
+impl<'a> Manifest<'a> {
+    pub fn new(source: &'a str, options: &'a ProcessOptions) -> Self {
+        Manifest {
+            tool: TOOL_NAME,
+            version: env!("CARGO_PKG_VERSION"),
+            source,
+            options,
+            tracks: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Serialise the manifest in the given format
+    pub fn write(&self, format: ManifestFormat) -> anyhow::Result<Vec<u8>> {
+        match format {
+            ManifestFormat::Json => Ok(serde_json::to_vec_pretty(self)?),
+            ManifestFormat::Csv => self.write_csv(),
+        }
+    }
+
+    /// One row per file. The options only fit into the JSON manifest.
+    fn write_csv(&self) -> anyhow::Result<Vec<u8>> {
+        let mode = serde_json::to_value(self.options.mode)?;
+        let mode = mode.as_str().unwrap_or_default();
+
+        let mut csv =
+            String::from("file,track,track_name,mode,tempo_percent,length_seconds,tool_version\n");
+        for entry in &self.files {
+            let row = [
+                entry.file.clone(),
+                entry
+                    .track
+                    .map(|track| track.to_string())
+                    .unwrap_or_default(),
+                entry.track_name.clone().unwrap_or_default(),
+                mode.to_string(),
+                entry
+                    .tempo_percent
+                    .map(|percent| percent.to_string())
+                    .unwrap_or_default(),
+                entry
+                    .length_seconds
+                    .map(|length| format!("{:.3}", length))
+                    .unwrap_or_default(),
+                self.version.to_string(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| escape_csv(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        Ok(csv.into_bytes())
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
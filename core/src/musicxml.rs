@@ -0,0 +1,189 @@
+use std::fmt::Write;
+
+use midly::{Timing, Track};
+
+use crate::score::{self, Score, Segment, DIVISIONS};
+
+/// Durations which can be written as a single note with their type and whether they are dotted,
+/// longest first
+const NOTE_VALUES: [(u64, &str, bool); 11] = [
+    (48, "whole", true),
+    (32, "whole", false),
+    (24, "half", true),
+    (16, "half", false),
+    (12, "quarter", true),
+    (8, "quarter", false),
+    (6, "eighth", true),
+    (4, "eighth", false),
+    (3, "16th", true),
+    (2, "16th", false),
+    (1, "32nd", false),
+];
+
+/// Escape text for use in XML and HTML
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Convert a track of a MIDI file into a MusicXML part
+pub fn export(
+    tracks: &[Track],
+    index: usize,
+    timing: Timing,
+    title: &str,
+    part_name: &str,
+) -> anyhow::Result<String> {
+    let score = Score::new(tracks, index, timing)?;
+    let flats = score.uses_flats();
+    let average_key = if score.notes.is_empty() {
+        60
+    } else {
+        score
+            .notes
+            .iter()
+            .map(|note| note.key as usize)
+            .sum::<usize>()
+            / score.notes.len()
+    };
+
+    let mut xml = String::new();
+    writeln!(
+        xml,
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#
+    )?;
+    writeln!(
+        xml,
+        r#"<!DOCTYPE score-partwise PUBLIC "-//Recordare//DTD MusicXML 4.0 Partwise//EN" "http://www.musicxml.org/dtds/partwise.dtd">"#
+    )?;
+    writeln!(xml, r#"<score-partwise version="4.0">"#)?;
+    writeln!(
+        xml,
+        "  <work><work-title>{}</work-title></work>",
+        escape(title)
+    )?;
+    writeln!(
+        xml,
+        r#"  <part-list><score-part id="P1"><part-name>{}</part-name></score-part></part-list>"#,
+        escape(part_name)
+    )?;
+    writeln!(xml, r#"  <part id="P1">"#)?;
+
+    let mut segments = score.segments.iter().peekable();
+    for (number, measure) in score.measures.iter().enumerate() {
+        writeln!(xml, r#"    <measure number="{}">"#, number + 1)?;
+
+        if number == 0 || measure.time_signature.is_some() || measure.key_signature.is_some() {
+            xml.push_str("      <attributes>");
+            if number == 0 {
+                write!(xml, "<divisions>{}</divisions>", DIVISIONS)?;
+            }
+            if let Some((fifths, minor)) = measure.key_signature {
+                let mode = if minor { "minor" } else { "major" };
+                write!(
+                    xml,
+                    "<key><fifths>{}</fifths><mode>{}</mode></key>",
+                    fifths, mode
+                )?;
+            }
+            if let Some((numerator, denominator_pow2)) = measure.time_signature {
+                write!(
+                    xml,
+                    "<time><beats>{}</beats><beat-type>{}</beat-type></time>",
+                    numerator,
+                    1u32 << denominator_pow2
+                )?;
+            }
+            if number == 0 {
+                if average_key < 60 {
+                    xml.push_str("<clef><sign>F</sign><line>4</line></clef>");
+                } else {
+                    xml.push_str("<clef><sign>G</sign><line>2</line></clef>");
+                }
+            }
+            xml.push_str("</attributes>\n");
+        }
+
+        while let Some(segment) =
+            segments.next_if(|segment| segment.start < measure.start + measure.length)
+        {
+            write_segment(&mut xml, segment, flats)?;
+        }
+
+        writeln!(xml, "    </measure>")?;
+    }
+
+    writeln!(xml, "  </part>")?;
+    writeln!(xml, "</score-partwise>")?;
+
+    Ok(xml)
+}
+
+/// Write a segment as notes of lengths which can be notated, tied together
+fn write_segment(xml: &mut String, segment: &Segment, flats: bool) -> std::fmt::Result {
+    let mut lengths = Vec::new();
+    let mut remaining = segment.length;
+    while remaining > 0 {
+        let value = NOTE_VALUES
+            .iter()
+            .find(|(length, _, _)| *length <= remaining)
+            .expect("A 32nd note fits into every remaining length");
+        lengths.push(value);
+        remaining -= value.0;
+    }
+
+    for (index, (length, note_type, dotted)) in lengths.iter().enumerate() {
+        let tie_start = segment.tied_to_next || index + 1 < lengths.len();
+        let tie_stop = segment.tied_to_previous || index > 0;
+
+        if segment.keys.is_empty() {
+            write!(xml, "      <note><rest/><duration>{}</duration>", length)?;
+            write!(xml, "<voice>1</voice><type>{}</type>", note_type)?;
+            if *dotted {
+                xml.push_str("<dot/>");
+            }
+            xml.push_str("</note>\n");
+            continue;
+        }
+
+        for (chord_index, key) in segment.keys.iter().enumerate() {
+            let (step, alter, octave) = score::spell(*key, flats);
+
+            xml.push_str("      <note>");
+            if chord_index > 0 {
+                xml.push_str("<chord/>");
+            }
+            write!(xml, "<pitch><step>{}</step>", step)?;
+            if alter != 0 {
+                write!(xml, "<alter>{}</alter>", alter)?;
+            }
+            write!(xml, "<octave>{}</octave></pitch>", octave)?;
+            write!(xml, "<duration>{}</duration>", length)?;
+            if tie_stop {
+                xml.push_str(r#"<tie type="stop"/>"#);
+            }
+            if tie_start {
+                xml.push_str(r#"<tie type="start"/>"#);
+            }
+            write!(xml, "<voice>1</voice><type>{}</type>", note_type)?;
+            if *dotted {
+                xml.push_str("<dot/>");
+            }
+            if tie_start || tie_stop {
+                xml.push_str("<notations>");
+                if tie_stop {
+                    xml.push_str(r#"<tied type="stop"/>"#);
+                }
+                if tie_start {
+                    xml.push_str(r#"<tied type="start"/>"#);
+                }
+                xml.push_str("</notations>");
+            }
+            xml.push_str("</note>\n");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+};
+
+use midly::{MidiMessage, TrackEvent, TrackEventKind};
+
+use crate::timing::{absolute_ticks, TempoMap, Tick};
+
+/// Note of a track with the ticks it starts and ends at
+#[derive(Clone, Copy)]
+pub struct Note {
+    pub start: Tick,
+    pub end: Tick,
+    pub channel: u8,
+    pub key: u8,
+    pub velocity: u8,
+}
+
+/// Pair the NoteOns of the track with their NoteOffs, sorted by start.
+/// Overlapping notes of the same key end in the order they started.
+/// Notes which never end last until the end of the track.
+pub fn notes(track: &[TrackEvent]) -> Vec<Note> {
+    let mut notes = Vec::new();
+    let mut sounding: HashMap<(u8, u8), VecDeque<(Tick, u8)>> = HashMap::new();
+    let mut end = 0;
+
+    for (tick, event) in absolute_ticks(track) {
+        end = tick;
+        let TrackEventKind::Midi { channel, message } = event.kind else {
+            continue;
+        };
+        let channel = channel.as_int();
+
+        match message {
+            MidiMessage::NoteOn { key, vel } if vel > 0 => sounding
+                .entry((channel, key.as_int()))
+                .or_default()
+                .push_back((tick, vel.as_int())),
+            MidiMessage::NoteOn { key, vel: _ } | MidiMessage::NoteOff { key, vel: _ } => {
+                if let Some((start, velocity)) = sounding
+                    .get_mut(&(channel, key.as_int()))
+                    .and_then(VecDeque::pop_front)
+                {
+                    notes.push(Note {
+                        start,
+                        end: tick,
+                        channel,
+                        key: key.as_int(),
+                        velocity,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for ((channel, key), starts) in sounding {
+        for (start, velocity) in starts {
+            notes.push(Note {
+                start,
+                end,
+                channel,
+                key,
+                velocity,
+            });
+        }
+    }
+
+    notes.sort_by_key(|note| (note.start, note.channel, note.key));
+    notes
+}
+
+/// List the notes of a track as CSV, comparing the velocities of the original track with the
+/// ones in the generated file. Velocities of notes missing from the generated file are left empty.
+pub fn notes_csv(original: &[Note], generated: &[Note], tempo_map: &TempoMap) -> String {
+    let mut csv = String::from(
+        "tick,seconds,channel,key,velocity_before,velocity_after,duration_ticks,duration_seconds\n",
+    );
+
+    let mut generated = generated.iter().peekable();
+    for note in original {
+        let position = (note.start, note.channel, note.key);
+        // Skip notes which only exist in the generated file
+        while generated
+            .next_if(|other| (other.start, other.channel, other.key) < position)
+            .is_some()
+        {}
+        let velocity_after = generated
+            .next_if(|other| (other.start, other.channel, other.key) == position)
+            .map(|other| other.velocity.to_string())
+            .unwrap_or_default();
+
+        let start = tempo_map.seconds(note.start);
+        let end = tempo_map.seconds(note.end);
+        let _ = writeln!(
+            csv,
+            "{},{:.3},{},{},{},{},{},{:.3}",
+            note.start,
+            start,
+            note.channel + 1,
+            note.key,
+            note.velocity,
+            velocity_after,
+            note.end - note.start,
+            end - start
+        );
+    }
+
+    csv
+}
@@ -0,0 +1,78 @@
+use std::borrow::Cow;
+
+use midly::{Format, Header, MetaMessage, Smf, Track, TrackEventKind};
+
+use crate::{timing, transform};
+
+/// MIDI file being generated from a parsed file. Its tracks borrow those of the parsed file
+/// until they get changed, so only the tracks which differ are copied.
+#[derive(Clone)]
+pub struct Output<'s, 'a> {
+    pub header: Header,
+    pub tracks: Vec<Cow<'s, Track<'a>>>,
+}
+
+impl<'s, 'a> Output<'s, 'a> {
+    /// Output containing the tracks of the file unchanged
+    pub fn new(smf: &'s Smf<'a>) -> Self {
+        Output {
+            header: smf.header,
+            tracks: smf.tracks.iter().map(Cow::Borrowed).collect(),
+        }
+    }
+
+    /// Output sharing all tracks of this one, e.g. to change the tempo of a copy
+    pub fn reborrow(&self) -> Output<'_, 'a> {
+        Output {
+            header: self.header,
+            tracks: self
+                .tracks
+                .iter()
+                .map(|track| Cow::Borrowed(&**track))
+                .collect(),
+        }
+    }
+
+    /// Add a track at the end, making a single track file contain parallel tracks
+    pub fn push(&mut self, track: Cow<'s, Track<'a>>) {
+        if self.header.format == Format::SingleTrack {
+            self.header.format = Format::Parallel;
+        }
+        self.tracks.push(track);
+    }
+
+    /// Scale the tempo of the whole file, changing only the tracks holding Tempo events
+    pub fn scale_tempo(&mut self, percent: u8) {
+        let has_tempo = |track: &Track| {
+            track
+                .iter()
+                .any(|event| matches!(event.kind, TrackEventKind::Meta(MetaMessage::Tempo(_))))
+        };
+        if !self.tracks.iter().any(|track| has_tempo(track)) {
+            // Files without a Tempo event get one in their first track
+            if let Some(track) = self.tracks.first_mut() {
+                transform::scale_tempo(std::slice::from_mut(track.to_mut()), percent);
+            }
+            return;
+        }
+        for track in &mut self.tracks {
+            if has_tempo(track) {
+                transform::scale_tempo(std::slice::from_mut(track.to_mut()), percent);
+            }
+        }
+    }
+
+    /// Length in seconds
+    pub fn length(&self) -> f64 {
+        timing::TempoMap::new(&self.tracks, self.header.timing)
+            .seconds(timing::end_tick(&self.tracks))
+    }
+
+    /// Copy of the whole file, for the few uses which need one
+    pub fn to_smf(&self) -> Smf<'a> {
+        Smf {
+            header: self.header,
+            tracks: self.tracks.iter().map(|track| track.to_vec()).collect(),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Work spread over the cores where threads are available. Native targets always use them,
+//! while the browser only does with the `threads` feature, which needs a build with atomics and
+//! a thread pool started by the page. Without threads the work is done in order.
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "threads"))]
+mod threaded {
+    use rayon::prelude::*;
+
+    /// Number of items which are worked on at the same time
+    pub fn threads() -> usize {
+        rayon::current_num_threads()
+    }
+
+    /// Apply `f` to every item, keeping their order
+    pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+        items.par_iter().map(f).collect()
+    }
+}
+
+#[cfg(not(any(not(target_arch = "wasm32"), feature = "threads")))]
+mod threaded {
+    /// Number of items which are worked on at the same time
+    pub fn threads() -> usize {
+        1
+    }
+
+    /// Apply `f` to every item, keeping their order
+    pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+        items.iter().map(f).collect()
+    }
+}
+
+pub use threaded::{map, threads};
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{ProcessOptions, TOOL_NAME};
+
+/// Version of the preset format, increased when old presets can no longer be read
+const PRESET_VERSION: u32 = 1;
+
+/// Processing settings saved to a file so they can be loaded again later
+#[derive(Serialize, Deserialize)]
+struct Preset<O = ProcessOptions> {
+    tool: String,
+    preset_version: u32,
+    options: O,
+}
+
+/// Serialise the settings as a JSON preset
+pub fn export(options: &ProcessOptions) -> anyhow::Result<Vec<u8>> {
+    let preset = Preset {
+        tool: TOOL_NAME.to_string(),
+        preset_version: PRESET_VERSION,
+        options: options.clone(),
+    };
+    Ok(serde_json::to_vec_pretty(&preset)?)
+}
+
+/// Read the settings of a JSON preset in the given form, checking that it can be loaded
+fn read<O: serde::de::DeserializeOwned>(data: &[u8]) -> anyhow::Result<O> {
+    let preset: Preset<O> = serde_json::from_slice(data)
+        .map_err(|e| anyhow::anyhow!("The file is not a valid preset: {}", e))?;
+    if preset.tool != TOOL_NAME {
+        anyhow::bail!("The preset was not saved by {}", TOOL_NAME);
+    }
+    if preset.preset_version > PRESET_VERSION {
+        anyhow::bail!("The preset was saved by a newer version and cannot be loaded");
+    }
+    Ok(preset.options)
+}
+
+/// Read the settings from a JSON preset
+pub fn import(data: &[u8]) -> anyhow::Result<ProcessOptions> {
+    read(data)
+}
+
+/// Read the settings of a JSON preset as changes for [apply], so settings the preset does not
+/// have keep their values
+pub fn import_changes(data: &[u8]) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+    read(data)
+}
+
+/// Change the settings with the given names to the given values, which are written as in a
+/// preset. Names which are not settings are rejected.
+pub fn apply(
+    options: &ProcessOptions,
+    changes: serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<ProcessOptions> {
+    let mut settings = serde_json::to_value(options)?;
+    let serde_json::Value::Object(names) = &mut settings else {
+        anyhow::bail!("The settings are not an object");
+    };
+    for (name, value) in changes {
+        if !names.contains_key(&name) {
+            anyhow::bail!("Unknown setting {}", name);
+        }
+        names.insert(name, value);
+    }
+    serde_json::from_value(settings).map_err(|e| anyhow::anyhow!("Invalid setting: {}", e))
+}
@@ -0,0 +1,165 @@
+//! Fixes of common mistakes of MIDI files, made before the tracks are split so the generated
+//! files do not inherit them
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use midly::{Format, MetaMessage, MidiMessage, Smf, Track, TrackEvent, TrackEventKind};
+use serde::{Deserialize, Serialize};
+
+use crate::transform;
+
+/// Mistake of a file which was fixed
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum RepairKind {
+    /// The header gives another number of tracks than the file holds, so the tracks found are
+    /// written
+    TrackCount { declared: usize, found: usize },
+    /// The header gives the single track format for several tracks, so they are written as
+    /// parallel tracks
+    SingleTrackFormat,
+    /// A track, counting from 0, did not end with an EndOfTrack event, so one was added
+    MissingEndOfTrack { track: usize },
+    /// EndOfTrack events of a track, counting from 0, which came before its end were removed
+    DuplicateEndOfTrack { track: usize, count: usize },
+    /// Notes of a track, counting from 0, which were still sounding at its end were ended there
+    DanglingNotes { track: usize, count: usize },
+}
+
+/// Fix made to an upload
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Repair {
+    pub file_name: String,
+    pub kind: RepairKind,
+}
+
+impl Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file_name = &self.file_name;
+        match self.kind {
+            RepairKind::TrackCount { declared, found } => write!(
+                f,
+                "{}: wrote the {} tracks found instead of the {} the header gives",
+                file_name, found, declared
+            ),
+            RepairKind::SingleTrackFormat => write!(
+                f,
+                "{}: wrote the tracks as parallel tracks instead of the single track format the header gives",
+                file_name
+            ),
+            RepairKind::MissingEndOfTrack { track } => write!(
+                f,
+                "{}: added the missing end of track {}",
+                file_name, track
+            ),
+            RepairKind::DuplicateEndOfTrack { track, count } => write!(
+                f,
+                "{}: removed {} extra ends of track {}",
+                file_name, count, track
+            ),
+            RepairKind::DanglingNotes { track, count } => write!(
+                f,
+                "{}: ended {} notes which were still sounding at the end of track {}",
+                file_name, count, track
+            ),
+        }
+    }
+}
+
+/// Number of tracks the header of the MIDI file in `data` gives
+fn declared_track_count(data: &[u8]) -> Option<usize> {
+    let start = data.windows(4).position(|id| id == b"MThd")?;
+    let count = data.get(start + 10..start + 12)?;
+    Some(u16::from_be_bytes([count[0], count[1]]) as usize)
+}
+
+/// Fix the EndOfTrack events and the notes of a track
+fn repair_track(track: &mut Track, index: usize, mut repaired: impl FnMut(RepairKind)) {
+    let is_end = |event: &TrackEvent| event.kind == TrackEventKind::Meta(MetaMessage::EndOfTrack);
+
+    let ends = track.last().is_some_and(is_end);
+    let end = if ends { track.pop() } else { None };
+    let extra_ends = track.iter().filter(|event| is_end(event)).count();
+    if extra_ends > 0 {
+        transform::retain_events(track, |event| !is_end(event));
+        repaired(RepairKind::DuplicateEndOfTrack {
+            track: index,
+            count: extra_ends,
+        });
+    }
+    let mut end = end.unwrap_or_else(|| {
+        repaired(RepairKind::MissingEndOfTrack { track: index });
+        TrackEvent {
+            delta: 0.into(),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        }
+    });
+
+    // Sorted so the added NoteOffs are the same every time
+    let mut sounding: BTreeMap<(u8, u8), usize> = BTreeMap::new();
+    for event in track.iter() {
+        if let TrackEventKind::Midi { channel, message } = event.kind {
+            match message {
+                MidiMessage::NoteOn { key, vel } if vel > 0 => {
+                    *sounding
+                        .entry((channel.as_int(), key.as_int()))
+                        .or_default() += 1
+                }
+                MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. } => {
+                    if let Some(count) = sounding.get_mut(&(channel.as_int(), key.as_int())) {
+                        *count = count.saturating_sub(1);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+    let dangling: usize = sounding.values().sum();
+    if dangling > 0 {
+        for (&(channel, key), &count) in &sounding {
+            for _ in 0..count {
+                // The NoteOffs happen when the track ends
+                track.push(TrackEvent {
+                    delta: end.delta,
+                    kind: TrackEventKind::Midi {
+                        channel: channel.into(),
+                        message: MidiMessage::NoteOff {
+                            key: key.into(),
+                            vel: 0.into(),
+                        },
+                    },
+                });
+                end.delta = 0.into();
+            }
+        }
+        repaired(RepairKind::DanglingNotes {
+            track: index,
+            count: dangling,
+        });
+    }
+    track.push(end);
+}
+
+/// Fix the common mistakes of the parsed MIDI file in `data`, returning the fixes made
+pub fn repair(file_name: &str, data: &[u8], smf: &mut Smf) -> Vec<Repair> {
+    let mut repairs = Vec::new();
+    let mut repaired = |kind| {
+        repairs.push(Repair {
+            file_name: file_name.to_string(),
+            kind,
+        })
+    };
+
+    let found = smf.tracks.len();
+    if let Some(declared) = declared_track_count(data).filter(|&declared| declared != found) {
+        repaired(RepairKind::TrackCount { declared, found });
+    }
+    if smf.header.format == Format::SingleTrack && found > 1 {
+        smf.header.format = Format::Parallel;
+        repaired(RepairKind::SingleTrackFormat);
+    }
+    for (index, track) in smf.tracks.iter_mut().enumerate() {
+        repair_track(track, index, &mut repaired);
+    }
+
+    repairs
+}
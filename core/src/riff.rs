@@ -0,0 +1,29 @@
+/// Whether the file is a RIFF MIDI file (.rmi).
+/// These wrap a standard MIDI file in the `data` chunk of a RIFF container.
+pub fn is_riff_midi(data: &[u8]) -> bool {
+    data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"RMID"
+}
+
+/// Return the standard MIDI file embedded in a RIFF MIDI file
+pub fn unwrap(data: &[u8]) -> anyhow::Result<&[u8]> {
+    let mut chunks = &data[12..];
+
+    while chunks.len() >= 8 {
+        let id = &chunks[0..4];
+        let size = u32::from_le_bytes([chunks[4], chunks[5], chunks[6], chunks[7]]) as usize;
+        let body = chunks
+            .get(8..8 + size)
+            .ok_or(anyhow::anyhow!("The RIFF MIDI file is truncated"))?;
+
+        if id == b"data" {
+            return Ok(body);
+        }
+
+        // Chunks are padded to an even length
+        chunks = chunks.get(8 + size + size % 2..).unwrap_or_default();
+    }
+
+    Err(anyhow::anyhow!(
+        "The RIFF MIDI file does not contain any MIDI data"
+    ))
+}
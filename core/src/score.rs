@@ -0,0 +1,242 @@
+use midly::{MetaMessage, Timing, Track, TrackEventKind};
+
+use crate::{
+    notes::{notes, Note},
+    timing::{self, absolute_ticks, Tick},
+};
+
+/// Divisions of a quarter note, allowing durations down to a 32nd note
+pub const DIVISIONS: u64 = 8;
+
+/// Step and alteration of each pitch class spelled with sharps
+const SHARP_STEPS: [(char, i8); 12] = [
+    ('C', 0),
+    ('C', 1),
+    ('D', 0),
+    ('D', 1),
+    ('E', 0),
+    ('F', 0),
+    ('F', 1),
+    ('G', 0),
+    ('G', 1),
+    ('A', 0),
+    ('A', 1),
+    ('B', 0),
+];
+
+/// Step and alteration of each pitch class spelled with flats
+const FLAT_STEPS: [(char, i8); 12] = [
+    ('C', 0),
+    ('D', -1),
+    ('D', 0),
+    ('E', -1),
+    ('E', 0),
+    ('F', 0),
+    ('G', -1),
+    ('G', 0),
+    ('A', -1),
+    ('A', 0),
+    ('B', -1),
+    ('B', 0),
+];
+
+/// Step, alteration and octave of a key
+pub fn spell(key: u8, flats: bool) -> (char, i8, i32) {
+    let (step, alter) = if flats {
+        FLAT_STEPS[key as usize % 12]
+    } else {
+        SHARP_STEPS[key as usize % 12]
+    };
+    (step, alter, key as i32 / 12 - 1)
+}
+
+/// Chord or rest between two onsets which does not cross a barline
+pub struct Segment {
+    pub start: u64,
+    pub length: u64,
+    /// Keys of the chord, empty for a rest
+    pub keys: Vec<u8>,
+    pub tied_to_previous: bool,
+    pub tied_to_next: bool,
+}
+
+/// Bar of the score
+pub struct Measure {
+    pub start: u64,
+    pub length: u64,
+    /// Time signature starting with this bar
+    pub time_signature: Option<(u8, u8)>,
+    /// Key signature starting with this bar
+    pub key_signature: Option<(i8, bool)>,
+}
+
+/// Single part laid out in bars for notation.
+/// The notes get quantised to 32nd notes and overlapping notes are cut short
+/// so the part can be written as a single voice of chords.
+pub struct Score {
+    pub notes: Vec<Note>,
+    pub measures: Vec<Measure>,
+    /// Chords and rests in order, each within a single bar
+    pub segments: Vec<Segment>,
+}
+
+impl Score {
+    pub fn new(tracks: &[Track], index: usize, timing: Timing) -> anyhow::Result<Self> {
+        let ticks_per_beat = timing::ticks_per_beat(timing)?;
+        let to_divisions = |tick: Tick| (tick * DIVISIONS + ticks_per_beat / 2) / ticks_per_beat;
+
+        let notes = notes(&tracks[index]);
+        let mut chords: Vec<(u64, u64, Vec<u8>)> = Vec::new();
+        for note in &notes {
+            let start = to_divisions(note.start);
+            let end = to_divisions(note.end).max(start + 1);
+            match chords.last_mut() {
+                Some((chord_start, chord_end, keys)) if *chord_start == start => {
+                    *chord_end = (*chord_end).min(end);
+                    if !keys.contains(&note.key) {
+                        keys.push(note.key);
+                    }
+                }
+                _ => chords.push((start, end, vec![note.key])),
+            }
+        }
+        // A chord ends once the next one starts
+        for i in 1..chords.len() {
+            let next_start = chords[i].0;
+            let previous_end = &mut chords[i - 1].1;
+            *previous_end = (*previous_end).min(next_start);
+        }
+
+        let end = chords.last().map_or(0, |(_, end, _)| *end);
+        let measures = measures(tracks, ticks_per_beat, end, &to_divisions);
+
+        // Fill the gaps between the chords with rests and cut everything at the barlines
+        let mut segments: Vec<Segment> = Vec::new();
+        let mut position = 0;
+        let score_end = measures
+            .last()
+            .map_or(0, |measure| measure.start + measure.length);
+        let rests_and_chords =
+            chords
+                .into_iter()
+                .chain(std::iter::once((score_end, score_end, Vec::new())));
+        for (start, end, keys) in rests_and_chords {
+            if start > position {
+                split_at_barlines(&mut segments, &measures, position, start, Vec::new());
+            }
+            if end > start {
+                split_at_barlines(&mut segments, &measures, start, end, keys);
+            }
+            position = end;
+        }
+
+        Ok(Score {
+            notes,
+            measures,
+            segments,
+        })
+    }
+
+    /// Whether the key signature of the part uses flats
+    pub fn uses_flats(&self) -> bool {
+        self.measures
+            .iter()
+            .find_map(|measure| measure.key_signature)
+            .is_some_and(|(fifths, _)| fifths < 0)
+    }
+}
+
+/// Bars of the score up to the given end, with the time and key signatures starting in them
+fn measures(
+    tracks: &[Track],
+    ticks_per_beat: Tick,
+    end: u64,
+    to_divisions: &impl Fn(Tick) -> u64,
+) -> Vec<Measure> {
+    let time_signatures = timing::time_signatures(tracks);
+
+    let mut key_signatures: Vec<(u64, i8, bool)> = tracks
+        .iter()
+        .flat_map(|track| absolute_ticks(track))
+        .filter_map(|(tick, event)| match event.kind {
+            TrackEventKind::Meta(MetaMessage::KeySignature(fifths, minor)) => {
+                Some((to_divisions(tick), fifths, minor))
+            }
+            _ => None,
+        })
+        .collect();
+    key_signatures.sort_by_key(|(start, _, _)| *start);
+    let mut key_signatures = key_signatures.into_iter().peekable();
+
+    let mut measures = Vec::new();
+    let mut start = 0;
+    let mut index = 0;
+    let mut previous_index = None;
+    while measures.is_empty() || start < end {
+        while time_signatures
+            .get(index + 1)
+            .is_some_and(|next| to_divisions(next.tick) <= start)
+        {
+            index += 1;
+        }
+        let time_signature = (previous_index != Some(index)).then(|| {
+            (
+                time_signatures[index].numerator,
+                time_signatures[index].denominator_pow2,
+            )
+        });
+        previous_index = Some(index);
+
+        // A time signature in the middle of a bar starts a new one
+        let mut length = to_divisions(time_signatures[index].bar_length(ticks_per_beat)).max(1);
+        if let Some(next) = time_signatures.get(index + 1) {
+            length = length.min(to_divisions(next.tick) - start);
+        }
+
+        let mut key_signature = None;
+        while let Some((_, fifths, minor)) =
+            key_signatures.next_if(|(key_start, _, _)| *key_start < start + length)
+        {
+            key_signature = Some((fifths, minor));
+        }
+
+        measures.push(Measure {
+            start,
+            length,
+            time_signature,
+            key_signature,
+        });
+        start += length;
+    }
+
+    measures
+}
+
+/// Add a chord or rest, splitting it into tied notes where it crosses a barline
+fn split_at_barlines(
+    segments: &mut Vec<Segment>,
+    measures: &[Measure],
+    start: u64,
+    end: u64,
+    keys: Vec<u8>,
+) {
+    let mut position = start;
+    for measure in measures {
+        let measure_end = measure.start + measure.length;
+        if measure_end <= position {
+            continue;
+        }
+        if position >= end {
+            break;
+        }
+        let segment_end = end.min(measure_end);
+        segments.push(Segment {
+            start: position,
+            length: segment_end - position,
+            tied_to_previous: position > start && !keys.is_empty(),
+            tied_to_next: segment_end < end && !keys.is_empty(),
+            keys: keys.clone(),
+        });
+        position = segment_end;
+    }
+}
@@ -0,0 +1,237 @@
+use midly::{MetaMessage, MidiMessage, Track, TrackEvent, TrackEventKind};
+
+use crate::{
+    timing::{absolute_ticks, Tick},
+    transform, ProcessOptions, TOOL_NAME,
+};
+
+/// Manufacturer ID reserved for non-commercial use which starts the sequencer specific data
+const MANUFACTURER_ID: u8 = 0x7D;
+/// Identifies the sequencer specific events holding the original velocities
+const MAGIC: &[u8] = b"midi_splitter velocities";
+
+/// Velocities of every NoteOn of the track including the ones acting as NoteOffs
+fn note_on_velocities<'a>(track: &'a [TrackEvent]) -> impl Iterator<Item = u8> + 'a {
+    track.iter().filter_map(|event| match event.kind {
+        TrackEventKind::Midi {
+            channel: _,
+            message: MidiMessage::NoteOn { key: _, vel },
+        } => Some(vel.as_int()),
+        _ => None,
+    })
+}
+
+/// Create a stamp recording the velocities of all notes of the track
+pub fn create(track: &[TrackEvent]) -> Vec<u8> {
+    let mut stamp = vec![MANUFACTURER_ID];
+    stamp.extend_from_slice(MAGIC);
+    stamp.extend(note_on_velocities(track));
+    stamp
+}
+
+/// Add a stamp created from the original track to the start of the track
+pub fn insert<'a>(track: &mut Track<'a>, stamp: &'a [u8]) {
+    track.insert(
+        0,
+        TrackEvent {
+            delta: 0.into(),
+            kind: TrackEventKind::Meta(MetaMessage::SequencerSpecific(stamp)),
+        },
+    );
+}
+
+/// Text recording the tool and the exact options a file was generated with
+pub fn provenance(options: &ProcessOptions) -> anyhow::Result<Vec<u8>> {
+    let mut text = format!(
+        "Generated by {} {} with ",
+        TOOL_NAME,
+        env!("CARGO_PKG_VERSION")
+    )
+    .into_bytes();
+    serde_json::to_writer(&mut text, options)?;
+    Ok(text)
+}
+
+/// Add the provenance text to the start of the first track
+pub fn insert_provenance<'a>(first_track: &mut Track<'a>, provenance: &'a [u8]) {
+    first_track.insert(
+        0,
+        TrackEvent {
+            delta: 0.into(),
+            kind: TrackEventKind::Meta(MetaMessage::Text(provenance)),
+        },
+    );
+}
+
+/// Original velocities recorded in a stamp event
+fn stamped_velocities(kind: &TrackEventKind) -> Option<Vec<u8>> {
+    match kind {
+        TrackEventKind::Meta(MetaMessage::SequencerSpecific(data)) => data
+            .strip_prefix(&[MANUFACTURER_ID])
+            .and_then(|data| data.strip_prefix(MAGIC))
+            .map(<[u8]>::to_vec),
+        _ => None,
+    }
+}
+
+/// Whether the event is a stamp of the original velocities
+pub fn is_stamp(kind: &TrackEventKind) -> bool {
+    matches!(
+        kind,
+        TrackEventKind::Meta(MetaMessage::SequencerSpecific(data))
+            if data.strip_prefix(&[MANUFACTURER_ID]).is_some_and(|data| data.starts_with(MAGIC))
+    )
+}
+
+/// Combine the stamps of tracks merged by [transform::merge_tracks] into the stamp of the merged
+/// track, with the velocities in the order its notes end up in. Tracks without a stamp, such as
+/// the click track, keep the velocities they have. Returns None if no track is stamped.
+pub fn merge<'a>(tracks: &[impl AsRef<Track<'a>>]) -> Option<Vec<u8>> {
+    let mut stamped = false;
+    let mut velocities: Vec<(Tick, u8)> = Vec::new();
+    for track in tracks {
+        let track = track.as_ref();
+        let stamp = track
+            .iter()
+            .find_map(|event| stamped_velocities(&event.kind));
+        stamped |= stamp.is_some();
+        let mut original = stamp.unwrap_or_default().into_iter();
+        velocities.extend(
+            absolute_ticks(track).filter_map(|(tick, event)| match event.kind {
+                TrackEventKind::Midi {
+                    channel: _,
+                    message: MidiMessage::NoteOn { key: _, vel },
+                } => Some((tick, original.next().unwrap_or(vel.as_int()))),
+                _ => None,
+            }),
+        );
+    }
+    // Stable like the merging, so notes at the same tick keep the order of their tracks
+    velocities.sort_by_key(|(tick, _)| *tick);
+
+    stamped.then(|| {
+        let mut stamp = vec![MANUFACTURER_ID];
+        stamp.extend_from_slice(MAGIC);
+        stamp.extend(velocities.into_iter().map(|(_, velocity)| velocity));
+        stamp
+    })
+}
+
+/// Restore the original velocities of all stamped tracks and remove the stamps
+pub fn restore(tracks: &mut [Track]) -> anyhow::Result<()> {
+    let mut restored = false;
+
+    for (index, track) in tracks.iter_mut().enumerate() {
+        let Some(velocities) = track
+            .iter()
+            .find_map(|event| stamped_velocities(&event.kind))
+        else {
+            continue;
+        };
+        transform::retain_events(track, |event| stamped_velocities(&event.kind).is_none());
+
+        if note_on_velocities(track).count() != velocities.len() {
+            return Err(anyhow::anyhow!(
+                "The notes of track {} were changed after the file was generated",
+                index
+            ));
+        }
+
+        let mut velocities = velocities.into_iter();
+        for event in track.iter_mut() {
+            if let TrackEventKind::Midi {
+                channel: _,
+                message: MidiMessage::NoteOn { key: _, vel },
+            } = &mut event.kind
+            {
+                *vel = velocities.next().unwrap_or_default().into();
+            }
+        }
+        restored = true;
+    }
+
+    if restored {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "The file does not contain any original velocities to restore"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(delta: u32, key: u8, vel: u8) -> TrackEvent<'static> {
+        TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Midi {
+                channel: 0.into(),
+                message: MidiMessage::NoteOn {
+                    key: key.into(),
+                    vel: vel.into(),
+                },
+            },
+        }
+    }
+
+    fn velocities(track: &[TrackEvent]) -> Vec<u8> {
+        note_on_velocities(track).collect()
+    }
+
+    #[test]
+    fn restore_undoes_changed_velocities() {
+        let original = vec![note(0, 60, 40), note(480, 60, 0), note(0, 62, 90)];
+        let stamp = create(&original);
+        let mut track = original.clone();
+        transform::map_velocities(&mut track, |vel| (vel.as_int() / 2).into());
+        insert(&mut track, &stamp);
+
+        let mut tracks = vec![track];
+        restore(&mut tracks).unwrap();
+        assert_eq!(tracks[0], original);
+    }
+
+    #[test]
+    fn restore_needs_a_stamp() {
+        let mut tracks = vec![vec![note(0, 60, 40)]];
+        assert!(restore(&mut tracks).is_err());
+    }
+
+    #[test]
+    fn restore_rejects_changed_notes() {
+        let original = vec![note(0, 60, 40)];
+        let stamp = create(&original);
+        let mut track = vec![note(0, 60, 40), note(480, 62, 50)];
+        insert(&mut track, &stamp);
+        assert!(restore(&mut [track]).is_err());
+    }
+
+    #[test]
+    fn merged_stamp_restores_merged_track() {
+        let first = vec![note(0, 60, 40), note(480, 60, 0)];
+        let second = vec![note(240, 64, 100), note(480, 64, 0)];
+        let original = transform::merge_tracks(&[&first, &second]);
+
+        let mut tracks = [first.clone(), second.clone()];
+        let stamps: Vec<Vec<u8>> = tracks.iter().map(|track| create(track)).collect();
+        for (track, stamp) in tracks.iter_mut().zip(&stamps) {
+            transform::map_velocities(track, |vel| (vel.as_int() / 4).into());
+            insert(track, stamp);
+        }
+        let stamp = merge(&tracks).unwrap();
+        let mut merged = transform::merge_tracks(&tracks);
+        assert_ne!(velocities(&merged), velocities(&original));
+        insert(&mut merged, &stamp);
+
+        let mut merged = [merged];
+        restore(&mut merged).unwrap();
+        assert_eq!(velocities(&merged[0]), velocities(&original));
+    }
+
+    #[test]
+    fn merge_without_stamps_is_none() {
+        assert_eq!(merge(&[vec![note(0, 60, 40)]]), None);
+    }
+}
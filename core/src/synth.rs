@@ -0,0 +1,306 @@
+//! Audio recordings of the generated files for players without MIDI support.
+//!
+//! The notes are played by a small additive synthesiser with one simple voice per General MIDI
+//! instrument family rather than by a soundfont, and the recordings are only written as WAV.
+//! MP3 encoding is not supported.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    f32::consts::TAU,
+};
+
+use midly::{MidiMessage, Smf, TrackEventKind};
+
+use crate::{
+    gm,
+    timing::{absolute_ticks, end_tick, TempoMap, Tick},
+    transform::Rng,
+};
+
+/// Sample rate of the rendered audio. Low enough to keep the files small for voice practice.
+const SAMPLE_RATE: u32 = 22050;
+/// Longest recording in seconds, the default [crate::Limits::max_seconds] of a song.
+/// Anything after it is cut off so a song cannot make the recording take all memory.
+const MAX_SECONDS: f32 = 30.0 * 60.0;
+/// Seconds a note keeps sounding after it was released
+const RELEASE: f32 = 0.15;
+/// Loudness of a single note at full velocity and volume, leaving headroom for chords
+const NOTE_GAIN: f32 = 0.15;
+
+const CHANNEL_VOLUME: u8 = 7;
+const PAN: u8 = 10;
+const EXPRESSION: u8 = 11;
+const SUSTAIN_PEDAL: u8 = 64;
+
+/// How a note is synthesised, chosen by General MIDI instrument family
+#[derive(Clone, Copy, PartialEq)]
+enum Voice {
+    /// Struck or plucked strings which decay on their own
+    Plucked,
+    /// Sustained tones rich in harmonics such as bowed strings and brass
+    Bright,
+    /// Sustained tones with mostly odd harmonics such as reeds
+    Hollow,
+    /// Nearly pure sustained tones such as flutes and organs
+    Pure,
+    /// Unpitched drum sounds on the percussion channel
+    Drum,
+}
+
+impl Voice {
+    fn new(channel: u8, program: u8) -> Self {
+        if channel == gm::PERCUSSION_CHANNEL {
+            return Voice::Drum;
+        }
+        match program / 8 {
+            // Piano, Chromatic Percussion, Guitar, Bass, Ethnic, Percussive
+            0 | 1 | 3 | 4 | 13 | 14 => Voice::Plucked,
+            // Strings, Ensemble, Brass, Synth Lead, Synth Effects
+            5 | 6 | 7 | 10 | 12 => Voice::Bright,
+            // Reed, Synth Pad
+            8 | 11 => Voice::Hollow,
+            // Organ, Pipe, Sound Effects
+            _ => Voice::Pure,
+        }
+    }
+}
+
+/// Channel state needed to synthesise a note
+#[derive(Clone, Copy)]
+struct ChannelState {
+    program: u8,
+    volume: u8,
+    expression: u8,
+    pan: u8,
+    sustain: bool,
+}
+
+impl Default for ChannelState {
+    fn default() -> Self {
+        ChannelState {
+            program: 0,
+            volume: 100,
+            expression: 127,
+            pan: 64,
+            sustain: false,
+        }
+    }
+}
+
+/// Note ready to be synthesised
+struct SynthNote {
+    start: f32,
+    end: f32,
+    key: u8,
+    voice: Voice,
+    /// Gain of the left and right channel
+    gain: (f32, f32),
+}
+
+/// Collect the notes of all tracks with the state of their channel at the time they start
+fn synth_notes(smf: &Smf) -> Vec<SynthNote> {
+    let tempo_map = TempoMap::new(&smf.tracks, smf.header.timing);
+
+    // Events of all tracks in the order they are played
+    let mut events: Vec<(Tick, usize, &TrackEventKind)> = smf
+        .tracks
+        .iter()
+        .enumerate()
+        .flat_map(|(track, events)| {
+            absolute_ticks(events).map(move |(tick, event)| (tick, track, &event.kind))
+        })
+        .collect();
+    events.sort_by_key(|(tick, track, _)| (*tick, *track));
+
+    let mut channels = [ChannelState::default(); 16];
+    let mut sounding: HashMap<(u8, u8), VecDeque<usize>> = HashMap::new();
+    // Notes which were released while the sustain pedal was held
+    let mut sustained: [Vec<usize>; 16] = Default::default();
+    let mut notes: Vec<SynthNote> = Vec::new();
+    let end = tempo_map.seconds(end_tick(&smf.tracks)) as f32;
+
+    for (tick, _, kind) in events {
+        let TrackEventKind::Midi { channel, message } = kind else {
+            continue;
+        };
+        let channel = channel.as_int();
+        let state = &mut channels[channel as usize];
+        let seconds = tempo_map.seconds(tick) as f32;
+
+        match *message {
+            MidiMessage::NoteOn { key, vel } if vel > 0 => {
+                let loudness = vel.as_int() as f32 / 127.0 * state.volume as f32 / 127.0
+                    * state.expression as f32
+                    / 127.0
+                    * NOTE_GAIN;
+                // Equal power panning
+                let angle = state.pan as f32 / 127.0 * TAU / 4.0;
+                sounding
+                    .entry((channel, key.as_int()))
+                    .or_default()
+                    .push_back(notes.len());
+                notes.push(SynthNote {
+                    start: seconds,
+                    end,
+                    key: key.as_int(),
+                    voice: Voice::new(channel, state.program),
+                    gain: (loudness * angle.cos(), loudness * angle.sin()),
+                });
+            }
+            MidiMessage::NoteOn { key, vel: _ } | MidiMessage::NoteOff { key, vel: _ } => {
+                if let Some(note) = sounding
+                    .get_mut(&(channel, key.as_int()))
+                    .and_then(VecDeque::pop_front)
+                {
+                    if state.sustain {
+                        sustained[channel as usize].push(note);
+                    } else {
+                        notes[note].end = seconds;
+                    }
+                }
+            }
+            MidiMessage::ProgramChange { program } => state.program = program.as_int(),
+            MidiMessage::Controller { controller, value } => match controller.as_int() {
+                CHANNEL_VOLUME => state.volume = value.as_int(),
+                EXPRESSION => state.expression = value.as_int(),
+                PAN => state.pan = value.as_int(),
+                SUSTAIN_PEDAL => {
+                    state.sustain = value >= 64;
+                    if !state.sustain {
+                        for note in sustained[channel as usize].drain(..) {
+                            notes[note].end = seconds;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    notes
+}
+
+/// Amplitude envelope of a note at the given time since it started
+fn envelope(voice: Voice, time: f32, length: f32) -> f32 {
+    let attack = (time / 0.01).min(1.0);
+    let sustain = match voice {
+        Voice::Plucked => (-time * 2.5).exp(),
+        Voice::Drum => 1.0,
+        _ => 0.7 + 0.3 * (-time * 8.0).exp(),
+    };
+    let release = if time > length {
+        (1.0 - (time - length) / RELEASE).max(0.0)
+    } else {
+        1.0
+    };
+    attack * sustain * release
+}
+
+/// Waveform of a pitched note at the given phase in radians
+fn waveform(voice: Voice, phase: f32) -> f32 {
+    match voice {
+        Voice::Plucked => phase.sin() + 0.5 * (2.0 * phase).sin() + 0.25 * (3.0 * phase).sin(),
+        Voice::Bright => (1..=6).map(|n| (n as f32 * phase).sin() / n as f32).sum(),
+        Voice::Hollow => [1, 3, 5, 7]
+            .iter()
+            .map(|&n| (n as f32 * phase).sin() / n as f32)
+            .sum(),
+        Voice::Pure | Voice::Drum => phase.sin() + 0.1 * (2.0 * phase).sin(),
+    }
+}
+
+/// Sample of a drum sound at the given time since it was struck
+fn drum(key: u8, time: f32, rng: &mut Rng) -> f32 {
+    let noise = (rng.next_u64() >> 40) as f32 / (1u64 << 23) as f32 - 1.0;
+    match key {
+        // Bass drums sweep down in pitch
+        35 | 36 => {
+            let frequency = 50.0 + 70.0 * (-time * 30.0).exp();
+            (TAU * frequency * time).sin() * (-time * 12.0).exp()
+        }
+        // Snares mix a tone with noise
+        38 | 40 => (0.5 * (TAU * 180.0 * time).sin() + noise) * (-time * 20.0).exp(),
+        // Closed and pedal hi-hats are short
+        42 | 44 => 0.5 * noise * (-time * 60.0).exp(),
+        // Cymbals ring for a long time
+        49 | 51 | 52 | 53 | 55 | 57 | 59 => 0.4 * noise * (-time * 3.0).exp(),
+        // Toms
+        41 | 43 | 45 | 47 | 48 | 50 => {
+            let frequency = 80.0 + (key - 41) as f32 * 15.0;
+            (TAU * frequency * time).sin() * (-time * 8.0).exp()
+        }
+        _ => 0.5 * noise * (-time * 15.0).exp(),
+    }
+}
+
+/// Render the file to 16 bit stereo PCM samples using simple built in instruments
+fn render(smf: &Smf) -> Vec<i16> {
+    let notes = synth_notes(smf);
+    let length = notes
+        .iter()
+        .map(|note| note.end + RELEASE)
+        .fold(0.0, f32::max)
+        .min(MAX_SECONDS);
+    let frames = (length * SAMPLE_RATE as f32).ceil() as usize;
+    let mut mix = vec![0.0f32; frames * 2];
+    let mut rng = Rng::new(0);
+
+    for note in &notes {
+        let first = (note.start * SAMPLE_RATE as f32) as usize;
+        let note_length = note.end - note.start;
+        let sounding_length = match note.voice {
+            // Drums ring out regardless of the NoteOff
+            Voice::Drum => 1.0,
+            _ => note_length + RELEASE,
+        };
+        let last = (((note.start + sounding_length) * SAMPLE_RATE as f32) as usize).min(frames);
+        let frequency = 440.0 * 2f32.powf((note.key as f32 - 69.0) / 12.0);
+
+        for frame in first..last {
+            let time = (frame - first) as f32 / SAMPLE_RATE as f32;
+            let sample = match note.voice {
+                Voice::Drum => drum(note.key, time, &mut rng),
+                voice => {
+                    waveform(voice, TAU * frequency * time) * envelope(voice, time, note_length)
+                }
+            };
+            mix[frame * 2] += sample * note.gain.0;
+            mix[frame * 2 + 1] += sample * note.gain.1;
+        }
+    }
+
+    // Soft clipping keeps loud passages from wrapping around
+    mix.iter()
+        .map(|sample| (sample.tanh() * i16::MAX as f32) as i16)
+        .collect()
+}
+
+/// Render the file to a WAV file
+pub fn render_wav(smf: &Smf) -> Vec<u8> {
+    let samples = render(smf);
+    let channels: u16 = 2;
+    let bytes_per_frame = channels * 2;
+    let data_size = (samples.len() * 2) as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * bytes_per_frame as u32).to_le_bytes());
+    wav.extend_from_slice(&bytes_per_frame.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    wav
+}
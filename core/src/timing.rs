@@ -0,0 +1,322 @@
+use midly::{MetaMessage, Timing, Track, TrackEvent, TrackEventKind};
+
+/// Tempo of 120 BPM in microseconds per beat which applies until the first Tempo event
+pub const DEFAULT_TEMPO: u32 = 500_000;
+
+/// Position of an event in ticks from the start of the track
+pub type Tick = u64;
+
+/// Iterate over the events of a track together with their absolute tick
+pub fn absolute_ticks<'a, 'b>(
+    track: &'a [TrackEvent<'b>],
+) -> impl Iterator<Item = (Tick, &'a TrackEvent<'b>)> {
+    track.iter().scan(0, |tick, event| {
+        *tick += event.delta.as_int() as Tick;
+        Some((*tick, event))
+    })
+}
+
+/// Ticks per quarter note of the file, if it uses metrical timing.
+/// Files can give 0, which is taken as 1 so nothing divides by it.
+pub fn ticks_per_beat(timing: Timing) -> anyhow::Result<Tick> {
+    match timing {
+        Timing::Metrical(ticks_per_beat) => Ok(ticks_per_beat.as_int().max(1) as Tick),
+        Timing::Timecode(_, _) => Err(anyhow::anyhow!(
+            "Files with SMPTE timecode timing have no bars or beats"
+        )),
+    }
+}
+
+/// Largest denominator of a time signature as a power of two, 1/128 notes.
+/// Files can give any byte, but larger denominators cannot be written down.
+const MAX_DENOMINATOR_POW2: u8 = 7;
+
+/// Time signature starting at a tick
+#[derive(Clone, Copy)]
+pub struct TimeSignature {
+    pub tick: Tick,
+    pub numerator: u8,
+    /// Denominator as a power of two
+    pub denominator_pow2: u8,
+}
+
+impl TimeSignature {
+    /// Length of a beat in ticks
+    pub fn beat_length(&self, ticks_per_beat: Tick) -> Tick {
+        (ticks_per_beat * 4)
+            .checked_shr(self.denominator_pow2.into())
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    /// Length of a bar in ticks
+    pub fn bar_length(&self, ticks_per_beat: Tick) -> Tick {
+        self.numerator.max(1) as Tick * self.beat_length(ticks_per_beat)
+    }
+}
+
+/// Time signature changes of the whole file sorted by tick.
+/// The file starts in 4/4 until the first time signature, and time signatures whose
+/// denominator is too large to exist are left out.
+pub fn time_signatures(tracks: &[Track]) -> Vec<TimeSignature> {
+    let mut time_signatures = vec![TimeSignature {
+        tick: 0,
+        numerator: 4,
+        denominator_pow2: 2,
+    }];
+    for track in tracks {
+        for (tick, event) in absolute_ticks(track) {
+            if let TrackEventKind::Meta(MetaMessage::TimeSignature(numerator, denominator, _, _)) =
+                event.kind
+            {
+                if denominator > MAX_DENOMINATOR_POW2 {
+                    continue;
+                }
+                time_signatures.push(TimeSignature {
+                    tick,
+                    numerator,
+                    denominator_pow2: denominator,
+                });
+            }
+        }
+    }
+    // Keep the last time signature at each tick so the default 4/4 gets overridden
+    time_signatures.sort_by_key(|time_signature| time_signature.tick);
+    time_signatures.reverse();
+    time_signatures.dedup_by_key(|time_signature| time_signature.tick);
+    time_signatures.reverse();
+    time_signatures
+}
+
+/// Tick at which the given bar starts, counting bars from 1
+pub fn bar_start(tracks: &[Track], ticks_per_beat: Tick, bar: u32) -> Tick {
+    let bar = bar.max(1) as Tick;
+    let time_signatures = time_signatures(tracks);
+
+    let mut current_bar = 1;
+    let mut current = time_signatures[0];
+    for &next in &time_signatures[1..] {
+        // A time signature in the middle of a bar starts a new one
+        let bars = (next.tick - current.tick).div_ceil(current.bar_length(ticks_per_beat));
+        if bar < current_bar + bars {
+            break;
+        }
+        current_bar += bars;
+        current = next;
+    }
+
+    current.tick + (bar - current_bar) * current.bar_length(ticks_per_beat)
+}
+
+/// Bar and beat of a tick, both counting from 1
+pub fn bar_and_beat(
+    time_signatures: &[TimeSignature],
+    ticks_per_beat: Tick,
+    tick: Tick,
+) -> (Tick, Tick) {
+    let mut bar = 1;
+    let mut current = time_signatures[0];
+    for &next in &time_signatures[1..] {
+        if next.tick > tick {
+            break;
+        }
+        // A time signature in the middle of a bar starts a new one
+        bar += (next.tick - current.tick).div_ceil(current.bar_length(ticks_per_beat));
+        current = next;
+    }
+
+    let offset = tick - current.tick;
+    let bar_length = current.bar_length(ticks_per_beat);
+    (
+        bar + offset / bar_length,
+        offset % bar_length / current.beat_length(ticks_per_beat) + 1,
+    )
+}
+
+/// Tick at which the track ends
+pub fn track_end_tick(track: &[TrackEvent]) -> Tick {
+    absolute_ticks(track).last().map_or(0, |(tick, _)| tick)
+}
+
+/// Tick at which the last track ends
+pub fn end_tick<'a>(tracks: &[impl AsRef<Track<'a>>]) -> Tick {
+    tracks
+        .iter()
+        .map(|track| track_end_tick(track.as_ref()))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Tempo events of the whole file as microseconds per beat, sorted by tick
+pub fn tempos<'a>(tracks: &[impl AsRef<Track<'a>>]) -> Vec<(Tick, u32)> {
+    let mut tempos: Vec<(Tick, u32)> = tracks
+        .iter()
+        .flat_map(|track| absolute_ticks(track.as_ref()))
+        .filter_map(|(tick, event)| match event.kind {
+            TrackEventKind::Meta(MetaMessage::Tempo(tempo)) => Some((tick, tempo.as_int())),
+            _ => None,
+        })
+        .collect();
+    tempos.sort_by_key(|(tick, _)| *tick);
+    tempos
+}
+
+/// Beats per minute of a tempo given in microseconds per beat
+pub fn bpm(tempo: u32) -> f64 {
+    60_000_000.0 / tempo.max(1) as f64
+}
+
+/// Converts ticks into seconds following the tempo changes of a file
+pub struct TempoMap {
+    /// Tick, time in seconds and seconds per tick at the start of each tempo
+    segments: Vec<(Tick, f64, f64)>,
+}
+
+impl TempoMap {
+    pub fn new<'a>(tracks: &[impl AsRef<Track<'a>>], timing: Timing) -> Self {
+        let ticks_per_beat = match timing {
+            Timing::Metrical(ticks_per_beat) => ticks_per_beat.as_int().max(1) as f64,
+            Timing::Timecode(fps, subframes) => {
+                let seconds_per_tick = 1.0 / (fps.as_f32() as f64 * subframes.max(1) as f64);
+                return TempoMap {
+                    segments: vec![(0, 0.0, seconds_per_tick)],
+                };
+            }
+        };
+        let seconds_per_tick = |tempo: u32| tempo as f64 / ticks_per_beat / 1_000_000.0;
+
+        let mut segments = vec![(0, 0.0, seconds_per_tick(DEFAULT_TEMPO))];
+        for (tick, tempo) in tempos(tracks) {
+            let &(start, seconds, current) = segments.last().expect("There is a first segment");
+            let seconds = seconds + (tick - start) as f64 * current;
+            // A later tempo at the same tick replaces the earlier one
+            if start == tick {
+                segments.pop();
+            }
+            segments.push((tick, seconds, seconds_per_tick(tempo)));
+        }
+
+        TempoMap { segments }
+    }
+
+    /// Time in seconds at which the given tick is played
+    pub fn seconds(&self, tick: Tick) -> f64 {
+        let index = self
+            .segments
+            .partition_point(|(start, _, _)| *start <= tick);
+        let (start, seconds, seconds_per_tick) = self.segments[index.saturating_sub(1)];
+        seconds + (tick - start) as f64 * seconds_per_tick
+    }
+}
+
+/// Ticks of all beats up to the end tick, each with whether it is the first beat of a bar
+pub fn beats(tracks: &[Track], ticks_per_beat: Tick) -> Vec<(Tick, bool)> {
+    let time_signatures = time_signatures(tracks);
+    let end = end_tick(tracks);
+
+    let mut beats = Vec::new();
+    for (index, time_signature) in time_signatures.iter().enumerate() {
+        let segment_end = time_signatures
+            .get(index + 1)
+            .map_or(end, |next| next.tick.min(end));
+
+        let mut tick = time_signature.tick;
+        let mut beat = 0;
+        while tick < segment_end {
+            beats.push((tick, beat == 0));
+            tick += time_signature.beat_length(ticks_per_beat);
+            beat = (beat + 1) % time_signature.numerator.max(1);
+        }
+    }
+    beats
+}
+
+/// Number of bars which start before the end tick
+pub fn bar_count(time_signatures: &[TimeSignature], ticks_per_beat: Tick, end: Tick) -> usize {
+    match end {
+        0 => 0,
+        end => bar_and_beat(time_signatures, ticks_per_beat, end - 1).0 as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use midly::MetaMessage;
+
+    use super::*;
+
+    fn time_signature(numerator: u8, denominator_pow2: u8) -> TimeSignature {
+        TimeSignature {
+            tick: 0,
+            numerator,
+            denominator_pow2,
+        }
+    }
+
+    fn meta(delta: u32, message: MetaMessage<'static>) -> TrackEvent<'static> {
+        TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Meta(message),
+        }
+    }
+
+    #[test]
+    fn ticks_per_beat_is_never_zero() {
+        assert_eq!(ticks_per_beat(Timing::Metrical(480.into())).unwrap(), 480);
+        assert_eq!(ticks_per_beat(Timing::Metrical(0.into())).unwrap(), 1);
+    }
+
+    #[test]
+    fn beat_length_follows_denominator() {
+        assert_eq!(time_signature(4, 2).beat_length(480), 480);
+        assert_eq!(time_signature(6, 3).beat_length(480), 240);
+        assert_eq!(time_signature(2, 1).beat_length(480), 960);
+        assert_eq!(time_signature(3, 2).bar_length(480), 1440);
+    }
+
+    #[test]
+    fn beat_length_of_huge_denominator_is_one_tick() {
+        assert_eq!(time_signature(4, 200).beat_length(480), 1);
+        assert_eq!(time_signature(4, 255).beat_length(480), 1);
+    }
+
+    #[test]
+    fn bar_count_defaults_to_four_four() {
+        let tracks = vec![vec![meta(480 * 8, MetaMessage::EndOfTrack)]];
+        assert_eq!(
+            bar_count(&time_signatures(&tracks), 480, end_tick(&tracks)),
+            2
+        );
+    }
+
+    #[test]
+    fn bar_count_follows_time_signatures() {
+        let tracks = vec![vec![
+            meta(0, MetaMessage::TimeSignature(3, 2, 24, 8)),
+            meta(480 * 9, MetaMessage::EndOfTrack),
+        ]];
+        assert_eq!(
+            bar_count(&time_signatures(&tracks), 480, end_tick(&tracks)),
+            3
+        );
+    }
+
+    #[test]
+    fn bar_count_ignores_impossible_denominators() {
+        let tracks = vec![vec![
+            meta(0, MetaMessage::TimeSignature(4, 200, 24, 8)),
+            meta(480 * 8, MetaMessage::EndOfTrack),
+        ]];
+        assert_eq!(time_signatures(&tracks).len(), 1);
+        assert_eq!(
+            bar_count(&time_signatures(&tracks), 480, end_tick(&tracks)),
+            2
+        );
+    }
+
+    #[test]
+    fn bar_count_of_empty_song_is_zero() {
+        let tracks = vec![vec![meta(0, MetaMessage::EndOfTrack)]];
+        assert_eq!(bar_count(&time_signatures(&tracks), 480, 0), 0);
+    }
+}
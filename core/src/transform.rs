@@ -0,0 +1,799 @@
+use std::collections::{HashMap, VecDeque};
+
+use midly::{
+    num::{u24, u28, u7},
+    MetaMessage, MidiMessage, Track, TrackEvent, TrackEventKind,
+};
+
+use crate::{
+    gm, stamp,
+    timing::{self, absolute_ticks, Tick},
+};
+
+/// Controller number of Channel Volume
+const CHANNEL_VOLUME: u8 = 7;
+/// Controller number of Pan
+const PAN: u8 = 10;
+/// Controller number of Expression
+const EXPRESSION: u8 = 11;
+/// Pan position of hard left
+pub const PAN_LEFT: u8 = 0;
+/// Pan position of hard right
+pub const PAN_RIGHT: u8 = 127;
+/// Channel Volume a General MIDI device starts out with
+const DEFAULT_CHANNEL_VOLUME: u8 = 100;
+
+/// Keep only the events for which `keep` returns true.
+/// The delta times of removed events are carried over so the timing of the rest stays intact.
+pub fn retain_events(track: &mut Vec<TrackEvent>, mut keep: impl FnMut(&TrackEvent) -> bool) {
+    let mut carried_delta = 0;
+    track.retain_mut(|event| {
+        let delta = carried_delta + event.delta.as_int();
+        if keep(event) {
+            event.delta = delta.into();
+            carried_delta = 0;
+            true
+        } else {
+            carried_delta = delta;
+            false
+        }
+    });
+}
+
+/// Remove all channel messages from the track, silencing it while keeping its meta events
+pub fn remove_channel_messages(track: &mut Vec<TrackEvent>) {
+    retain_events(track, |event| {
+        !matches!(
+            event.kind,
+            TrackEventKind::Midi {
+                channel: _,
+                message: _
+            }
+        )
+    });
+}
+
+/// Remove SysEx, sequencer specific and text events such as lyrics and markers which some
+/// devices struggle with. Tempo, time and key signatures and track names are kept.
+/// Karaoke files keep their lyrics which they store in both Lyric and Text events.
+pub fn strip_non_essential_events(tracks: &mut [Track], keep_lyrics: bool) {
+    for track in tracks {
+        retain_events(track, |event| match event.kind {
+            TrackEventKind::SysEx(_) | TrackEventKind::Escape(_) => false,
+            TrackEventKind::Meta(MetaMessage::Lyric(_) | MetaMessage::Text(_)) if keep_lyrics => {
+                true
+            }
+            TrackEventKind::Meta(meta) => !matches!(
+                meta,
+                MetaMessage::SequencerSpecific(_)
+                    | MetaMessage::Unknown(_, _)
+                    | MetaMessage::Text(_)
+                    | MetaMessage::Lyric(_)
+                    | MetaMessage::Marker(_)
+                    | MetaMessage::CuePoint(_)
+            ),
+            TrackEventKind::Midi {
+                channel: _,
+                message: _,
+            } => true,
+        });
+    }
+}
+
+/// Replace the velocity of every sounding NoteOn event with the result of `f`.
+/// NoteOns with a velocity of 0 act as NoteOffs and are left alone.
+pub fn map_velocities(track: &mut [TrackEvent], mut f: impl FnMut(u7) -> u7) {
+    for event in track {
+        if let TrackEventKind::Midi {
+            channel: _,
+            message: MidiMessage::NoteOn { key: _, vel },
+        } = &mut event.kind
+        {
+            if *vel > 0 {
+                *vel = f(*vel);
+            }
+        }
+    }
+}
+
+/// Velocity lowered by the reduction if it is above the threshold, never going below it
+pub fn reduced_velocity(vel: u8, reduction: u8, threshold: u8) -> u8 {
+    if vel <= threshold {
+        vel
+    } else {
+        vel.saturating_sub(reduction).max(threshold)
+    }
+}
+
+/// Lower the velocities of all notes in the track which are above the threshold.
+/// Velocities never get reduced below the threshold.
+pub fn reduce_velocities(track: &mut [TrackEvent], reduction: u8, threshold: u8) {
+    map_velocities(track, |vel| {
+        reduced_velocity(vel.as_int(), reduction, threshold).into()
+    });
+}
+
+/// Scale the velocities of all notes in the track so their average or, if `peak` is set,
+/// their maximum matches the target velocity
+pub fn normalize_velocities(track: &mut [TrackEvent], target: u8, peak: bool) {
+    let mut velocities = Vec::new();
+    map_velocities(track, |vel| {
+        velocities.push(vel.as_int() as u32);
+        vel
+    });
+
+    let level = if peak {
+        velocities.iter().copied().max()
+    } else {
+        (!velocities.is_empty()).then(|| velocities.iter().sum::<u32>() / velocities.len() as u32)
+    };
+    let Some(level) = level.filter(|level| *level > 0) else {
+        return;
+    };
+
+    map_velocities(track, |vel| {
+        let vel = (vel.as_int() as u32 * target as u32 + level / 2) / level;
+        (vel.clamp(1, u7::max_value().as_int() as u32) as u8).into()
+    });
+}
+
+/// Compress the dynamic range of all notes in the track toward a target velocity.
+/// The distance of each velocity from the target is divided by the ratio before the makeup
+/// gain is added, so quiet notes get louder and loud notes get quieter.
+pub fn compress_velocities(track: &mut [TrackEvent], target: u8, ratio: u8, makeup: i8) {
+    map_velocities(track, |vel| {
+        compressed_velocity(vel.as_int(), target, ratio, makeup).into()
+    });
+}
+
+/// Velocity compressed toward the target as done by [compress_velocities]
+pub fn compressed_velocity(vel: u8, target: u8, ratio: u8, makeup: i8) -> u8 {
+    let ratio = ratio.max(1) as i16;
+    let vel = target as i16 + (vel as i16 - target as i16) / ratio + makeup as i16;
+    // Compressing should never silence a note
+    vel.clamp(1, u7::max_value().as_int() as i16) as u8
+}
+
+/// Raise the velocities of all notes in the track, saturating at the maximum velocity
+pub fn boost_velocities(track: &mut [TrackEvent], boost: u8) {
+    map_velocities(track, |vel| {
+        vel.as_int()
+            .saturating_add(boost)
+            .min(u7::max_value().as_int())
+            .into()
+    });
+}
+
+/// Small seedable pseudo random number generator (SplitMix64) so humanized files can be reproduced
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random number between `-range` and `range` inclusive
+    fn offset(&mut self, range: u8) -> i16 {
+        (self.next_u64() % (2 * range as u64 + 1)) as i16 - range as i16
+    }
+}
+
+/// Randomly vary the velocities of all notes by up to `range` in either direction.
+/// Notes never get silenced by this.
+pub fn humanize_velocities(track: &mut [TrackEvent], range: u8, rng: &mut Rng) {
+    map_velocities(track, |vel| {
+        let vel = vel.as_int() as i16 + rng.offset(range);
+        (vel.clamp(1, u7::max_value().as_int() as i16) as u8).into()
+    });
+}
+
+/// Scale a 7-bit value by a percentage
+pub fn scale_u7(value: u7, percent: u8) -> u7 {
+    ((value.as_int() as u16 * percent as u16 / 100).min(u7::max_value().as_int() as u16) as u8)
+        .into()
+}
+
+/// Channels the track sends channel messages on
+pub fn used_channels(track: &[TrackEvent]) -> [bool; 16] {
+    let mut channels = [false; 16];
+    for event in track {
+        if let TrackEventKind::Midi {
+            channel,
+            message: _,
+        } = event.kind
+        {
+            channels[channel.as_int() as usize] = true;
+        }
+    }
+    channels
+}
+
+/// Insert a message at the start of the track for each of the given channels
+fn insert_message(track: &mut Vec<TrackEvent>, channels: [bool; 16], message: MidiMessage) {
+    for channel in (0..16).rev() {
+        if channels[channel] {
+            track.insert(
+                0,
+                TrackEvent {
+                    delta: 0.into(),
+                    kind: TrackEventKind::Midi {
+                        channel: (channel as u8).into(),
+                        message,
+                    },
+                },
+            );
+        }
+    }
+}
+
+/// Insert a controller change at the start of the track for each of the given channels
+fn insert_controller(track: &mut Vec<TrackEvent>, channels: [bool; 16], controller: u8, value: u7) {
+    insert_message(
+        track,
+        channels,
+        MidiMessage::Controller {
+            controller: controller.into(),
+            value,
+        },
+    );
+}
+
+/// Scale the Channel Volume (CC7) and Expression (CC11) controllers of the track.
+/// Channels which never set their volume get an initial CC7 so they are turned down as well.
+pub fn scale_volume_controllers(track: &mut Vec<TrackEvent>, percent: u8) {
+    let mut missing_volume = used_channels(track);
+
+    for event in track.iter_mut() {
+        if let TrackEventKind::Midi {
+            channel,
+            message: MidiMessage::Controller { controller, value },
+        } = &mut event.kind
+        {
+            if *controller == CHANNEL_VOLUME {
+                missing_volume[channel.as_int() as usize] = false;
+                *value = scale_u7(*value, percent);
+            } else if *controller == EXPRESSION {
+                *value = scale_u7(*value, percent);
+            }
+        }
+    }
+
+    insert_controller(
+        track,
+        missing_volume,
+        CHANNEL_VOLUME,
+        scale_u7(DEFAULT_CHANNEL_VOLUME.into(), percent),
+    );
+}
+
+/// Pan every channel of the track to the given position, overriding any existing Pan (CC10)
+pub fn set_pan(track: &mut Vec<TrackEvent>, pan: u7) {
+    let channels = used_channels(track);
+
+    for event in track.iter_mut() {
+        if let TrackEventKind::Midi {
+            channel: _,
+            message: MidiMessage::Controller { controller, value },
+        } = &mut event.kind
+        {
+            if *controller == PAN {
+                *value = pan;
+            }
+        }
+    }
+
+    insert_controller(track, channels, PAN, pan);
+}
+
+/// Scale the pressure of all polyphonic and channel aftertouch messages in the track
+pub fn scale_aftertouch(track: &mut [TrackEvent], percent: u8) {
+    for event in track {
+        if let TrackEventKind::Midi {
+            channel: _,
+            message:
+                MidiMessage::Aftertouch { key: _, vel } | MidiMessage::ChannelAftertouch { vel },
+        } = &mut event.kind
+        {
+            *vel = scale_u7(*vel, percent);
+        }
+    }
+}
+
+/// Switch every melodic channel of the track to the given program, overriding any existing
+/// ProgramChange. The percussion channel is left alone as its programs select drum kits.
+pub fn set_program(track: &mut Vec<TrackEvent>, program: u7) {
+    let mut channels = used_channels(track);
+    channels[gm::PERCUSSION_CHANNEL as usize] = false;
+
+    for event in track.iter_mut() {
+        if let TrackEventKind::Midi {
+            channel,
+            message: MidiMessage::ProgramChange { program: current },
+        } = &mut event.kind
+        {
+            if *channel != gm::PERCUSSION_CHANNEL {
+                *current = program;
+            }
+        }
+    }
+
+    insert_message(track, channels, MidiMessage::ProgramChange { program });
+}
+
+/// Scale the tempo of the whole file to the given percentage.
+/// Files without a Tempo event get one at the start of the first track.
+/// A percentage of 0 is taken as 1, the slowest tempo there is.
+pub fn scale_tempo(tracks: &mut [Track], percent: u8) {
+    let scale = |tempo: u32| -> u24 {
+        let tempo = tempo as u64 * 100 / percent.max(1) as u64;
+        (tempo.min(u24::max_value().as_int() as u64) as u32).into()
+    };
+
+    let mut has_tempo = false;
+    for event in tracks.iter_mut().flatten() {
+        if let TrackEventKind::Meta(MetaMessage::Tempo(tempo)) = &mut event.kind {
+            has_tempo = true;
+            *tempo = scale(tempo.as_int());
+        }
+    }
+
+    if let (false, Some(first_track)) = (has_tempo, tracks.first_mut()) {
+        first_track.insert(
+            0,
+            TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Meta(MetaMessage::Tempo(scale(timing::DEFAULT_TEMPO))),
+            },
+        );
+    }
+}
+
+/// Transpose all notes by the given number of semitones, clamping them to the valid key range.
+/// The percussion channel is left alone as its keys select instruments rather than pitches.
+pub fn transpose(tracks: &mut [Track], semitones: i8) {
+    for event in tracks.iter_mut().flatten() {
+        if let TrackEventKind::Midi {
+            channel,
+            message:
+                MidiMessage::NoteOn { key, vel: _ }
+                | MidiMessage::NoteOff { key, vel: _ }
+                | MidiMessage::Aftertouch { key, vel: _ },
+        } = &mut event.kind
+        {
+            if *channel != gm::PERCUSSION_CHANNEL {
+                let transposed = key.as_int() as i16 + semitones as i16;
+                *key = (transposed.clamp(0, u7::max_value().as_int() as i16) as u8).into();
+            }
+        }
+    }
+}
+
+/// Whether an event sets up the state of the playback rather than being part of the music
+/// itself, so it has to be kept when cutting away the start of a track
+fn is_setup_event(kind: &TrackEventKind) -> bool {
+    match kind {
+        TrackEventKind::Midi {
+            channel: _,
+            message,
+        } => matches!(
+            message,
+            MidiMessage::Controller { .. }
+                | MidiMessage::ProgramChange { .. }
+                | MidiMessage::PitchBend { .. }
+        ),
+        TrackEventKind::Meta(meta) => !matches!(
+            meta,
+            MetaMessage::Lyric(_)
+                | MetaMessage::Marker(_)
+                | MetaMessage::CuePoint(_)
+                | MetaMessage::EndOfTrack
+        ),
+        TrackEventKind::SysEx(_) | TrackEventKind::Escape(_) => true,
+    }
+}
+
+/// Cut all tracks down to the ticks between `start` and `end`.
+/// Setup events before the range are moved to its start, notes still sounding at the end of
+/// the range get stopped there and NoteOffs of notes started before the range are dropped.
+pub fn trim(tracks: &mut [Track], start: Tick, end: Tick) {
+    for track in tracks.iter_mut() {
+        let mut trimmed: Vec<(Tick, TrackEvent)> = Vec::with_capacity(track.len());
+        let mut sounding = [[0u16; 128]; 16];
+        let mut end_of_track = end;
+
+        for (tick, event) in absolute_ticks(track) {
+            match event.kind {
+                TrackEventKind::Meta(MetaMessage::EndOfTrack) => {
+                    end_of_track = end_of_track.min(tick);
+                }
+                kind if tick < start => {
+                    if is_setup_event(&kind) {
+                        trimmed.push((start, *event));
+                    }
+                }
+                _ if tick >= end => {}
+                TrackEventKind::Midi {
+                    channel,
+                    message: MidiMessage::NoteOn { key, vel },
+                } if vel > 0 => {
+                    sounding[channel.as_int() as usize][key.as_int() as usize] += 1;
+                    trimmed.push((tick, *event));
+                }
+                TrackEventKind::Midi {
+                    channel,
+                    message:
+                        MidiMessage::NoteOn { key, vel: _ } | MidiMessage::NoteOff { key, vel: _ },
+                } => {
+                    let count = &mut sounding[channel.as_int() as usize][key.as_int() as usize];
+                    if *count > 0 {
+                        *count -= 1;
+                        trimmed.push((tick, *event));
+                    }
+                }
+                _ => trimmed.push((tick, *event)),
+            }
+        }
+
+        for (channel, keys) in sounding.iter().enumerate() {
+            for (key, &count) in keys.iter().enumerate() {
+                for _ in 0..count {
+                    trimmed.push((
+                        end,
+                        TrackEvent {
+                            delta: 0.into(),
+                            kind: TrackEventKind::Midi {
+                                channel: (channel as u8).into(),
+                                message: MidiMessage::NoteOff {
+                                    key: (key as u8).into(),
+                                    vel: 0.into(),
+                                },
+                            },
+                        },
+                    ));
+                }
+            }
+        }
+
+        let last_tick = trimmed.last().map_or(start, |(tick, _)| *tick);
+        trimmed.push((
+            end_of_track.max(last_tick),
+            TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+            },
+        ));
+
+        let mut previous_tick = start;
+        *track = trimmed
+            .into_iter()
+            .map(|(tick, mut event)| {
+                let delta = (tick - previous_tick).min(u28::max_value().as_int() as Tick);
+                event.delta = (delta as u32).into();
+                previous_tick = tick;
+                event
+            })
+            .collect();
+    }
+}
+
+/// Merge all tracks into a single track with their events interleaved by time.
+/// Events at the same tick keep the order of their tracks. Only the name of the first track is
+/// kept and a single EndOfTrack ends the merged track. The stamps of the original velocities are
+/// left out, as they only fit their own tracks, see [stamp::merge].
+pub fn merge_tracks<'a>(tracks: &[impl AsRef<Track<'a>>]) -> Track<'a> {
+    let mut events: Vec<(Tick, TrackEvent<'a>)> = tracks
+        .iter()
+        .enumerate()
+        .flat_map(|(index, track)| {
+            absolute_ticks(track.as_ref()).filter_map(move |(tick, event)| match event.kind {
+                TrackEventKind::Meta(MetaMessage::EndOfTrack) => None,
+                TrackEventKind::Meta(MetaMessage::TrackName(_)) if index > 0 => None,
+                kind if stamp::is_stamp(&kind) => None,
+                _ => Some((tick, *event)),
+            })
+        })
+        .collect();
+    // Stable, so events at the same tick keep their order
+    events.sort_by_key(|(tick, _)| *tick);
+
+    let end = tracks
+        .iter()
+        .map(|track| timing::track_end_tick(track.as_ref()))
+        .max()
+        .unwrap_or(0);
+    events.push((
+        end,
+        TrackEvent {
+            delta: 0.into(),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        },
+    ));
+
+    let mut previous_tick = 0;
+    events
+        .into_iter()
+        .map(|(tick, mut event)| {
+            let delta = (tick - previous_tick).min(u28::max_value().as_int() as Tick);
+            event.delta = (delta as u32).into();
+            previous_tick = tick;
+            event
+        })
+        .collect()
+}
+
+/// Set the name of the track, replacing its existing TrackName event
+pub fn set_track_name<'a>(track: &mut Vec<TrackEvent<'a>>, name: &'a [u8]) {
+    for event in track.iter_mut() {
+        if let TrackEventKind::Meta(MetaMessage::TrackName(current)) = &mut event.kind {
+            *current = name;
+            return;
+        }
+    }
+
+    track.insert(
+        0,
+        TrackEvent {
+            delta: 0.into(),
+            kind: TrackEventKind::Meta(MetaMessage::TrackName(name)),
+        },
+    );
+}
+
+/// Key of a note event
+fn note_key(kind: &TrackEventKind) -> Option<u7> {
+    match kind {
+        TrackEventKind::Midi {
+            channel: _,
+            message:
+                MidiMessage::NoteOn { key, vel: _ }
+                | MidiMessage::NoteOff { key, vel: _ }
+                | MidiMessage::Aftertouch { key, vel: _ },
+        } => Some(*key),
+        _ => None,
+    }
+}
+
+/// Replace a track with two tracks, the first playing the notes at or above the split key and
+/// the second the notes below it. All other events end up in both tracks.
+pub fn split_track_by_key<'a>(
+    tracks: &mut Vec<Track<'a>>,
+    index: usize,
+    split_key: u7,
+    names: [&'a [u8]; 2],
+) {
+    let mut upper = tracks.remove(index);
+    let mut lower = upper.clone();
+
+    retain_events(&mut upper, |event| {
+        note_key(&event.kind).is_none_or(|key| key >= split_key)
+    });
+    retain_events(&mut lower, |event| {
+        note_key(&event.kind).is_none_or(|key| key < split_key)
+    });
+    set_track_name(&mut upper, names[0]);
+    set_track_name(&mut lower, names[1]);
+
+    tracks.insert(index, lower);
+    tracks.insert(index, upper);
+}
+
+/// Which of the two voices split out of a track an event belongs to
+#[derive(Clone, Copy, PartialEq)]
+enum Voice {
+    Upper,
+    Lower,
+    Both,
+}
+
+/// Replace a track with two tracks, the first playing the upper and the second the lower notes
+/// of the chords in it. Notes starting at the same time are ordered by pitch with the upper half
+/// going to the first track, single notes are sung in unison and end up in both.
+pub fn split_track_into_voices<'a>(
+    tracks: &mut Vec<Track<'a>>,
+    index: usize,
+    names: [&'a [u8]; 2],
+) {
+    let track = tracks.remove(index);
+
+    // Keys starting at each onset
+    let mut onsets: HashMap<Tick, Vec<u7>> = HashMap::new();
+    for (tick, event) in absolute_ticks(&track) {
+        if let TrackEventKind::Midi {
+            channel: _,
+            message: MidiMessage::NoteOn { key, vel },
+        } = event.kind
+        {
+            if vel > 0 {
+                onsets.entry(tick).or_default().push(key);
+            }
+        }
+    }
+    for keys in onsets.values_mut() {
+        keys.sort();
+        keys.dedup();
+    }
+
+    // Voices of the notes which are currently sounding so their NoteOffs follow them
+    let mut sounding: HashMap<(u8, u7), VecDeque<Voice>> = HashMap::new();
+    let voices: Vec<Voice> = absolute_ticks(&track)
+        .map(|(tick, event)| match event.kind {
+            TrackEventKind::Midi {
+                channel,
+                message: MidiMessage::NoteOn { key, vel },
+            } if vel > 0 => {
+                let keys = &onsets[&tick];
+                let voice = if keys.len() == 1 {
+                    Voice::Both
+                } else if keys.iter().position(|k| *k == key).unwrap_or(0) >= keys.len() / 2 {
+                    Voice::Upper
+                } else {
+                    Voice::Lower
+                };
+                sounding
+                    .entry((channel.as_int(), key))
+                    .or_default()
+                    .push_back(voice);
+                voice
+            }
+            TrackEventKind::Midi {
+                channel,
+                message: MidiMessage::NoteOn { key, vel: _ } | MidiMessage::NoteOff { key, vel: _ },
+            } => sounding
+                .get_mut(&(channel.as_int(), key))
+                .and_then(VecDeque::pop_front)
+                .unwrap_or(Voice::Both),
+            TrackEventKind::Midi {
+                channel,
+                message: MidiMessage::Aftertouch { key, vel: _ },
+            } => sounding
+                .get(&(channel.as_int(), key))
+                .and_then(|voices| voices.front().copied())
+                .unwrap_or(Voice::Both),
+            _ => Voice::Both,
+        })
+        .collect();
+
+    let voice_track = |excluded: Voice, name: &'a [u8]| {
+        let mut voice_track = track.clone();
+        let mut voices = voices.iter();
+        retain_events(&mut voice_track, |_| voices.next() != Some(&excluded));
+        set_track_name(&mut voice_track, name);
+        voice_track
+    };
+    let upper = voice_track(Voice::Lower, names[0]);
+    let lower = voice_track(Voice::Upper, names[1]);
+
+    tracks.insert(index, lower);
+    tracks.insert(index, upper);
+}
+
+/// Replace a track with one track per given channel, each only playing the messages of its
+/// channel. All other events end up in every track.
+pub fn split_track_by_channel<'a>(
+    tracks: &mut Vec<Track<'a>>,
+    index: usize,
+    channels: &[u8],
+    names: &[&'a [u8]],
+) {
+    let track = tracks.remove(index);
+
+    for (&channel, &name) in channels.iter().zip(names).rev() {
+        let mut channel_track = track.clone();
+        retain_events(&mut channel_track, |event| match event.kind {
+            TrackEventKind::Midi {
+                channel: event_channel,
+                message: _,
+            } => event_channel == channel,
+            _ => true,
+        });
+        set_track_name(&mut channel_track, name);
+        tracks.insert(index, channel_track);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(delta: u32, key: u8, vel: u8) -> TrackEvent<'static> {
+        TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Midi {
+                channel: 0.into(),
+                message: MidiMessage::NoteOn {
+                    key: key.into(),
+                    vel: vel.into(),
+                },
+            },
+        }
+    }
+
+    fn meta(delta: u32, message: MetaMessage<'static>) -> TrackEvent<'static> {
+        TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Meta(message),
+        }
+    }
+
+    fn tempo(tracks: &[Track]) -> Vec<u32> {
+        tracks
+            .iter()
+            .flatten()
+            .filter_map(|event| match event.kind {
+                TrackEventKind::Meta(MetaMessage::Tempo(tempo)) => Some(tempo.as_int()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scale_tempo_scales_every_tempo() {
+        let mut tracks = vec![vec![
+            meta(0, MetaMessage::Tempo(500_000.into())),
+            meta(480, MetaMessage::Tempo(400_000.into())),
+        ]];
+        scale_tempo(&mut tracks, 50);
+        assert_eq!(tempo(&tracks), [1_000_000, 800_000]);
+        scale_tempo(&mut tracks, 200);
+        assert_eq!(tempo(&tracks), [500_000, 400_000]);
+    }
+
+    #[test]
+    fn scale_tempo_adds_missing_tempo() {
+        let mut tracks = vec![vec![note(0, 60, 64)]];
+        scale_tempo(&mut tracks, 200);
+        assert_eq!(tempo(&tracks), [timing::DEFAULT_TEMPO / 2]);
+    }
+
+    #[test]
+    fn scale_tempo_clamps_to_slowest_tempo() {
+        let mut tracks = vec![vec![meta(0, MetaMessage::Tempo(500_000.into()))]];
+        scale_tempo(&mut tracks, 0);
+        assert_eq!(tempo(&tracks), [u24::max_value().as_int()]);
+    }
+
+    #[test]
+    fn merge_tracks_interleaves_by_time() {
+        let first = vec![
+            meta(0, MetaMessage::TrackName(b"First")),
+            note(0, 60, 10),
+            note(480, 60, 0),
+            meta(0, MetaMessage::EndOfTrack),
+        ];
+        let second = vec![
+            meta(0, MetaMessage::TrackName(b"Second")),
+            note(240, 64, 20),
+            note(480, 64, 0),
+            meta(0, MetaMessage::EndOfTrack),
+        ];
+        let merged = merge_tracks(&[first, second]);
+        assert_eq!(
+            merged,
+            [
+                meta(0, MetaMessage::TrackName(b"First")),
+                note(0, 60, 10),
+                note(240, 64, 20),
+                note(240, 60, 0),
+                note(240, 64, 0),
+                meta(0, MetaMessage::EndOfTrack),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_tracks_leaves_out_stamps() {
+        let mut track = vec![note(0, 60, 10), meta(0, MetaMessage::EndOfTrack)];
+        let stamp = stamp::create(&track);
+        stamp::insert(&mut track, &stamp);
+        let merged = merge_tracks(&[track]);
+        assert!(!merged.iter().any(|event| stamp::is_stamp(&event.kind)));
+    }
+}
@@ -0,0 +1,216 @@
+use std::io::Write;
+
+use midly::{EventIter, Track, TrackEvent};
+
+use crate::{notes, SplitError};
+
+/// Notes of a track as they are compared
+fn note_pairs(track: &[TrackEvent]) -> Vec<(u64, u64, u8, u8, u8)> {
+    notes::notes(track)
+        .iter()
+        .map(|note| (note.start, note.end, note.channel, note.key, note.velocity))
+        .collect()
+}
+
+/// Writer which checks a generated MIDI file while it is written, reading every chunk back as
+/// soon as it is complete and comparing it with the track it was written from. Only the chunk
+/// being written is held, so files can be checked while they are streamed into an archive, and
+/// files which would not play as intended are reported rather than handed out.
+pub struct Verifier<'t, 'a, W> {
+    out: W,
+    name: &'t str,
+    tracks: &'t [&'t Track<'a>],
+    /// Written bytes of the chunk which is not complete yet
+    chunk: Vec<u8>,
+    /// Number of tracks read back so far
+    read: usize,
+    /// First difference found
+    error: Option<String>,
+}
+
+impl<'t, 'a, W: Write> Verifier<'t, 'a, W> {
+    pub fn new(name: &'t str, tracks: &'t [&'t Track<'a>], out: W) -> Self {
+        Verifier {
+            out,
+            name,
+            tracks,
+            chunk: Vec::new(),
+            read: 0,
+            error: None,
+        }
+    }
+
+    /// Check the chunks which have been written completely
+    fn read_chunks(&mut self) {
+        while self.error.is_none() && self.chunk.len() >= 8 {
+            let length =
+                u32::from_be_bytes([self.chunk[4], self.chunk[5], self.chunk[6], self.chunk[7]])
+                    as usize;
+            let Some(end) = 8usize
+                .checked_add(length)
+                .filter(|&end| end <= self.chunk.len())
+            else {
+                break;
+            };
+            let chunk: Vec<u8> = self.chunk.drain(..end).collect();
+            self.error = self.check_chunk(&chunk[..4], &chunk[8..]).err();
+        }
+    }
+
+    fn check_chunk(&mut self, id: &[u8], body: &[u8]) -> Result<(), String> {
+        match id {
+            b"MThd" => {
+                let track_count = body
+                    .get(2..4)
+                    .map(|count| u16::from_be_bytes([count[0], count[1]]));
+                if track_count.map(usize::from) != Some(self.tracks.len()) {
+                    return Err(format!(
+                        "its header does not give {} tracks",
+                        self.tracks.len()
+                    ));
+                }
+            }
+            b"MTrk" => {
+                let index = self.read;
+                self.read += 1;
+                let written = self
+                    .tracks
+                    .get(index)
+                    .ok_or_else(|| format!("it has more than {} tracks", self.tracks.len()))?;
+                let read = EventIter::new(body)
+                    .collect::<Result<Vec<TrackEvent>, _>>()
+                    .map_err(|e| format!("track {} cannot be read: {}", index, e))?;
+                if read.len() != written.len() {
+                    return Err(format!(
+                        "track {} has {} events instead of {}",
+                        index,
+                        read.len(),
+                        written.len()
+                    ));
+                }
+                if note_pairs(&read) != note_pairs(written) {
+                    return Err(format!(
+                        "the notes of track {} do not start and end as written",
+                        index
+                    ));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Finish the check once the whole file has been written
+    pub fn finish(self) -> Result<(), SplitError> {
+        let failed = |detail: String| SplitError::InvalidOutput {
+            output: self.name.to_string(),
+            detail,
+        };
+
+        if let Some(detail) = self.error {
+            return Err(failed(detail));
+        }
+        if !self.chunk.is_empty() {
+            return Err(failed("it ends in the middle of a chunk".to_string()));
+        }
+        if self.read != self.tracks.len() {
+            return Err(failed(format!(
+                "it has {} tracks instead of {}",
+                self.read,
+                self.tracks.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Verifier<'_, '_, W> {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        let written = self.out.write(data)?;
+        self.chunk.extend_from_slice(&data[..written]);
+        self.read_chunks();
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use midly::{Format, Header, MetaMessage, MidiMessage, Timing, TrackEventKind};
+
+    use super::*;
+
+    const HEADER: Header = Header {
+        format: Format::Parallel,
+        timing: Timing::Metrical(midly::num::u15::new(480)),
+    };
+
+    fn track(key: u8) -> Track<'static> {
+        let note = |delta: u32, vel: u8| TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Midi {
+                channel: 0.into(),
+                message: MidiMessage::NoteOn {
+                    key: key.into(),
+                    vel: vel.into(),
+                },
+            },
+        };
+        vec![
+            note(0, 64),
+            note(480, 0),
+            TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+            },
+        ]
+    }
+
+    fn write(tracks: &[&Track], out: impl Write) {
+        midly::write_std(&HEADER, tracks.iter().map(|track| track.iter()), out).unwrap();
+    }
+
+    #[test]
+    fn written_file_passes_unchanged() {
+        let (first, second) = (track(60), track(64));
+        let tracks = [&first, &second];
+        let mut expected = Vec::new();
+        write(&tracks, &mut expected);
+
+        let mut data = Vec::new();
+        let mut verifier = Verifier::new("song.mid", &tracks, &mut data);
+        write(&tracks, &mut verifier);
+        assert_eq!(verifier.finish(), Ok(()));
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn other_notes_are_reported() {
+        let (written, expected) = (track(60), track(64));
+        let tracks = [&expected];
+        let mut verifier = Verifier::new("song.mid", &tracks, std::io::sink());
+        write(&[&written], &mut verifier);
+        assert!(matches!(
+            verifier.finish(),
+            Err(SplitError::InvalidOutput { .. })
+        ));
+    }
+
+    #[test]
+    fn missing_tracks_are_reported() {
+        let track = track(60);
+        let tracks = [&track, &track];
+        let mut verifier = Verifier::new("song.mid", &tracks, std::io::sink());
+        write(&[&track], &mut verifier);
+        assert!(verifier.finish().is_err());
+
+        let mut data = Vec::new();
+        write(&[&track], &mut data);
+        let mut verifier = Verifier::new("song.mid", &tracks[..1], std::io::sink());
+        verifier.write_all(&data[..data.len() - 1]).unwrap();
+        assert!(verifier.finish().is_err());
+    }
+}
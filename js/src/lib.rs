@@ -0,0 +1,134 @@
+//! The processing of MIDI Splitter for other web apps, without the user interface.
+//! `wasm-pack build js` creates the package including its TypeScript types.
+
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use midi_splitter_core::{preset, validate_name_template, ProcessOptions, SplitError};
+use wasm_bindgen::prelude::*;
+
+/// Name of the file when the options do not give one
+const DEFAULT_FILE_NAME: &str = "song.mid";
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &'static str = r#"
+/** Options named as in the settings saved by MIDI Splitter. Missing options keep their default. */
+export interface SplitOptions {
+  /** Name of the MIDI file, which the names of the generated files are built from. "song.mid" by default. */
+  file_name?: string;
+  mode?: "Emphasize" | "Solo" | "MinusOne";
+  normalize?: { target: number; peak: boolean } | null;
+  velocity_mapping?:
+    | { Reduce: { amount: number; threshold: number } }
+    | { Compress: { target: number; ratio: number; makeup: number } };
+  velocity_boost?: number;
+  dynamics_mode?: "Velocity" | "Controllers" | "Both";
+  volume_scale?: number;
+  aftertouch_scale?: number | null;
+  practice_panning?: boolean;
+  keep_drums?: boolean;
+  background_program?: number | null;
+  humanize?: { range: number; seed: number } | null;
+  tempo_variants?: number[];
+  transpose?: number;
+  bar_range?: [number, number] | null;
+  hand_split?: { track: number; key: number } | null;
+  voice_split?: number | null;
+  split_channels?: boolean;
+  stamp_original_velocities?: boolean;
+  click_track?: { downbeat_velocity: number; beat_velocity: number } | null;
+  strip_non_essential_events?: boolean;
+  skip_empty_tracks?: boolean;
+  export_lyrics?: boolean;
+  name_template?: string;
+  all_tracks_suffix?: string | null;
+  include_original?: boolean;
+  manifest?: "Json" | "Csv" | null;
+  archive_format?: "Zip" | "TarGz";
+  export_musicxml?: boolean;
+  render_audio?: boolean;
+  export_note_csv?: boolean;
+  export_abc?: boolean;
+  reproducible_archive?: boolean;
+  single_track_output?: boolean;
+  number_files?: boolean;
+  cue_sheet?: boolean;
+  stamp_provenance?: boolean;
+  lenient_parsing?: boolean;
+  repair?: boolean;
+  /** Sizes beyond which files are not processed, or null to process files of any size */
+  limits?: {
+    max_bytes: number;
+    max_tracks: number;
+    max_events: number;
+    max_seconds: number;
+    max_beats: number;
+  } | null;
+}
+
+/** File created from the MIDI file */
+export interface SplitFile {
+  name: string;
+  bytes: Uint8Array;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SplitOptions")]
+    pub type SplitOptions;
+
+    #[wasm_bindgen(typescript_type = "SplitFile[]")]
+    pub type SplitFiles;
+}
+
+/// Read the options given from JavaScript, returning the name of the file with them
+fn options(options: Option<SplitOptions>) -> Result<(String, ProcessOptions), JsError> {
+    let mut changes = match options {
+        Some(options) => match serde_wasm_bindgen::from_value(options.into())? {
+            serde_json::Value::Object(changes) => changes,
+            serde_json::Value::Null => serde_json::Map::new(),
+            _ => return Err(JsError::new("The options are not an object")),
+        },
+        None => serde_json::Map::new(),
+    };
+    let file_name = match changes.remove("file_name") {
+        Some(serde_json::Value::String(file_name)) => file_name,
+        Some(_) => return Err(JsError::new("file_name is not a string")),
+        None => DEFAULT_FILE_NAME.to_string(),
+    };
+
+    let options = preset::apply(&ProcessOptions::default(), changes)
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    validate_name_template(&options.name_template).map_err(|e| JsError::new(&e))?;
+    Ok((file_name, options))
+}
+
+/// Error thrown for a failure of the processing, with its code as the code property
+fn split_error(error: SplitError) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    let _ = Reflect::set(&js_error, &"code".into(), &error.code().into());
+    js_error.into()
+}
+
+/// Create the files for the tracks of a MIDI file as MIDI Splitter does, without bundling them
+/// into an archive
+#[wasm_bindgen(js_name = splitMidi)]
+pub fn split_midi(bytes: &[u8], options: Option<SplitOptions>) -> Result<SplitFiles, JsValue> {
+    let (file_name, options) = self::options(options)?;
+    let outputs = midi_splitter_core::process(&file_name, bytes, &options).map_err(split_error)?;
+
+    let files: Array = outputs
+        .files
+        .iter()
+        .map(|file| {
+            let object = Object::new();
+            let _ = Reflect::set(&object, &"name".into(), &file.name.as_str().into());
+            let _ = Reflect::set(
+                &object,
+                &"bytes".into(),
+                &Uint8Array::from(file.data.as_slice()),
+            );
+            JsValue::from(object)
+        })
+        .collect();
+    Ok(files.unchecked_into())
+}
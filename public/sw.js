@@ -0,0 +1,97 @@
+// Keeps the page and the files trunk builds for it, so the app also opens without a network.
+// The page is served from the cache and checked for a new version in the background; if there
+// is one, it is cached together with its files and the open pages are told to reload.
+
+const CACHE = "midi_splitter";
+const PAGE = "./";
+
+// Files the page refers to, which trunk names after their hash
+function referencedFiles(page) {
+	const files = [...page.matchAll(/(?:href|src)="([^"]+)"/g)].map((match) => match[1]);
+	return files.filter((file) => !file.startsWith("http") && !file.startsWith("data:"));
+}
+
+// Cache the page together with its files, dropping the files of older versions
+async function storePage(cache, response) {
+	const page = await response.clone().text();
+	const files = referencedFiles(page).map((file) => new URL(file, self.registration.scope).href);
+	await cache.addAll(files);
+	await cache.put(PAGE, response);
+	// Files which are not referenced, such as the worker, are cached again when they are loaded
+	const keep = new Set([...files, new URL(PAGE, self.registration.scope).href]);
+	for (const request of await cache.keys()) {
+		if (!keep.has(request.url)) {
+			await cache.delete(request);
+		}
+	}
+}
+
+self.addEventListener("install", (event) => {
+	event.waitUntil(
+		(async () => {
+			const cache = await caches.open(CACHE);
+			await storePage(cache, await fetch(PAGE, { cache: "no-cache" }));
+			await self.skipWaiting();
+		})(),
+	);
+});
+
+self.addEventListener("activate", (event) => {
+	event.waitUntil(self.clients.claim());
+});
+
+// Fetch the page again and tell the open pages if it changed
+async function checkForUpdate(cache) {
+	const cached = await cache.match(PAGE);
+	const response = await fetch(PAGE, { cache: "no-cache" });
+	if (!response.ok) {
+		return;
+	}
+	const page = await response.clone().text();
+	if (cached && page === (await cached.text())) {
+		return;
+	}
+	await storePage(cache, response);
+	for (const client of await self.clients.matchAll({ type: "window" })) {
+		client.postMessage({ type: "update-available" });
+	}
+}
+
+self.addEventListener("fetch", (event) => {
+	const request = event.request;
+	if (request.method !== "GET" || new URL(request.url).origin !== self.location.origin) {
+		return;
+	}
+
+	if (request.mode === "navigate") {
+		event.respondWith(
+			(async () => {
+				const cache = await caches.open(CACHE);
+				const cached = await cache.match(PAGE);
+				if (cached) {
+					// Without a network the check fails and the cached version stays
+					event.waitUntil(checkForUpdate(cache).catch(() => {}));
+					return cached;
+				}
+				return fetch(request);
+			})(),
+		);
+		return;
+	}
+
+	// Everything else is cached the first time it is loaded, e.g. the worker
+	event.respondWith(
+		(async () => {
+			const cache = await caches.open(CACHE);
+			const cached = await cache.match(request);
+			if (cached) {
+				return cached;
+			}
+			const response = await fetch(request);
+			if (response.ok) {
+				await cache.put(request, response.clone());
+			}
+			return response;
+		})(),
+	);
+});
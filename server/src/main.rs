@@ -0,0 +1,194 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::env;
+
+    use axum::{
+        extract::{DefaultBodyLimit, Multipart},
+        http::{header, StatusCode},
+        response::{IntoResponse, Response},
+        routing::post,
+        Json, Router,
+    };
+    use midi_splitter_core::{
+        preset, process_all, validate_name_template, File, Limits, ProcessOptions, SplitError,
+    };
+    use serde_json::json;
+
+    const USAGE: &str = "\
+Usage: midi-splitter-server [--listen <ADDRESS>]
+
+Serves POST /split, which takes a multipart form with one or more MIDI files in fields named
+file and optionally the settings as JSON in a field named options, named as in the settings
+saved by the web app. It responds with the archive of the created files, or with an error as
+JSON holding a code and a message.
+
+Options:
+  -l, --listen <ADDRESS>  Address to listen on, 127.0.0.1:8080 by default
+  -h, --help              Print this help
+";
+
+    /// Largest request accepted, which is far more than any MIDI file needs
+    const MAX_REQUEST_SIZE: usize = 64 * 1024 * 1024;
+
+    /// Error answering a request, with the JSON sent back to the client
+    struct ErrorResponse(StatusCode, serde_json::Value);
+
+    impl ErrorResponse {
+        fn bad_request(message: impl ToString) -> ErrorResponse {
+            ErrorResponse(
+                StatusCode::BAD_REQUEST,
+                json!({ "code": "bad_request", "message": message.to_string() }),
+            )
+        }
+    }
+
+    /// Failures of the processing keep their fields besides the code and the message
+    impl From<SplitError> for ErrorResponse {
+        fn from(error: SplitError) -> ErrorResponse {
+            let status = match error {
+                _ if error.is_invalid_options() => StatusCode::BAD_REQUEST,
+                SplitError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+                SplitError::ZipError { .. } | SplitError::InvalidOutput { .. } => {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+                _ => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            let mut body = serde_json::to_value(&error).unwrap_or_default();
+            body["message"] = error.to_string().into();
+            ErrorResponse(status, body)
+        }
+    }
+
+    impl IntoResponse for ErrorResponse {
+        fn into_response(self) -> Response {
+            (self.0, Json(self.1)).into_response()
+        }
+    }
+
+    /// Read the settings sent with a request, which are changes to the defaults
+    fn options(json: &str) -> Result<ProcessOptions, ErrorResponse> {
+        let changes = serde_json::from_str(json)
+            .map_err(|e| ErrorResponse::bad_request(format!("The options are not valid: {}", e)))?;
+        let options = preset::apply(&ProcessOptions::default(), changes)
+            .map_err(ErrorResponse::bad_request)?;
+        validate_name_template(&options.name_template).map_err(ErrorResponse::bad_request)?;
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Create the files for the uploaded MIDI files and send back their archive
+    async fn split(mut multipart: Multipart) -> Result<Response, ErrorResponse> {
+        let mut files = Vec::new();
+        let mut options = ProcessOptions::default();
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(ErrorResponse::bad_request)?
+        {
+            match field.name() {
+                Some("file") => {
+                    let name = field
+                        .file_name()
+                        .ok_or_else(|| ErrorResponse::bad_request("An uploaded file has no name"))?
+                        .to_string();
+                    let data = field.bytes().await.map_err(ErrorResponse::bad_request)?;
+                    files.push(File {
+                        name,
+                        data: data.to_vec(),
+                    });
+                }
+                Some("options") => {
+                    options =
+                        self::options(&field.text().await.map_err(ErrorResponse::bad_request)?)?
+                }
+                name => {
+                    return Err(ErrorResponse::bad_request(format!(
+                        "Unknown field {}",
+                        name.unwrap_or_default()
+                    )))
+                }
+            }
+        }
+        if files.is_empty() {
+            return Err(ErrorResponse::bad_request("No MIDI file was uploaded"));
+        }
+
+        // Only the archive is sent back
+        options.keep_files = false;
+        // The limits protect the server, so they are not up to the client
+        options.limits = Some(Limits::default());
+        let archive_format = options.archive_format;
+        // The processing keeps a thread busy, which must not hold up other requests
+        let outputs = tokio::task::spawn_blocking(move || process_all(files, &options))
+            .await
+            .map_err(|e| {
+                ErrorResponse(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    json!({ "code": "internal", "message": e.to_string() }),
+                )
+            })??;
+
+        // Header values are limited to ASCII
+        let file_name: String = outputs
+            .archive_name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_graphic() && c != '"' && c != '\\' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Ok((
+            [
+                (header::CONTENT_TYPE, archive_format.mime_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", file_name),
+                ),
+            ],
+            outputs.archive,
+        )
+            .into_response())
+    }
+
+    #[tokio::main]
+    pub async fn main() -> anyhow::Result<()> {
+        let mut address = "127.0.0.1:8080".to_string();
+        let mut arguments = env::args().skip(1);
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "-l" | "--listen" => {
+                    address = arguments
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("{} needs a value\n\n{}", argument, USAGE))?
+                }
+                "-h" | "--help" => {
+                    print!("{}", USAGE);
+                    return Ok(());
+                }
+                _ => anyhow::bail!("Unknown option {}\n\n{}", argument, USAGE),
+            }
+        }
+
+        let app = Router::new()
+            .route("/split", post(split))
+            .layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE));
+        let listener = tokio::net::TcpListener::bind(&address)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to listen on {}: {}", address, e))?;
+        println!("Listening on http://{}", listener.local_addr()?);
+        axum::serve(listener, app).await?;
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> anyhow::Result<()> {
+    native::main()
+}
+
+// Requests cannot be served from the browser
+#[cfg(target_arch = "wasm32")]
+fn main() {}
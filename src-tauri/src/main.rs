@@ -0,0 +1,135 @@
+// Keeps Windows from opening a console next to the window in release builds
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use midi_splitter_core::MIDI_EXTENSIONS;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+/// File exchanged with the page
+#[derive(Serialize, Deserialize)]
+struct LocalFile {
+    name: String,
+    data: Vec<u8>,
+}
+
+fn is_midi_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| MIDI_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+fn read(path: &Path) -> Result<LocalFile, String> {
+    Ok(LocalFile {
+        name: path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| format!("The name of {} is not valid UTF-8", path.display()))?
+            .to_string(),
+        data: fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+    })
+}
+
+/// Read the MIDI files of the folder and its subfolders in the order of their names
+fn read_folder(folder: &Path, files: &mut Vec<LocalFile>) -> Result<(), String> {
+    let mut entries = fs::read_dir(folder)
+        .and_then(|entries| entries.map(|entry| Ok(entry?.path())).collect())
+        .map_err(|e: std::io::Error| format!("Failed to read {}: {}", folder.display(), e))?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            read_folder(&entry, files)?;
+        } else if is_midi_file(&entry) {
+            files.push(read(&entry)?);
+        }
+    }
+    Ok(())
+}
+
+/// Let the user pick MIDI files, returning none if the dialog was closed
+#[tauri::command]
+async fn open_midi_files(app: AppHandle) -> Result<Vec<LocalFile>, String> {
+    let Some(picked) = app
+        .dialog()
+        .file()
+        .add_filter("MIDI", &MIDI_EXTENSIONS)
+        .blocking_pick_files()
+    else {
+        return Ok(Vec::new());
+    };
+    picked
+        .into_iter()
+        .map(|path| read(&path.into_path().map_err(|e| e.to_string())?))
+        .collect()
+}
+
+/// Read the dropped files, searching dropped folders for MIDI files
+#[tauri::command]
+async fn read_midi_files(paths: Vec<PathBuf>) -> Result<Vec<LocalFile>, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            read_folder(&path, &mut files)?;
+        } else if is_midi_file(&path) {
+            files.push(read(&path)?);
+        }
+    }
+    Ok(files)
+}
+
+/// Let the user save a file, returning false if the dialog was closed
+#[tauri::command]
+async fn save_file(app: AppHandle, name: String, data: Vec<u8>) -> Result<bool, String> {
+    let Some(path) = app.dialog().file().set_file_name(name).blocking_save_file() else {
+        return Ok(false);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    fs::write(&path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(true)
+}
+
+/// Let the user pick a folder and write the files into it, returning false if the dialog was
+/// closed. Names may contain the folders of songs, which get created.
+#[tauri::command]
+async fn save_to_folder(app: AppHandle, files: Vec<LocalFile>) -> Result<bool, String> {
+    let Some(folder) = app.dialog().file().blocking_pick_folder() else {
+        return Ok(false);
+    };
+    let folder = folder.into_path().map_err(|e| e.to_string())?;
+    for file in files {
+        let name = Path::new(&file.name);
+        // Files stay inside the picked folder
+        if !name
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(format!("Invalid file name {}", file.name));
+        }
+        let path = folder.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&path, file.data)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(true)
+}
+
+fn main() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_dialog::init())
+        .invoke_handler(tauri::generate_handler![
+            open_midi_files,
+            read_midi_files,
+            save_file,
+            save_to_folder
+        ])
+        .run(tauri::generate_context!())
+        .expect("Failed to run the desktop app");
+}
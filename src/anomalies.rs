@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use midi_splitter_core::timing::{self, Tick};
+use midly::{MetaMessage, MidiMessage, TrackEvent, TrackEventKind};
+
+use crate::i18n::Strings;
+
+/// Problem in an uploaded file which does not keep it from being processed
+#[derive(Clone, Copy, PartialEq)]
+pub enum Anomaly {
+    /// NoteOns which never get a NoteOff and last until the end of the track
+    UnmatchedNoteOns {
+        track: usize,
+        count: usize,
+    },
+    /// NoteOffs for keys which are not sounding
+    UnmatchedNoteOffs {
+        track: usize,
+        count: usize,
+    },
+    /// NoteOns for keys which are still sounding on the same channel
+    OverlappingNotes {
+        track: usize,
+        count: usize,
+    },
+    /// Notes ending at the same tick they start, which cannot be heard
+    ZeroLengthNotes {
+        track: usize,
+        count: usize,
+    },
+    MissingEndOfTrack {
+        track: usize,
+    },
+    /// Track with notes but no name, so its files are named after the instrument or the index
+    UnnamedTrack {
+        track: usize,
+    },
+}
+
+impl Anomaly {
+    /// Description of the problem, counting the tracks from 1
+    pub fn message(&self, text: &Strings) -> String {
+        match *self {
+            Anomaly::UnmatchedNoteOns { track, count } => {
+                (text.anomaly_unmatched_note_ons)(track + 1, count)
+            }
+            Anomaly::UnmatchedNoteOffs { track, count } => {
+                (text.anomaly_unmatched_note_offs)(track + 1, count)
+            }
+            Anomaly::OverlappingNotes { track, count } => {
+                (text.anomaly_overlapping_notes)(track + 1, count)
+            }
+            Anomaly::ZeroLengthNotes { track, count } => {
+                (text.anomaly_zero_length_notes)(track + 1, count)
+            }
+            Anomaly::MissingEndOfTrack { track } => (text.anomaly_missing_end_of_track)(track + 1),
+            Anomaly::UnnamedTrack { track } => (text.anomaly_unnamed_track)(track + 1),
+        }
+    }
+}
+
+/// Find the problems of the track with the given index
+pub fn check_track(index: usize, track: &[TrackEvent]) -> Vec<Anomaly> {
+    // Start ticks of the sounding notes per channel and key
+    let mut sounding: HashMap<(u8, u8), Vec<Tick>> = HashMap::new();
+    let mut unmatched_note_offs = 0;
+    let mut overlapping_notes = 0;
+    let mut zero_length_notes = 0;
+    let mut has_notes = false;
+    let mut has_name = false;
+
+    for (tick, event) in timing::absolute_ticks(track) {
+        match event.kind {
+            TrackEventKind::Meta(MetaMessage::TrackName(_)) => has_name = true,
+            TrackEventKind::Midi { channel, message } => {
+                let (key, note_on) = match message {
+                    MidiMessage::NoteOn { key, vel } => (key, vel > 0),
+                    MidiMessage::NoteOff { key, vel: _ } => (key, false),
+                    _ => continue,
+                };
+                let starts = sounding
+                    .entry((channel.as_int(), key.as_int()))
+                    .or_default();
+                if note_on {
+                    has_notes = true;
+                    if !starts.is_empty() {
+                        overlapping_notes += 1;
+                    }
+                    starts.push(tick);
+                } else if starts.is_empty() {
+                    unmatched_note_offs += 1;
+                } else if starts.remove(0) == tick {
+                    zero_length_notes += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    let unmatched_note_ons = sounding.values().map(Vec::len).sum();
+
+    let mut anomalies = Vec::new();
+    if unmatched_note_ons > 0 {
+        anomalies.push(Anomaly::UnmatchedNoteOns {
+            track: index,
+            count: unmatched_note_ons,
+        });
+    }
+    if unmatched_note_offs > 0 {
+        anomalies.push(Anomaly::UnmatchedNoteOffs {
+            track: index,
+            count: unmatched_note_offs,
+        });
+    }
+    if overlapping_notes > 0 {
+        anomalies.push(Anomaly::OverlappingNotes {
+            track: index,
+            count: overlapping_notes,
+        });
+    }
+    if zero_length_notes > 0 {
+        anomalies.push(Anomaly::ZeroLengthNotes {
+            track: index,
+            count: zero_length_notes,
+        });
+    }
+    let ends = matches!(
+        track.last().map(|event| event.kind),
+        Some(TrackEventKind::Meta(MetaMessage::EndOfTrack))
+    );
+    if !ends {
+        anomalies.push(Anomaly::MissingEndOfTrack { track: index });
+    }
+    if has_notes && !has_name {
+        anomalies.push(Anomaly::UnnamedTrack { track: index });
+    }
+    anomalies
+}
@@ -0,0 +1,49 @@
+//! Worker processing the uploads away from the page, so the page keeps responding while big
+//! files are split
+
+#[path = "../protocol.rs"]
+mod protocol;
+
+use midi_splitter_core::process_files;
+use protocol::{Request, Response};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{
+    js_sys,
+    wasm_bindgen::{closure::Closure, JsCast},
+    DedicatedWorkerGlobalScope, MessageEvent,
+};
+
+fn post(response: Response) {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let (message, transfer) = response.to_js().expect("Failed to encode the response");
+    scope
+        .post_message_with_transfer(&message, &transfer)
+        .expect("Failed to post the response");
+}
+
+async fn process(request: Request) {
+    let result = process_files(request.files, &request.options, async |progress| {
+        post(Response::Progress(progress));
+        Ok(())
+    })
+    .await;
+    post(match result {
+        Ok(outputs) => Response::Done(outputs),
+        Err(e) => Response::Failed(e),
+    });
+}
+
+fn main() {
+    console_error_panic_hook::set_once();
+
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(|event: MessageEvent| {
+        match Request::from_js(&event.data()) {
+            Ok(request) => spawn_local(process(request)),
+            Err(e) => post(Response::Failed(e.into())),
+        }
+    });
+    scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    // The worker handles messages until the page terminates it
+    on_message.forget();
+}
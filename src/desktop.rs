@@ -0,0 +1,117 @@
+use midi_splitter_core::File;
+use web_sys::{
+    js_sys::{Array, Object, Reflect, Uint8Array},
+    wasm_bindgen::{closure::Closure, JsValue},
+};
+
+use crate::js::{call_async, error_message};
+
+/// Global object the desktop app adds to the page
+fn tauri() -> JsValue {
+    Reflect::get(&leptos::window(), &"__TAURI__".into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+/// Whether the page runs in the desktop app, which opens and saves files with the dialogs of
+/// the system
+pub fn is_desktop() -> bool {
+    !tauri().is_undefined()
+}
+
+/// Run a command of the desktop app with the given arguments
+async fn invoke(command: &str, args: &[(&str, JsValue)]) -> Result<JsValue, String> {
+    let core = Reflect::get(&tauri(), &"core".into()).map_err(|e| error_message(&e))?;
+    let arguments = Object::new();
+    for (name, value) in args {
+        Reflect::set(&arguments, &(*name).into(), value).map_err(|e| error_message(&e))?;
+    }
+    call_async(&core, "invoke", &[command.into(), arguments.into()])
+        .await
+        // Commands fail with the message itself
+        .map_err(|error| error.as_string().unwrap_or_else(|| error_message(&error)))
+}
+
+/// Files as the desktop app sends them, with the data as a list of bytes
+fn files_from_js(files: &JsValue) -> Vec<File> {
+    Array::from(files)
+        .iter()
+        .map(|file| File {
+            name: Reflect::get(&file, &"name".into())
+                .ok()
+                .and_then(|name| name.as_string())
+                .unwrap_or_default(),
+            data: Reflect::get(&file, &"data".into())
+                .map(|data| Uint8Array::new(&data).to_vec())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn bytes_to_js(data: &[u8]) -> JsValue {
+    Array::from(&Uint8Array::from(data)).into()
+}
+
+/// Let the user pick MIDI files with the open dialog of the system. Returns no files if the
+/// user closed the dialog.
+pub async fn open_files() -> Result<Vec<File>, String> {
+    Ok(files_from_js(&invoke("open_midi_files", &[]).await?))
+}
+
+/// Read the MIDI files at the given paths, searching folders and their subfolders
+pub async fn read_files(paths: JsValue) -> Result<Vec<File>, String> {
+    Ok(files_from_js(
+        &invoke("read_midi_files", &[("paths", paths)]).await?,
+    ))
+}
+
+/// Let the user save a file with the save dialog of the system.
+/// Returns false if the user closed the dialog.
+pub async fn save_file(file: &File) -> Result<bool, String> {
+    invoke(
+        "save_file",
+        &[
+            ("name", file.name.as_str().into()),
+            ("data", bytes_to_js(&file.data)),
+        ],
+    )
+    .await
+    .map(|saved| saved.is_truthy())
+}
+
+/// Let the user pick a folder and write all files into it, creating the folders of songs.
+/// Returns false if the user did not pick a folder.
+pub async fn save_to_picked_folder(files: &[File]) -> Result<bool, String> {
+    let files: Array = files
+        .iter()
+        .map(|file| {
+            let object = Object::new();
+            let _ = Reflect::set(&object, &"name".into(), &file.name.as_str().into());
+            let _ = Reflect::set(&object, &"data".into(), &bytes_to_js(&file.data));
+            JsValue::from(object)
+        })
+        .collect();
+    invoke("save_to_folder", &[("files", files.into())])
+        .await
+        .map(|saved| saved.is_truthy())
+}
+
+/// Call the handler with the paths of files and folders dropped onto the window. The desktop
+/// app takes drops over from the page, so the page itself does not get their files.
+pub async fn on_drop(handler: impl Fn(JsValue) + 'static) -> Result<(), String> {
+    let on_drop = Closure::<dyn Fn(JsValue)>::new(move |event: JsValue| {
+        let paths = Reflect::get(&event, &"payload".into())
+            .and_then(|payload| Reflect::get(&payload, &"paths".into()))
+            .unwrap_or(JsValue::UNDEFINED);
+        if !paths.is_undefined() {
+            handler(paths);
+        }
+    });
+    let event = Reflect::get(&tauri(), &"event".into()).map_err(|e| error_message(&e))?;
+    call_async(
+        &event,
+        "listen",
+        &["tauri://drag-drop".into(), on_drop.into_js_value()],
+    )
+    .await
+    .map_err(|e| error_message(&e))?;
+    Ok(())
+}
@@ -0,0 +1,314 @@
+use std::collections::{HashMap, VecDeque};
+
+use midi_splitter_core::{
+    find_track_name, riff,
+    timing::{self, TempoMap, Tick},
+    File,
+};
+use midly::{Format, MetaMessage, MidiMessage, Smf, TrackEvent, TrackEventKind};
+
+use crate::i18n::Strings;
+
+/// Changes listed per track, so a track whose every note changed does not freeze the page
+pub const MAX_LISTED_CHANGES: usize = 500;
+
+/// Type of an event as listed in the comparison
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventType {
+    NoteOn,
+    NoteOff,
+    Aftertouch,
+    Controller,
+    ProgramChange,
+    ChannelAftertouch,
+    PitchBend,
+    Meta,
+    SysEx,
+}
+
+impl EventType {
+    pub fn name(&self, text: &Strings) -> &'static str {
+        match self {
+            EventType::NoteOn => text.event_note_on,
+            EventType::NoteOff => text.event_note_off,
+            EventType::Aftertouch => text.event_aftertouch,
+            EventType::Controller => text.event_controller,
+            EventType::ProgramChange => text.event_program_change,
+            EventType::ChannelAftertouch => text.event_channel_aftertouch,
+            EventType::PitchBend => text.event_pitch_bend,
+            EventType::Meta => text.event_meta,
+            EventType::SysEx => text.event_sysex,
+        }
+    }
+}
+
+/// Where an event is in the song
+#[derive(Clone, Copy)]
+pub enum Position {
+    /// Bar and beat counting from 1
+    Beat(Tick, Tick),
+    /// Files with timecode timing have no bars
+    Seconds(f64),
+}
+
+/// Event of the original which was changed or removed, or an event which was inserted
+#[derive(Clone)]
+pub struct EventChange {
+    pub tick: Tick,
+    pub position: Position,
+    pub event: EventType,
+    /// Counting from 0
+    pub channel: Option<u8>,
+    /// Key of note and aftertouch events, number of controllers
+    pub number: Option<u8>,
+    /// Missing if the event was inserted
+    pub original: Option<String>,
+    /// Missing if the event was removed
+    pub processed: Option<String>,
+}
+
+/// Changes of one track of a processed file
+#[derive(Clone)]
+pub struct TrackDiff {
+    pub name: Option<String>,
+    /// Sorted by tick
+    pub changes: Vec<EventChange>,
+}
+
+/// Event which finds its counterpart in the other file by everything but its value
+#[derive(PartialEq, Eq, Hash)]
+struct Identity {
+    tick: Tick,
+    event: EventType,
+    channel: Option<u8>,
+    number: Option<u8>,
+    /// Text of meta and SysEx events, which have no value which could change
+    content: Option<String>,
+}
+
+/// Split an event into what identifies it and its value
+fn identify(tick: Tick, kind: &TrackEventKind) -> (Identity, Option<String>) {
+    let identity = |event, channel: Option<u8>, number: Option<u8>| Identity {
+        tick,
+        event,
+        channel,
+        number,
+        content: None,
+    };
+    match *kind {
+        TrackEventKind::Midi { channel, message } => {
+            let channel = Some(channel.as_int());
+            match message {
+                MidiMessage::NoteOn { key, vel } if vel > 0 => (
+                    identity(EventType::NoteOn, channel, Some(key.as_int())),
+                    Some(vel.to_string()),
+                ),
+                MidiMessage::NoteOn { key, vel } | MidiMessage::NoteOff { key, vel } => (
+                    identity(EventType::NoteOff, channel, Some(key.as_int())),
+                    Some(vel.to_string()),
+                ),
+                MidiMessage::Aftertouch { key, vel } => (
+                    identity(EventType::Aftertouch, channel, Some(key.as_int())),
+                    Some(vel.to_string()),
+                ),
+                MidiMessage::Controller { controller, value } => (
+                    identity(EventType::Controller, channel, Some(controller.as_int())),
+                    Some(value.to_string()),
+                ),
+                MidiMessage::ProgramChange { program } => (
+                    identity(EventType::ProgramChange, channel, None),
+                    Some(program.to_string()),
+                ),
+                MidiMessage::ChannelAftertouch { vel } => (
+                    identity(EventType::ChannelAftertouch, channel, None),
+                    Some(vel.to_string()),
+                ),
+                MidiMessage::PitchBend { bend } => (
+                    identity(EventType::PitchBend, channel, None),
+                    Some(bend.as_int().to_string()),
+                ),
+            }
+        }
+        TrackEventKind::Meta(meta) => {
+            let content = describe_meta(meta);
+            (
+                Identity {
+                    content: Some(content.clone()),
+                    ..identity(EventType::Meta, None, None)
+                },
+                Some(content),
+            )
+        }
+        TrackEventKind::SysEx(data) | TrackEventKind::Escape(data) => {
+            let content = data
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect::<Vec<_>>()
+                .join(" ");
+            (
+                Identity {
+                    content: Some(content.clone()),
+                    ..identity(EventType::SysEx, None, None)
+                },
+                Some(content),
+            )
+        }
+    }
+}
+
+/// Text of a meta event, showing the text of text events
+fn describe_meta(meta: MetaMessage) -> String {
+    match meta {
+        MetaMessage::Text(text)
+        | MetaMessage::Copyright(text)
+        | MetaMessage::TrackName(text)
+        | MetaMessage::InstrumentName(text)
+        | MetaMessage::Lyric(text)
+        | MetaMessage::Marker(text)
+        | MetaMessage::CuePoint(text)
+        | MetaMessage::ProgramName(text)
+        | MetaMessage::DeviceName(text) => {
+            let name = format!("{:?}", meta);
+            let name = name.split('(').next().unwrap_or_default();
+            format!("{}: {}", name, String::from_utf8_lossy(text))
+        }
+        MetaMessage::Tempo(tempo) => format!("Tempo: {:.1} BPM", timing::bpm(tempo.as_int())),
+        meta => format!("{:?}", meta),
+    }
+}
+
+/// Compare the events of a processed track with the original ones
+fn diff_events(
+    original: &[(Tick, &TrackEvent)],
+    processed: &[(Tick, &TrackEvent)],
+    position: &dyn Fn(Tick) -> Position,
+) -> Vec<EventChange> {
+    // Values of the original events by identity, in the order they appear
+    let mut remaining: HashMap<Identity, VecDeque<Option<String>>> = HashMap::new();
+    for (tick, event) in original {
+        let (identity, value) = identify(*tick, &event.kind);
+        remaining.entry(identity).or_default().push_back(value);
+    }
+
+    let mut changes = Vec::new();
+    for (tick, event) in processed {
+        let (identity, value) = identify(*tick, &event.kind);
+        let original = remaining
+            .get_mut(&identity)
+            .and_then(|values| values.pop_front());
+        if original.as_ref() == Some(&value) {
+            continue;
+        }
+        changes.push(EventChange {
+            tick: identity.tick,
+            position: position(identity.tick),
+            event: identity.event,
+            channel: identity.channel,
+            number: identity.number,
+            original: original.flatten(),
+            processed: value,
+        });
+    }
+    for (identity, values) in remaining {
+        for value in values {
+            changes.push(EventChange {
+                tick: identity.tick,
+                position: position(identity.tick),
+                event: identity.event,
+                channel: identity.channel,
+                number: identity.number,
+                original: value,
+                processed: None,
+            });
+        }
+    }
+    changes.sort_by_key(|change| change.tick);
+    changes
+}
+
+/// Events of a track with their absolute ticks
+fn events<'a, 'b>(track: &'a [TrackEvent<'b>]) -> Vec<(Tick, &'a TrackEvent<'b>)> {
+    timing::absolute_ticks(track).collect()
+}
+
+/// Compare every track of a processed file with the track of the original it came from.
+/// Tracks are matched by name and otherwise by position. A file merged into a single track is
+/// compared with all tracks of the original together.
+pub fn diff(original: &Smf, processed: &Smf) -> Vec<TrackDiff> {
+    let time_signatures = timing::time_signatures(&processed.tracks);
+    let ticks_per_beat = timing::ticks_per_beat(processed.header.timing).ok();
+    let tempo_map = TempoMap::new(&processed.tracks, processed.header.timing);
+    let position = |tick| match ticks_per_beat {
+        Some(ticks_per_beat) => {
+            let (bar, beat) = timing::bar_and_beat(&time_signatures, ticks_per_beat, tick);
+            Position::Beat(bar, beat)
+        }
+        None => Position::Seconds(tempo_map.seconds(tick)),
+    };
+
+    let names = |smf: &Smf| -> Vec<Option<String>> {
+        smf.tracks
+            .iter()
+            .map(|track| find_track_name(track).ok().flatten().map(str::to_string))
+            .collect()
+    };
+    let original_names = names(original);
+    let processed_names = names(processed);
+
+    if processed.header.format == Format::SingleTrack && original.tracks.len() > 1 {
+        let mut merged: Vec<(Tick, &TrackEvent)> = original
+            .tracks
+            .iter()
+            .flat_map(|track| events(track))
+            .collect();
+        merged.sort_by_key(|(tick, _)| *tick);
+        return processed
+            .tracks
+            .iter()
+            .zip(processed_names)
+            .map(|(track, name)| TrackDiff {
+                name,
+                changes: diff_events(&merged, &events(track), &position),
+            })
+            .collect();
+    }
+
+    let mut used = vec![false; original.tracks.len()];
+    processed
+        .tracks
+        .iter()
+        .zip(processed_names)
+        .enumerate()
+        .map(|(index, (track, name))| {
+            let by_name = name.as_ref().and_then(|name| {
+                (0..original.tracks.len())
+                    .find(|&other| !used[other] && original_names[other].as_ref() == Some(name))
+            });
+            let counterpart = by_name.or((index < used.len() && !used[index]).then_some(index));
+            let original_events = match counterpart {
+                Some(other) => {
+                    used[other] = true;
+                    events(&original.tracks[other])
+                }
+                None => Vec::new(),
+            };
+            TrackDiff {
+                name,
+                changes: diff_events(&original_events, &events(track), &position),
+            }
+        })
+        .collect()
+}
+
+/// Compare a generated file with the upload it was created from
+pub fn diff_files(original: &File, processed: &File) -> anyhow::Result<Vec<TrackDiff>> {
+    let original_data = if riff::is_riff_midi(&original.data) {
+        riff::unwrap(&original.data)?
+    } else {
+        original.data.as_slice()
+    };
+    Ok(diff(
+        &Smf::parse(original_data)?,
+        &Smf::parse(&processed.data)?,
+    ))
+}
@@ -0,0 +1,71 @@
+use midi_splitter_core::File;
+use web_sys::js_sys::{Object, Reflect, Uint8Array};
+
+use crate::{
+    i18n::Strings,
+    js::{call_async, error_message, is_abort},
+};
+
+/// Whether the browser can write files into a directory picked by the user
+pub fn is_supported() -> bool {
+    Reflect::has(&leptos::window(), &"showDirectoryPicker".into()).unwrap_or(false)
+}
+
+/// Let the user pick a directory and write all files into it, reporting the number of files
+/// written so far. Returns false if the user did not pick a directory.
+pub async fn save_to_picked_directory(
+    files: &[File],
+    progress: impl Fn(usize),
+    text: &Strings,
+) -> Result<bool, String> {
+    let options = Object::new();
+    Reflect::set(&options, &"mode".into(), &"readwrite".into()).map_err(|e| error_message(&e))?;
+
+    let directory =
+        match call_async(&leptos::window(), "showDirectoryPicker", &[options.into()]).await {
+            Ok(directory) => directory,
+            // The user closed the picker
+            Err(error) if is_abort(&error) => return Ok(false),
+            Err(error) => return Err(error_message(&error)),
+        };
+
+    for (index, file) in files.iter().enumerate() {
+        let create = Object::new();
+        Reflect::set(&create, &"create".into(), &true.into()).map_err(|e| error_message(&e))?;
+
+        let write = async {
+            // Files of multiple songs are in folders which have to be created first
+            let (folders, file_name) = match file.name.rsplit_once('/') {
+                Some((folders, file_name)) => (folders.split('/').collect(), file_name),
+                None => (Vec::new(), file.name.as_str()),
+            };
+            let mut parent = directory.clone();
+            for folder in folders {
+                parent = call_async(
+                    &parent,
+                    "getDirectoryHandle",
+                    &[folder.into(), create.clone().into()],
+                )
+                .await?;
+            }
+
+            let handle =
+                call_async(&parent, "getFileHandle", &[file_name.into(), create.into()]).await?;
+            let writable = call_async(&handle, "createWritable", &[]).await?;
+            call_async(
+                &writable,
+                "write",
+                &[Uint8Array::from(file.data.as_slice()).into()],
+            )
+            .await?;
+            call_async(&writable, "close", &[]).await
+        };
+        write
+            .await
+            .map_err(|e| (text.save_file_failed)(&file.name, &error_message(&e)))?;
+
+        progress(index + 1);
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,29 @@
+use crate::i18n::Strings;
+
+/// Key saved in the local storage of the browser once the first-run hints are dismissed
+const STORAGE_KEY: &str = "midi_splitter_hints_dismissed";
+
+/// Explanation of the option with the given input id, if it has one
+pub fn explanation(text: &Strings, id: &str) -> Option<&'static str> {
+    text.option_help
+        .iter()
+        .find(|(option, _)| *option == id)
+        .map(|(_, explanation)| *explanation)
+}
+
+/// Whether the hints for new users were dismissed in a previous session
+pub fn hints_dismissed() -> bool {
+    leptos::window()
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .is_some()
+}
+
+/// Stop showing the hints for new users, also in later sessions
+pub fn dismiss_hints() {
+    if let Ok(Some(storage)) = leptos::window().local_storage() {
+        let _ = storage.set_item(STORAGE_KEY, "true");
+    }
+}
@@ -0,0 +1,135 @@
+use midi_splitter_core::{File, ProcessOptions};
+use serde::{Deserialize, Serialize};
+use web_sys::{js_sys::Date, wasm_bindgen::JsValue, Storage};
+
+/// Key of the history saved in the local storage of the browser
+const STORAGE_KEY: &str = "midi_splitter_history";
+
+/// Number of runs kept in the history
+const MAX_ENTRIES: usize = 10;
+
+/// Uploads larger than this are not kept so the history fits into the local storage
+const MAX_STORED_BYTES: usize = 512 * 1024;
+
+/// Uploaded file encoded as hexadecimal text so it can be kept in the local storage
+#[derive(Clone, Serialize, Deserialize)]
+struct StoredFile {
+    name: String,
+    data: String,
+}
+
+/// Previous run which can be repeated with the same files and settings
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub file_names: Vec<String>,
+    pub options: ProcessOptions,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: f64,
+    /// Empty if the uploads were too large to keep
+    files: Vec<StoredFile>,
+}
+
+impl HistoryEntry {
+    pub fn has_files(&self) -> bool {
+        !self.files.is_empty()
+    }
+
+    /// Uploaded files of the run, if they were kept
+    pub fn files(&self) -> Option<Vec<File>> {
+        if self.files.is_empty() {
+            return None;
+        }
+        self.files
+            .iter()
+            .map(|file| {
+                Some(File {
+                    name: file.name.clone(),
+                    data: decode_hex(&file.data)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Date and time of the run in the format of the language
+    pub fn date(&self, locale: &str) -> String {
+        Date::new(&JsValue::from_f64(self.timestamp))
+            .to_locale_string(locale, &JsValue::UNDEFINED)
+            .into()
+    }
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn local_storage() -> Option<Storage> {
+    leptos::window().local_storage().ok().flatten()
+}
+
+/// Runs of previous sessions, the most recent first
+pub fn load() -> Vec<HistoryEntry> {
+    local_storage()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .and_then(|history| serde_json::from_str(&history).ok())
+        .unwrap_or_default()
+}
+
+/// Remember a run, replacing an earlier run of the same files, and return the new history
+pub fn add(uploads: &[File], options: &ProcessOptions) -> Vec<HistoryEntry> {
+    let file_names: Vec<String> = uploads.iter().map(|file| file.name.clone()).collect();
+    let size: usize = uploads.iter().map(|file| file.data.len()).sum();
+    let entry = HistoryEntry {
+        file_names: file_names.clone(),
+        options: options.clone(),
+        timestamp: Date::now(),
+        files: if size <= MAX_STORED_BYTES {
+            uploads
+                .iter()
+                .map(|file| StoredFile {
+                    name: file.name.clone(),
+                    data: encode_hex(&file.data),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        },
+    };
+
+    let mut history = load();
+    history.retain(|earlier| earlier.file_names != file_names);
+    history.insert(0, entry);
+    history.truncate(MAX_ENTRIES);
+    save(&mut history);
+    history
+}
+
+/// Save the history, forgetting the oldest runs until it fits into the storage
+fn save(history: &mut Vec<HistoryEntry>) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    while !history.is_empty() {
+        let Ok(json) = serde_json::to_string(history) else {
+            return;
+        };
+        if storage.set_item(STORAGE_KEY, &json).is_ok() {
+            return;
+        }
+        history.pop();
+    }
+    let _ = storage.remove_item(STORAGE_KEY);
+}
+
+/// Forget all previous runs
+pub fn clear() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(STORAGE_KEY);
+    }
+}
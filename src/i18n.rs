@@ -0,0 +1,1315 @@
+use web_sys::js_sys::Reflect;
+
+use midi_splitter_core::{
+    diagnosis::ReadProblem,
+    key::Key,
+    lenient::{SkipReason, Skipped},
+    repair::{Repair, RepairKind},
+    Phase, Progress,
+};
+
+/// Key of the language saved in the local storage of the browser
+const STORAGE_KEY: &str = "midi_splitter_language";
+
+/// Language of the user interface
+#[derive(Clone, Copy, PartialEq)]
+pub enum Locale {
+    English,
+    German,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::German];
+
+    /// Language code used in the local storage and the lang attribute of the page
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::German => "de",
+        }
+    }
+
+    /// Name of the language in the language itself
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::German => "Deutsch",
+        }
+    }
+
+    /// Language with the given code, matching regional codes like "de-AT" as well
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let language = code.split('-').next().unwrap_or_default();
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(language))
+    }
+
+    /// Text of the user interface in the language
+    pub fn strings(&self) -> &'static Strings {
+        match self {
+            Locale::English => &ENGLISH,
+            Locale::German => &GERMAN,
+        }
+    }
+}
+
+/// Language picked in a previous session, otherwise the language of the browser
+pub fn load() -> Locale {
+    let window = leptos::window();
+    let saved = window
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten());
+    let browser = Reflect::get(&window, &"navigator".into())
+        .and_then(|navigator| Reflect::get(&navigator, &"language".into()))
+        .ok()
+        .and_then(|language| language.as_string());
+
+    saved
+        .or(browser)
+        .and_then(|code| Locale::from_code(&code))
+        .unwrap_or(Locale::English)
+}
+
+/// Mark the page as being in the language and remember it for the next session
+pub fn apply(locale: Locale) {
+    if let Some(root) = leptos::document().document_element() {
+        let _ = root.set_attribute("lang", locale.code());
+    }
+    if let Ok(Some(storage)) = leptos::window().local_storage() {
+        let _ = storage.set_item(STORAGE_KEY, locale.code());
+    }
+}
+
+/// Text of the user interface in one language
+pub struct Strings {
+    pub theme: &'static str,
+    pub theme_system: &'static str,
+    pub theme_light: &'static str,
+    pub theme_dark: &'static str,
+    pub language: &'static str,
+    pub intro: &'static str,
+    pub error: &'static str,
+    pub number_error: &'static str,
+
+    pub mode: &'static str,
+    pub mode_emphasize: &'static str,
+    pub mode_solo: &'static str,
+    pub mode_minus_one: &'static str,
+    pub normalize: &'static str,
+    pub target_velocity: &'static str,
+    pub normalize_average: &'static str,
+    pub normalize_peak: &'static str,
+    pub velocity_mapping: &'static str,
+    pub mapping_reduce: &'static str,
+    pub mapping_compress: &'static str,
+    pub compress_ratio: &'static str,
+    pub compress_makeup: &'static str,
+    pub velocity_reduction: &'static str,
+    pub advanced_options: &'static str,
+    pub advanced_options_changed: &'static str,
+    pub reduction_threshold: &'static str,
+    pub velocity_boost: &'static str,
+    pub velocity_histogram: &'static str,
+    pub histogram_before: &'static str,
+    pub histogram_after: &'static str,
+    pub silenced_notes: fn(usize, usize) -> String,
+    pub dynamics_mode: &'static str,
+    pub dynamics_velocity: &'static str,
+    pub dynamics_controllers: &'static str,
+    pub dynamics_both: &'static str,
+    pub volume_scale: &'static str,
+    pub scale_aftertouch: &'static str,
+    pub aftertouch_scale: &'static str,
+    pub practice_panning: &'static str,
+    pub keep_drums: &'static str,
+    pub background_program: &'static str,
+    pub keep_instruments: &'static str,
+    pub humanize: &'static str,
+    pub stamp_velocities: &'static str,
+    pub humanize_range: &'static str,
+    pub humanize_seed: &'static str,
+
+    pub transpose: &'static str,
+    pub skip_empty_tracks: &'static str,
+    pub split_hands: &'static str,
+    pub split_track: &'static str,
+    pub hand_split_key: &'static str,
+    pub split_voices: &'static str,
+    pub split_channels: &'static str,
+    pub click_track: &'static str,
+    pub click_downbeat_velocity: &'static str,
+    pub click_beat_velocity: &'static str,
+    pub strip_events: &'static str,
+    pub export_lyrics: &'static str,
+    pub export_musicxml: &'static str,
+    pub export_abc: &'static str,
+    pub export_note_csv: &'static str,
+    pub render_audio: &'static str,
+    pub single_track_output: &'static str,
+    pub include_all_tracks: &'static str,
+    pub all_tracks_suffix: &'static str,
+    pub empty_all_tracks_suffix: &'static str,
+    pub include_original: &'static str,
+    pub cue_sheet: &'static str,
+    pub stamp_provenance: &'static str,
+    pub lenient_parsing: &'static str,
+    pub repair: &'static str,
+    pub manifest: &'static str,
+    pub manifest_none: &'static str,
+    pub archive_format: &'static str,
+    pub archive_zip: &'static str,
+    pub archive_tar_gz: &'static str,
+    pub reproducible_archive: &'static str,
+    pub bar_range: &'static str,
+    pub first_bar: &'static str,
+    pub last_bar: &'static str,
+    pub tempo_variants: &'static str,
+    pub tempo_variants_placeholder: &'static str,
+    pub name_template: &'static str,
+    pub number_files: &'static str,
+
+    pub settings: &'static str,
+    pub download_settings: &'static str,
+    pub copy_link: &'static str,
+    pub link_copied: &'static str,
+    pub reset_settings: &'static str,
+    pub reprocess: &'static str,
+    pub processing_settings: &'static str,
+    pub ensemble_preset: &'static str,
+    pub choose_preset: &'static str,
+    pub preset_satb_choir: &'static str,
+    pub preset_concert_band: &'static str,
+    pub preset_piano_duet: &'static str,
+    pub preset_worship_team: &'static str,
+    pub shortcuts: &'static str,
+    pub shortcut_open_files: &'static str,
+    pub shortcut_reprocess: &'static str,
+    pub shortcut_download: &'static str,
+    pub shortcut_play_pause: &'static str,
+    pub shortcut_help: &'static str,
+    pub close: &'static str,
+    pub cancel: &'static str,
+    pub history: &'static str,
+    pub process_again: &'static str,
+    pub history_files_too_large: &'static str,
+    pub clear_history: &'static str,
+    pub load_settings: &'static str,
+
+    pub upload: &'static str,
+    pub track: &'static str,
+    pub track_name: &'static str,
+    pub channels: &'static str,
+    pub notes: &'static str,
+    pub instrument: &'static str,
+    pub track_end: &'static str,
+    pub own_file: &'static str,
+    pub drag_to_reorder: &'static str,
+    pub move_track_up: fn(usize) -> String,
+    pub move_track_down: fn(usize) -> String,
+    pub file_format: &'static str,
+    pub format_single_track: &'static str,
+    pub format_parallel: &'static str,
+    pub format_sequential: &'static str,
+    /// Shown for single track files whose parts are only told apart by their channels
+    pub format_single_track_hint: &'static str,
+    pub timing_division: &'static str,
+    pub timing_metrical: fn(u16) -> String,
+    /// Frames per second and ticks per frame of timecode files
+    pub timing_timecode: fn(f32, u8) -> String,
+    pub track_count: &'static str,
+    pub file_size: &'static str,
+    /// Label of the checkbox giving the track with the given number a file of its own
+    pub own_file_for: fn(usize) -> String,
+    pub track_end_at: fn(usize, &str) -> String,
+    pub zoom: &'static str,
+    pub tempo: &'static str,
+    pub tempo_constant: fn(f64) -> String,
+    pub tempo_changing: fn(f64, usize, f64, f64) -> String,
+    pub time_signatures: &'static str,
+    pub key: &'static str,
+    /// Names of the tonics of major keys by pitch class starting from C
+    pub major_tonics: [&'static str; 12],
+    /// Names of the tonics of minor keys by pitch class starting from C
+    pub minor_tonics: [&'static str; 12],
+    pub major_key: fn(&str) -> String,
+    pub minor_key: fn(&str) -> String,
+    pub key_estimated: fn(&str) -> String,
+    pub key_from_signature: fn(&str) -> String,
+    pub key_signature_differs: fn(&str) -> String,
+    pub from_bar: fn(usize) -> String,
+    pub anomalies: &'static str,
+    pub anomaly_unmatched_note_ons: fn(usize, usize) -> String,
+    pub anomaly_unmatched_note_offs: fn(usize, usize) -> String,
+    pub anomaly_overlapping_notes: fn(usize, usize) -> String,
+    pub anomaly_zero_length_notes: fn(usize, usize) -> String,
+    pub anomaly_missing_end_of_track: fn(usize) -> String,
+    pub anomaly_unnamed_track: fn(usize) -> String,
+    pub listen: &'static str,
+    pub compare: &'static str,
+    pub compare_with_original: fn(&str) -> String,
+    pub changes_in: fn(&str) -> String,
+    pub change_count: fn(usize) -> String,
+    pub no_changes: &'static str,
+    pub more_changes: fn(usize) -> String,
+    pub diff_position: &'static str,
+    pub diff_channel: &'static str,
+    pub diff_event: &'static str,
+    pub diff_original: &'static str,
+    pub diff_processed: &'static str,
+    pub diff_inserted: &'static str,
+    pub diff_removed: &'static str,
+    pub event_note_on: &'static str,
+    pub event_note_off: &'static str,
+    pub event_aftertouch: &'static str,
+    pub event_controller: &'static str,
+    pub event_program_change: &'static str,
+    pub event_channel_aftertouch: &'static str,
+    pub event_pitch_bend: &'static str,
+    pub event_meta: &'static str,
+    pub event_sysex: &'static str,
+    pub playback: &'static str,
+    pub mute: &'static str,
+    pub solo: &'static str,
+    pub listen_to: fn(&str) -> String,
+    pub piano_roll: &'static str,
+    pub files_ready: fn(usize) -> String,
+    pub preparing_playback: &'static str,
+    pub listening_to: fn(&str) -> String,
+    pub listening_to_original: &'static str,
+    pub switch_to_generated: &'static str,
+    pub switch_to_original: &'static str,
+
+    pub created_files: &'static str,
+    pub skipped_parts: &'static str,
+    pub skipped_leading_bytes: fn(usize) -> String,
+    pub skipped_trailing_bytes: fn(usize) -> String,
+    pub skipped_unknown_chunk: fn(usize) -> String,
+    pub skipped_invalid_format: &'static str,
+    pub skipped_invalid_frame_rate: &'static str,
+    pub skipped_malformed_events: fn(usize, usize) -> String,
+    pub skipped_truncated_track: fn(usize, usize) -> String,
+    pub repaired_mistakes: &'static str,
+    pub repaired_track_count: fn(usize, usize) -> String,
+    pub repaired_single_track_format: &'static str,
+    pub repaired_missing_end_of_track: fn(usize) -> String,
+    pub repaired_duplicate_end_of_track: fn(usize, usize) -> String,
+    pub repaired_dangling_notes: fn(usize, usize) -> String,
+    pub share: &'static str,
+    pub download: &'static str,
+    pub save_to_folder: &'static str,
+    pub saved_files: fn(usize, usize) -> String,
+
+    pub restore_title: &'static str,
+    pub restore_upload: &'static str,
+    pub download_file: fn(&str) -> String,
+
+    pub progress_file: fn(usize, usize) -> String,
+    pub progress_loading: fn(&str) -> String,
+    pub progress_parsing: &'static str,
+    pub progress_transforming: &'static str,
+    pub progress_generating: fn(usize, usize) -> String,
+    pub progress_archiving: &'static str,
+
+    /// Message for a number outside of its range, given the label, minimum and maximum
+    pub number_out_of_range: fn(&str, &str, &str) -> String,
+    pub invalid_number: fn(&str) -> String,
+    pub number_errors_pending: &'static str,
+    pub only_midi_files: &'static str,
+    pub play_failed: fn(&str, &str) -> String,
+    pub copy_link_failed: fn(&str) -> String,
+    pub load_settings_failed: fn(&str, &str) -> String,
+    pub save_file_failed: fn(&str, &str) -> String,
+    pub share_unsupported: fn(&str) -> String,
+    pub worker_start_failed: fn(&str) -> String,
+    pub worker_failed: fn(&str) -> String,
+    pub worker_send_failed: fn(&str) -> String,
+    pub worker_no_result: &'static str,
+    /// Message for an entered tempo percentage which is not a number in range, given the
+    /// percentage and the maximum
+    pub invalid_tempo_percentage: fn(&str, u8) -> String,
+
+    pub unreadable_file: fn(&str) -> String,
+    pub unreadable_upload: &'static str,
+    pub corrupted_at: fn(usize) -> String,
+    pub non_utf8_track_name: fn(&str, usize) -> String,
+    pub suggestion_non_utf8_track_name: &'static str,
+    pub invalid_tempo_variant: fn(u8, u8) -> String,
+    /// Message for a setting above 127, given the name of the setting and its value
+    pub invalid_midi_value: fn(&str, u8) -> String,
+    pub too_large_bytes: fn(&str, &str, &str) -> String,
+    pub too_large_tracks: fn(&str, usize, usize) -> String,
+    pub too_large_events: fn(&str, usize, usize) -> String,
+    /// Message for a song which lasts too long, given its length and the limit in minutes
+    pub too_large_duration: fn(&str, usize, usize) -> String,
+    pub too_large_beats: fn(&str, usize, usize) -> String,
+    pub suggestion_too_large: &'static str,
+    pub process_anyway: &'static str,
+    pub problem_invalid_extension: &'static str,
+    pub problem_not_midi: &'static str,
+    pub problem_truncated: &'static str,
+    pub problem_unsupported_format: &'static str,
+    pub problem_corrupted: &'static str,
+    pub suggestion_invalid_extension: &'static str,
+    pub suggestion_not_midi: &'static str,
+    pub suggestion_truncated: &'static str,
+    pub suggestion_unsupported_format: &'static str,
+    pub suggestion_corrupted: &'static str,
+    pub choose_other_files: &'static str,
+
+    /// Explanations of the options by the id of their input
+    pub option_help: &'static [(&'static str, &'static str)],
+    pub show_help: &'static str,
+    pub first_run_hint: &'static str,
+    pub dismiss_hint: &'static str,
+    pub update_available: &'static str,
+    pub reload: &'static str,
+    pub later: &'static str,
+
+    pub guided_mode: &'static str,
+    pub single_page_mode: &'static str,
+    pub steps: &'static str,
+    pub step_upload: &'static str,
+    pub step_pick_parts: &'static str,
+    pub step_settings: &'static str,
+    pub step_download: &'static str,
+    pub back: &'static str,
+    pub next: &'static str,
+    pub create_files: &'static str,
+    pub start_over: &'static str,
+}
+
+impl Strings {
+    /// Name of a musical key such as "D minor"
+    pub fn key_name(&self, key: Key) -> String {
+        if key.minor {
+            (self.minor_key)(self.minor_tonics[key.tonic as usize % 12])
+        } else {
+            (self.major_key)(self.major_tonics[key.tonic as usize % 12])
+        }
+    }
+
+    /// Description of the progress of the processing
+    pub fn progress(&self, progress: &Progress) -> String {
+        let mut message = String::new();
+        if progress.files > 1 && !matches!(progress.phase, Phase::Archiving) {
+            message.push_str(&(self.progress_file)(progress.file + 1, progress.files));
+        }
+        match progress.phase {
+            Phase::Parsing => message.push_str(self.progress_parsing),
+            Phase::Transforming => message.push_str(self.progress_transforming),
+            Phase::Generating { track, tracks } => {
+                message.push_str(&(self.progress_generating)(track + 1, tracks))
+            }
+            Phase::Archiving => message.push_str(self.progress_archiving),
+        }
+        message
+    }
+
+    /// Upload being read before the processing starts, counting from 0
+    pub fn loading(&self, file: usize, files: usize, file_name: &str) -> String {
+        let mut message = String::new();
+        if files > 1 {
+            message.push_str(&(self.progress_file)(file + 1, files));
+        }
+        message.push_str(&(self.progress_loading)(file_name));
+        message
+    }
+
+    /// Part of an upload which was left out, counting tracks from 1 as the track list does
+    pub fn skipped(&self, skipped: &Skipped) -> String {
+        let part = match skipped.reason {
+            SkipReason::LeadingBytes => (self.skipped_leading_bytes)(skipped.length),
+            SkipReason::TrailingBytes => (self.skipped_trailing_bytes)(skipped.length),
+            SkipReason::UnknownChunk => (self.skipped_unknown_chunk)(skipped.length),
+            SkipReason::InvalidFormat => self.skipped_invalid_format.to_string(),
+            SkipReason::InvalidFrameRate => self.skipped_invalid_frame_rate.to_string(),
+            SkipReason::MalformedEvents { track } => {
+                (self.skipped_malformed_events)(track + 1, skipped.length)
+            }
+            SkipReason::TruncatedTrack { track } => {
+                (self.skipped_truncated_track)(track + 1, skipped.length)
+            }
+        };
+        format!("{}: {}", skipped.file_name, part)
+    }
+
+    /// Mistake of an upload which was fixed, counting tracks from 1 as the track list does
+    pub fn repair(&self, repair: &Repair) -> String {
+        let fix = match repair.kind {
+            RepairKind::TrackCount { declared, found } => {
+                (self.repaired_track_count)(declared, found)
+            }
+            RepairKind::SingleTrackFormat => self.repaired_single_track_format.to_string(),
+            RepairKind::MissingEndOfTrack { track } => {
+                (self.repaired_missing_end_of_track)(track + 1)
+            }
+            RepairKind::DuplicateEndOfTrack { track, count } => {
+                (self.repaired_duplicate_end_of_track)(track + 1, count)
+            }
+            RepairKind::DanglingNotes { track, count } => {
+                (self.repaired_dangling_notes)(track + 1, count)
+            }
+        };
+        format!("{}: {}", repair.file_name, fix)
+    }
+
+    /// Why an upload cannot be read
+    pub fn problem_description(&self, problem: ReadProblem) -> &'static str {
+        match problem {
+            ReadProblem::InvalidExtension => self.problem_invalid_extension,
+            ReadProblem::NotMidi => self.problem_not_midi,
+            ReadProblem::Truncated => self.problem_truncated,
+            ReadProblem::UnsupportedFormat => self.problem_unsupported_format,
+            ReadProblem::Corrupted => self.problem_corrupted,
+        }
+    }
+
+    /// What the user can do to get an upload which can be read
+    pub fn problem_suggestion(&self, problem: ReadProblem) -> &'static str {
+        match problem {
+            ReadProblem::InvalidExtension => self.suggestion_invalid_extension,
+            ReadProblem::NotMidi => self.suggestion_not_midi,
+            ReadProblem::Truncated => self.suggestion_truncated,
+            ReadProblem::UnsupportedFormat => self.suggestion_unsupported_format,
+            ReadProblem::Corrupted => self.suggestion_corrupted,
+        }
+    }
+}
+
+pub const ENGLISH: Strings = Strings {
+    theme: "Theme",
+    theme_system: "Same as the system",
+    theme_light: "Light",
+    theme_dark: "Dark",
+    language: "Language",
+    intro: "Create files for each MIDI track with reduced note velocities for other tracks",
+    error: "Error",
+    number_error: "Number Error",
+
+    mode: "Create a file for each track containing",
+    mode_emphasize: "All tracks with the track emphasised",
+    mode_solo: "Only the track",
+    mode_minus_one: "All tracks except the track (minus one)",
+    normalize: "Normalize the velocities of every track first",
+    target_velocity: "Target velocity (1-127)",
+    normalize_average: "for the average velocity",
+    normalize_peak: "for the loudest note",
+    velocity_mapping: "Change the note velocities of the other tracks by",
+    mapping_reduce: "Reducing them by a fixed amount",
+    mapping_compress: "Compressing them toward a target velocity",
+    compress_ratio: "Ratio (1-20)",
+    compress_makeup: "Makeup (-64 to 64)",
+    velocity_reduction: "Reduce the note velocities by (0-127)",
+    advanced_options: "Advanced options",
+    advanced_options_changed: "(some are changed)",
+    reduction_threshold: "Only reduce velocities above and never below (0-127)",
+    velocity_boost: "Boost the note velocities of the emphasised track by (0-127)",
+    velocity_histogram: "Velocities of the tracks which are not emphasised",
+    histogram_before: "Before",
+    histogram_after: "After",
+    silenced_notes: |silenced, total| {
+        format!("{} of {} notes would become silent", silenced, total)
+    },
+    dynamics_mode: "Reduce the dynamics of the other tracks using",
+    dynamics_velocity: "Note velocities",
+    dynamics_controllers: "Volume and Expression controllers (CC7/CC11)",
+    dynamics_both: "Note velocities and volume controllers",
+    volume_scale: "Scale the volume controllers to (0-100%)",
+    scale_aftertouch: "Scale the aftertouch of the other tracks",
+    aftertouch_scale: "Scale the aftertouch to (0-100%)",
+    practice_panning: "Pan the emphasised track left and all other tracks right",
+    keep_drums: "Keep the drums at their original velocities",
+    background_program: "Switch the instrument of the other tracks to",
+    keep_instruments: "Keep the original instruments",
+    humanize: "Randomly vary the velocities of the other tracks",
+    stamp_velocities: "Record the original velocities so they can be restored later",
+    humanize_range: "Vary by up to (1-30)",
+    humanize_seed: "Random seed",
+
+    transpose: "Transpose all notes by semitones (-12 to 12)",
+    skip_empty_tracks: "Skip tracks without notes",
+    split_hands: "Split a piano track into right and left hand",
+    split_track: "Track to split (starting at 0)",
+    hand_split_key: "Lowest key of the right hand (60 = middle C)",
+    split_voices: "Split the chords of a track into an upper and a lower voice",
+    split_channels: "Split tracks using multiple channels into one track per channel",
+    click_track: "Add a metronome click track to every file",
+    click_downbeat_velocity: "Velocity of the first beat of a bar (1-127)",
+    click_beat_velocity: "Velocity of the other beats (1-127)",
+    strip_events: "Remove SysEx, lyrics, markers and other text events",
+    export_lyrics: "Add a text file with the lyrics",
+    export_musicxml: "Add the part of each track as MusicXML",
+    export_abc: "Add the part of each single-line track as ABC notation",
+    export_note_csv: "Add a CSV file listing the notes of each track",
+    render_audio: "Add a WAV recording of each file (makes the zip much larger)",
+    single_track_output: "Write single track MIDI files (Format 0) for older sequencers",
+    include_all_tracks: "Add a file with all tracks unchanged",
+    all_tracks_suffix: "Suffix of the file with all tracks",
+    empty_all_tracks_suffix: "The suffix of the file with all tracks must not be empty",
+    include_original: "Add the uploaded file unchanged",
+    cue_sheet: "Include a printable cue sheet listing the tracks",
+    stamp_provenance: "Record the version and settings used in every generated file",
+    lenient_parsing: "Read damaged files as far as possible",
+    repair: "Fix common mistakes of the uploads",
+    manifest: "Add a manifest describing all files",
+    manifest_none: "None",
+    archive_format: "Bundle the files as",
+    archive_zip: "Zip",
+    archive_tar_gz: "Gzip-compressed tar (.tar.gz)",
+    reproducible_archive:
+        "Sort the files in the archive so the same upload always gives the same archive",
+    bar_range: "Only export a range of bars",
+    first_bar: "From bar",
+    last_bar: "To bar",
+    tempo_variants: "Also create slowed down copies at these tempos (e.g. 50, 75)",
+    tempo_variants_placeholder: "None",
+    name_template: "File names ({song}, {track}, {index} and {mode} are replaced)",
+    number_files: "Number the files in track order (01_, 02_, ...)",
+
+    settings: "Settings",
+    download_settings: "Download settings",
+    copy_link: "Copy a link to these settings",
+    link_copied: "Link copied",
+    reset_settings: "Reset to the defaults",
+    reprocess: "Process again with the current settings",
+    processing_settings: "Processing settings",
+    ensemble_preset: "Settings for an ensemble",
+    choose_preset: "Choose an ensemble",
+    preset_satb_choir: "SATB choir",
+    preset_concert_band: "Concert band",
+    preset_piano_duet: "Piano duet",
+    preset_worship_team: "Worship team",
+    shortcuts: "Keyboard shortcuts",
+    shortcut_open_files: "Open files",
+    shortcut_reprocess: "Process the files again",
+    shortcut_download: "Download the generated files",
+    shortcut_play_pause: "Play or pause the playback",
+    shortcut_help: "Show or hide the keyboard shortcuts",
+    close: "Close",
+    cancel: "Cancel",
+    history: "Recently processed",
+    process_again: "Process again",
+    history_files_too_large: "The files were too large to keep",
+    clear_history: "Clear the history",
+    load_settings: "Load settings from a downloaded file",
+
+    upload: "Upload files or drop them here",
+    track: "Track",
+    track_name: "Name",
+    channels: "Channels",
+    notes: "Notes",
+    instrument: "Instrument",
+    track_end: "Last note ends",
+    own_file: "Own file",
+    drag_to_reorder: "Drag to change the order the files are numbered and the tracks are written in",
+    move_track_up: |track| format!("Move track {} up", track),
+    move_track_down: |track| format!("Move track {} down", track),
+    file_format: "Format",
+    format_single_track: "0 (all parts in one track)",
+    format_parallel: "1 (tracks played together)",
+    format_sequential: "2 (independent patterns)",
+    format_single_track_hint: "All parts of this file are in a single track, so only one file would be created. Turn on splitting tracks by channel to get a file for every part.",
+    timing_division: "Timing",
+    timing_metrical: |ticks| format!("{} ticks per quarter note (PPQ)", ticks),
+    timing_timecode: |fps, ticks| format!("SMPTE {} fps, {} ticks per frame", fps, ticks),
+    track_count: "Tracks",
+    file_size: "Size",
+    own_file_for: |track| format!("Create a file for track {}", track),
+    track_end_at: |bar, time| format!("Bar {} ({})", bar, time),
+    zoom: "Zoom",
+    tempo: "Tempo",
+    tempo_constant: |bpm| format!("{:.0} BPM throughout", bpm),
+    tempo_changing: |initial, changes, min, max| {
+        format!(
+            "{:.0} BPM at the start, changing {} times between {:.0} and {:.0} BPM",
+            initial, changes, min, max
+        )
+    },
+    time_signatures: "Time signatures",
+    key: "Key",
+    major_tonics: [
+        "C", "D♭", "D", "E♭", "E", "F", "F♯", "G", "A♭", "A", "B♭", "B",
+    ],
+    minor_tonics: [
+        "C", "C♯", "D", "E♭", "E", "F", "F♯", "G", "G♯", "A", "B♭", "B",
+    ],
+    major_key: |tonic| format!("{} major", tonic),
+    minor_key: |tonic| format!("{} minor", tonic),
+    key_estimated: |key| format!("{} (estimated from the notes)", key),
+    key_from_signature: |key| format!("{} (from the key signature)", key),
+    key_signature_differs: |key| format!("the key signature says {}", key),
+    from_bar: |bar| format!("from bar {}", bar),
+    anomalies: "The file can be processed, but has some problems:",
+    anomaly_unmatched_note_ons: |track, count| {
+        format!(
+            "Track {}: notes which never end and last until the end of the track ({})",
+            track, count
+        )
+    },
+    anomaly_unmatched_note_offs: |track, count| {
+        format!(
+            "Track {}: notes which end without having started ({})",
+            track, count
+        )
+    },
+    anomaly_overlapping_notes: |track, count| {
+        format!(
+            "Track {}: notes which start while the same key is still sounding ({})",
+            track, count
+        )
+    },
+    anomaly_zero_length_notes: |track, count| {
+        format!(
+            "Track {}: notes which end as soon as they start and cannot be heard ({})",
+            track, count
+        )
+    },
+    anomaly_missing_end_of_track: |track| {
+        format!("Track {}: the end of the track is missing", track)
+    },
+    anomaly_unnamed_track: |track| {
+        format!(
+            "Track {}: the track has no name, so its files are named after the instrument",
+            track
+        )
+    },
+    listen: "Listen",
+    compare: "Compare",
+    compare_with_original: |file_name| format!("Compare {} with the original", file_name),
+    changes_in: |file_name| format!("Changes in {}", file_name),
+    change_count: |count| match count {
+        1 => "1 change".to_string(),
+        count => format!("{} changes", count),
+    },
+    no_changes: "No events were changed",
+    more_changes: |count| format!("and {} more changes", count),
+    diff_position: "Bar.Beat",
+    diff_channel: "Channel",
+    diff_event: "Event",
+    diff_original: "Original",
+    diff_processed: "Processed",
+    diff_inserted: "inserted",
+    diff_removed: "removed",
+    event_note_on: "Note On",
+    event_note_off: "Note Off",
+    event_aftertouch: "Aftertouch",
+    event_controller: "Controller",
+    event_program_change: "Program Change",
+    event_channel_aftertouch: "Channel Aftertouch",
+    event_pitch_bend: "Pitch Bend",
+    event_meta: "Meta Event",
+    event_sysex: "SysEx",
+    playback: "Playback",
+    mute: "Mute while listening",
+    solo: "Solo while listening",
+    listen_to: |file_name| format!("Listen to {}", file_name),
+    piano_roll: "Notes of all tracks over time",
+    files_ready: |count| format!("{} files have been created", count),
+    preparing_playback: "Preparing playback",
+    listening_to: |file_name| format!("Listening to {}", file_name),
+    listening_to_original: "Listening to the original",
+    switch_to_generated: "Switch to the generated file",
+    switch_to_original: "Switch to the original",
+
+    created_files: "The following files have been created:",
+    skipped_parts: "These parts of the uploads could not be read and were left out:",
+    skipped_leading_bytes: |length| format!("{} bytes before the start of the MIDI data", length),
+    skipped_trailing_bytes: |length| format!("{} bytes after the end of the MIDI data", length),
+    skipped_unknown_chunk: |length| format!("An unknown section of {} bytes", length),
+    skipped_invalid_format: "The MIDI format given in the file, which does not exist",
+    skipped_invalid_frame_rate: "The frame rate given in the file, which does not exist. 25 frames per second are used instead.",
+    skipped_malformed_events: |track, length| {
+        format!("{} bytes of damaged events at the end of track {}", length, track)
+    },
+    skipped_truncated_track: |track, length| {
+        format!("{} bytes missing from the end of track {}", length, track)
+    },
+    repaired_mistakes: "These mistakes of the uploads were fixed:",
+    repaired_track_count: |declared, found| {
+        format!("The header gives {} tracks, but the file holds {}", declared, found)
+    },
+    repaired_single_track_format: "The header gives the single track format for several tracks, which are written as parallel tracks instead",
+    repaired_missing_end_of_track: |track| format!("Track {} did not end properly", track),
+    repaired_duplicate_end_of_track: |track, count| {
+        format!("Track {} had {} ends too many", track, count)
+    },
+    repaired_dangling_notes: |track, count| {
+        format!("{} notes were still sounding at the end of track {} and now end there", count, track)
+    },
+    share: "Share",
+    download: "Download",
+    save_to_folder: "Save to folder",
+    saved_files: |saved, total| format!("Saved {} of {} files", saved, total),
+
+    restore_title: "Restore the original velocities of a generated file",
+    restore_upload: "Upload a file generated with the original velocities recorded",
+    download_file: |file_name| format!("Download {}", file_name),
+
+    progress_file: |file, files| format!("File {} of {}: ", file, files),
+    progress_loading: |file_name| format!("Loading {}", file_name),
+    progress_parsing: "Reading the file",
+    progress_transforming: "Preparing the tracks",
+    progress_generating: |track, tracks| format!("Generating track {} of {}", track, tracks),
+    progress_archiving: "Creating the archive",
+
+    number_out_of_range: |label, min, max| format!("{} must be between {} and {}", label, min, max),
+    invalid_number: |label| format!("Invalid number entered for: {}", label),
+    number_errors_pending: "Cannot process file until a valid number is entered",
+    only_midi_files: "Only MIDI files can be processed",
+    play_failed: |file_name, error| format!("Failed to play {}: {}", file_name, error),
+    copy_link_failed: |error| format!("Failed to copy the link: {}", error),
+    load_settings_failed: |file_name, error| format!("Failed to load {}: {}", file_name, error),
+    save_file_failed: |file_name, error| format!("Failed to save {}: {}", file_name, error),
+    share_unsupported: |file_name| format!("This browser cannot share {}", file_name),
+    worker_start_failed: |error| format!("Failed to start the worker: {}", error),
+    worker_failed: |error| format!("The worker failed: {}", error),
+    worker_send_failed: |error| format!("Failed to send the files to the worker: {}", error),
+    worker_no_result: "The worker stopped without a result",
+    invalid_tempo_percentage: |percent, max| {
+        format!(
+            "Invalid tempo percentage \"{}\". Must be between 1 and {}",
+            percent, max
+        )
+    },
+    unreadable_file: |file_name| format!("{} could not be read", file_name),
+    unreadable_upload: "The upload could not be read",
+    corrupted_at: |offset| format!("Reading stopped at byte {}.", offset),
+    non_utf8_track_name: |file_name, track| {
+        format!("The name of track {} of {} is not text", track, file_name)
+    },
+    suggestion_non_utf8_track_name: "Type a name for the track in the track list, which replaces the stored one.",
+    invalid_tempo_variant: |percent, maximum| {
+        format!("The tempo variant of {}% cannot be created. Tempo variants must be between 1% and {}%.", percent, maximum)
+    },
+    invalid_midi_value: |setting, value| {
+        format!("The setting {} is {}, but it must be between 0 and 127.", setting, value)
+    },
+    too_large_bytes: |file_name, size, maximum| {
+        format!("{} is {} in size, more than the {} MIDI files usually stay below", file_name, size, maximum)
+    },
+    too_large_tracks: |file_name, tracks, maximum| {
+        format!("{} has {} tracks, more than the {} MIDI files usually stay below", file_name, tracks, maximum)
+    },
+    too_large_events: |file_name, events, maximum| {
+        format!("{} has {} events, more than the {} MIDI files usually stay below", file_name, events, maximum)
+    },
+    too_large_duration: |file_name, minutes, maximum| {
+        format!("{} lasts {} minutes, longer than the {} MIDI files usually stay below", file_name, minutes, maximum)
+    },
+    too_large_beats: |file_name, beats, maximum| {
+        format!("{} has {} beats, more than the {} MIDI files usually stay below", file_name, beats, maximum)
+    },
+    suggestion_too_large: "It may not be a MIDI file or be damaged. Processing it can take very long and make the page stop responding.",
+    process_anyway: "Process Anyway",
+    problem_invalid_extension: "Only files ending in .mid, .midi, .kar or .rmi can be processed.",
+    problem_not_midi: "The file does not contain MIDI data. It may be an audio file or a web page saved under a MIDI name.",
+    problem_truncated: "The file ends too early, most likely because a download or copy was interrupted.",
+    problem_unsupported_format: "The file uses a MIDI format or frame rate which does not exist in the MIDI standard.",
+    problem_corrupted: "The structure of the file is damaged.",
+    suggestion_invalid_extension: "If it is a MIDI file, rename it so it ends in .mid. Otherwise export the song as a Standard MIDI File.",
+    suggestion_not_midi: "Export the song as a Standard MIDI File from your notation software or DAW and upload that file.",
+    suggestion_truncated: "Download or copy the file again and upload the complete file.",
+    suggestion_unsupported_format: "Open the file in a MIDI editor and save it again as a Standard MIDI File of type 0 or 1.",
+    suggestion_corrupted: "Open the file in a MIDI editor and save it again, or use another copy of the song.",
+    choose_other_files: "Choose Other Files",
+    option_help: &[
+        ("ensemble_preset_input", "Fills in all settings for a typical rehearsal of the ensemble. Every setting can still be changed afterwards."),
+        ("mode_input", "What each created file contains: every track with one of them louder, only one track, or every track but one to play along with."),
+        ("normalize_input", "Evens out tracks recorded at different levels before anything else is changed, so a quiet track is not lost when it is emphasised."),
+        ("normalize_target_input", "Velocity the tracks are scaled to. 64 is half of the maximum velocity of 127."),
+        ("normalize_level_input", "Whether the average velocity of a track or its loudest note is scaled to the target."),
+        ("velocity_mapping_input", "Reducing subtracts the same amount from every note. Compressing pulls loud and quiet notes toward the target so the other tracks keep some of their expression."),
+        ("compress_target_input", "Velocity the notes of the other tracks are pulled toward."),
+        ("compress_ratio_input", "How strongly the notes are pulled toward the target. With a ratio of 4, a note 40 above the target ends up 10 above it."),
+        ("compress_makeup_input", "Added to every compressed velocity afterwards. Negative values make the other tracks quieter overall."),
+        ("vol_input", "Subtracted from the velocity of every note of the other tracks. With 40, a note of velocity 100 is played at 60."),
+        ("reduction_threshold_input", "Notes at or below this velocity are left alone and no note is reduced below it. With 20, quiet notes stay audible."),
+        ("boost_input", "Added to the velocity of every note of the emphasised track, up to the maximum of 127."),
+        ("dynamics_mode_input", "Note velocities change how hard each note is played. Volume controllers change the loudness of the whole channel, which also affects sustained sounds like strings."),
+        ("volume_scale_input", "Volume of the other tracks in percent of their original volume. 50% halves their channel volume."),
+        ("scale_aftertouch_input", "Also lowers the aftertouch pressure of the other tracks, which some instruments use to swell notes."),
+        ("aftertouch_scale_input", "Aftertouch of the other tracks in percent of the original pressure."),
+        ("practice_panning_input", "With headphones the emphasised track is heard on the left and the accompaniment on the right."),
+        ("keep_drums_input", "Drum tracks keep their original velocities, so the beat stays as clear as before."),
+        ("background_program_input", "Plays all other tracks with one instrument, e.g. a soft piano, so the emphasised track stands out by its sound."),
+        ("humanize_input", "Adds small random changes to the velocities of the other tracks so repeated notes sound less mechanical."),
+        ("stamp_velocities_input", "Keeps the original velocities inside the created files so the tool at the bottom of the page can restore them."),
+        ("humanize_range_input", "Largest change of a velocity in either direction. With 5, a note of velocity 80 is played between 75 and 85."),
+        ("humanize_seed_input", "The same seed always gives the same random changes, so files can be created again exactly."),
+        ("transpose_input", "Moves every note up or down by semitones, e.g. -2 for a B♭ instrument reading concert pitch. Drums are not transposed."),
+        ("skip_empty_tracks_input", "Tracks without notes, such as tempo or lyrics tracks, do not get a file of their own."),
+        ("split_hands_input", "Divides a piano track at a key into a track for each hand, so each hand can be practised on its own."),
+        ("hand_split_track_input", "Number of the track to split, counting from 0. The first track of the preview is 0."),
+        ("hand_split_key_input", "Notes from this key upwards go to the right hand. 60 is middle C."),
+        ("split_voices_input", "Divides the chords of a track into a track with the highest note and a track with the rest, e.g. for two singers on one staff."),
+        ("voice_split_track_input", "Number of the track to split, counting from 0. The first track of the preview is 0."),
+        ("split_channels_input", "Some files put several instruments on one track using different channels. Each channel then gets its own track."),
+        ("click_track_input", "Adds a metronome following the tempo and time signature of the song to every created file."),
+        ("click_downbeat_velocity_input", "Loudness of the click on the first beat of each bar."),
+        ("click_beat_velocity_input", "Loudness of the click on the other beats."),
+        ("strip_events_input", "Removes events which are not needed for playback. This can help older devices which cannot handle them."),
+        ("export_lyrics_input", "Collects the lyrics of karaoke files into a text file next to the MIDI files."),
+        ("export_musicxml_input", "Adds sheet music of each track which notation software like MuseScore can open."),
+        ("export_abc_input", "Adds each track with one note at a time as ABC, a text format for sheet music."),
+        ("export_note_csv_input", "Lists every note with its start, length, key and velocity in a table for spreadsheets."),
+        ("render_audio_input", "Adds a recording of each file played by the built-in synthesiser, for players without MIDI support."),
+        ("single_track_output_input", "Merges each created file into a single track. Some older keyboards and sequencers only play files like this."),
+        ("include_all_tracks_input", "Adds a copy of the song with every track as it was, e.g. for listening to the full arrangement."),
+        ("all_tracks_suffix_input", "Added to the name of the copy with all tracks, e.g. \"All\" gives Song_All.mid."),
+        ("include_original_input", "Adds the uploaded file itself, so the archive contains everything needed to start over."),
+        ("cue_sheet_input", "Adds a page listing the tracks with their instruments, lengths and files, which can be printed for rehearsals."),
+        ("stamp_provenance_input", "Writes the version of this tool and the settings into every file, so it can be seen later how it was created."),
+        ("lenient_parsing_input", "Files which break the MIDI standard in small ways are processed anyway. The parts which cannot be read are left out and listed with the created files."),
+        ("repair_input", "Fixes tracks without a proper end, notes which never end and a wrong number of tracks in the header before splitting. The fixes are listed with the created files."),
+        ("manifest_input", "Adds a list of all created files with their tracks, e.g. for importing them into other software."),
+        ("archive_format_input", "Zip archives open on every system. Tar.gz archives are common on Linux and often smaller."),
+        ("reproducible_archive_input", "The archive gets the same contents and order every time, which helps when comparing archives or keeping them in version control."),
+        ("bar_range_input", "Only the given bars are kept, e.g. to practise a difficult passage."),
+        ("first_bar_input", "First bar which is kept, counting from 1."),
+        ("last_bar_input", "Last bar which is kept, counting from 1."),
+        ("tempo_variants_input", "Percentages of the original tempo, separated by commas. 50, 75 adds copies at half and three quarters of the speed."),
+        ("name_template_input", "Pattern for the names of the created files, e.g. {song} - {track} gives \"Song - Violin.mid\"."),
+        ("number_files_input", "Puts the number of the track in front of each file name so file managers list them in the order of the song."),
+    ],
+    show_help: "What does this option do?",
+    first_run_hint: "Select the ⓘ next to an option to see what it does and an example.",
+    dismiss_hint: "Got It",
+    update_available: "A new version of the app has been downloaded. Reload to use it, the files you picked will have to be chosen again.",
+    reload: "Reload",
+    later: "Later",
+    guided_mode: "Guide me step by step",
+    single_page_mode: "Show everything on one page",
+    steps: "Steps",
+    step_upload: "Upload",
+    step_pick_parts: "Pick Parts",
+    step_settings: "Choose Emphasis",
+    step_download: "Download",
+    back: "Back",
+    next: "Next",
+    create_files: "Create Files",
+    start_over: "Start Over",
+};
+
+pub const GERMAN: Strings = Strings {
+    theme: "Design",
+    theme_system: "Wie im System",
+    theme_light: "Hell",
+    theme_dark: "Dunkel",
+    language: "Sprache",
+    intro: "Erstellt für jede MIDI-Spur eine Datei, in der die anderen Spuren leiser sind",
+    error: "Fehler",
+    number_error: "Ungültige Zahl",
+
+    mode: "Für jede Spur eine Datei erstellen mit",
+    mode_emphasize: "Allen Spuren, die Spur hervorgehoben",
+    mode_solo: "Nur der Spur",
+    mode_minus_one: "Allen Spuren außer der Spur (Minus One)",
+    normalize: "Zuerst die Anschlagstärken jeder Spur angleichen",
+    target_velocity: "Ziel-Anschlagstärke (1-127)",
+    normalize_average: "für die durchschnittliche Anschlagstärke",
+    normalize_peak: "für die lauteste Note",
+    velocity_mapping: "Die Anschlagstärken der anderen Spuren ändern durch",
+    mapping_reduce: "Verringern um einen festen Wert",
+    mapping_compress: "Komprimieren zu einer Ziel-Anschlagstärke",
+    compress_ratio: "Verhältnis (1-20)",
+    compress_makeup: "Ausgleich (-64 bis 64)",
+    velocity_reduction: "Die Anschlagstärken verringern um (0-127)",
+    advanced_options: "Erweiterte Einstellungen",
+    advanced_options_changed: "(einige sind geändert)",
+    reduction_threshold: "Nur Anschlagstärken darüber verringern und nie darunter (0-127)",
+    velocity_boost: "Die Anschlagstärken der hervorgehobenen Spur erhöhen um (0-127)",
+    velocity_histogram: "Anschlagstärken der nicht hervorgehobenen Spuren",
+    histogram_before: "Vorher",
+    histogram_after: "Nachher",
+    silenced_notes: |silenced, total| format!("{} von {} Noten würden verstummen", silenced, total),
+    dynamics_mode: "Die Dynamik der anderen Spuren verringern über",
+    dynamics_velocity: "Die Anschlagstärken",
+    dynamics_controllers: "Die Volume- und Expression-Controller (CC7/CC11)",
+    dynamics_both: "Die Anschlagstärken und die Lautstärke-Controller",
+    volume_scale: "Die Lautstärke-Controller skalieren auf (0-100 %)",
+    scale_aftertouch: "Den Aftertouch der anderen Spuren skalieren",
+    aftertouch_scale: "Den Aftertouch skalieren auf (0-100 %)",
+    practice_panning: "Die hervorgehobene Spur nach links und alle anderen nach rechts legen",
+    keep_drums: "Das Schlagzeug mit den ursprünglichen Anschlagstärken beibehalten",
+    background_program: "Das Instrument der anderen Spuren wechseln zu",
+    keep_instruments: "Die ursprünglichen Instrumente behalten",
+    humanize: "Die Anschlagstärken der anderen Spuren zufällig variieren",
+    stamp_velocities:
+        "Die ursprünglichen Anschlagstärken speichern, um sie später wiederherzustellen",
+    humanize_range: "Variieren um bis zu (1-30)",
+    humanize_seed: "Startwert des Zufalls",
+
+    transpose: "Alle Noten um Halbtöne transponieren (-12 bis 12)",
+    skip_empty_tracks: "Spuren ohne Noten überspringen",
+    split_hands: "Eine Klavierspur in rechte und linke Hand aufteilen",
+    split_track: "Aufzuteilende Spur (ab 0 gezählt)",
+    hand_split_key: "Tiefste Taste der rechten Hand (60 = eingestrichenes C)",
+    split_voices: "Die Akkorde einer Spur in eine Ober- und eine Unterstimme aufteilen",
+    split_channels: "Spuren mit mehreren Kanälen in eine Spur pro Kanal aufteilen",
+    click_track: "Jeder Datei eine Metronomspur hinzufügen",
+    click_downbeat_velocity: "Anschlagstärke der ersten Zählzeit eines Takts (1-127)",
+    click_beat_velocity: "Anschlagstärke der anderen Zählzeiten (1-127)",
+    strip_events: "SysEx, Liedtexte, Marker und andere Text-Events entfernen",
+    export_lyrics: "Eine Textdatei mit dem Liedtext hinzufügen",
+    export_musicxml: "Die Stimme jeder Spur als MusicXML hinzufügen",
+    export_abc: "Die Stimme jeder einstimmigen Spur in ABC-Notation hinzufügen",
+    export_note_csv: "Eine CSV-Datei mit den Noten jeder Spur hinzufügen",
+    render_audio: "Eine WAV-Aufnahme jeder Datei hinzufügen (macht die Zip-Datei viel größer)",
+    single_track_output: "Einspurige MIDI-Dateien (Format 0) für ältere Sequenzer schreiben",
+    include_all_tracks: "Eine Datei mit allen Spuren unverändert hinzufügen",
+    all_tracks_suffix: "Namenszusatz der Datei mit allen Spuren",
+    empty_all_tracks_suffix: "Der Namenszusatz der Datei mit allen Spuren darf nicht leer sein",
+    include_original: "Die hochgeladene Datei unverändert hinzufügen",
+    cue_sheet: "Eine druckbare Übersicht der Spuren hinzufügen",
+    stamp_provenance: "Die Version und die Einstellungen in jeder erstellten Datei vermerken",
+    lenient_parsing: "Beschädigte Dateien so weit wie möglich lesen",
+    repair: "Häufige Fehler der hochgeladenen Dateien beheben",
+    manifest: "Ein Verzeichnis aller Dateien hinzufügen",
+    manifest_none: "Keins",
+    archive_format: "Die Dateien bündeln als",
+    archive_zip: "Zip",
+    archive_tar_gz: "Gzip-komprimiertes Tar (.tar.gz)",
+    reproducible_archive:
+        "Die Dateien im Archiv sortieren, damit dieselbe Datei immer dasselbe Archiv ergibt",
+    bar_range: "Nur einen Taktbereich exportieren",
+    first_bar: "Von Takt",
+    last_bar: "Bis Takt",
+    tempo_variants: "Zusätzlich langsamere Kopien in diesen Tempi erstellen (z. B. 50, 75)",
+    tempo_variants_placeholder: "Keine",
+    name_template: "Dateinamen ({song}, {track}, {index} und {mode} werden ersetzt)",
+    number_files: "Die Dateien in Spurreihenfolge nummerieren (01_, 02_, ...)",
+
+    settings: "Einstellungen",
+    download_settings: "Einstellungen herunterladen",
+    copy_link: "Einen Link zu diesen Einstellungen kopieren",
+    link_copied: "Link kopiert",
+    reset_settings: "Auf die Standardwerte zurücksetzen",
+    reprocess: "Mit den aktuellen Einstellungen erneut verarbeiten",
+    processing_settings: "Verarbeitungseinstellungen",
+    ensemble_preset: "Einstellungen für ein Ensemble",
+    choose_preset: "Ensemble auswählen",
+    preset_satb_choir: "Gemischter Chor (SATB)",
+    preset_concert_band: "Blasorchester",
+    preset_piano_duet: "Klavier zu vier Händen",
+    preset_worship_team: "Lobpreisband",
+    shortcuts: "Tastenkürzel",
+    shortcut_open_files: "Dateien öffnen",
+    shortcut_reprocess: "Die Dateien erneut verarbeiten",
+    shortcut_download: "Die erstellten Dateien herunterladen",
+    shortcut_play_pause: "Die Wiedergabe starten oder anhalten",
+    shortcut_help: "Die Tastenkürzel ein- oder ausblenden",
+    close: "Schließen",
+    cancel: "Abbrechen",
+    history: "Zuletzt verarbeitet",
+    process_again: "Erneut verarbeiten",
+    history_files_too_large: "Die Dateien waren zu groß zum Aufbewahren",
+    clear_history: "Verlauf löschen",
+    load_settings: "Einstellungen aus einer heruntergeladenen Datei laden",
+
+    upload: "Dateien hochladen oder hier ablegen",
+    track: "Spur",
+    track_name: "Name",
+    channels: "Kanäle",
+    notes: "Noten",
+    instrument: "Instrument",
+    track_end: "Letzte Note endet",
+    own_file: "Eigene Datei",
+    drag_to_reorder: "Ziehen, um die Reihenfolge der Nummerierung und der Spuren in den Dateien zu ändern",
+    move_track_up: |track| format!("Spur {} nach oben verschieben", track),
+    move_track_down: |track| format!("Spur {} nach unten verschieben", track),
+    file_format: "Format",
+    format_single_track: "0 (alle Stimmen in einer Spur)",
+    format_parallel: "1 (gleichzeitig gespielte Spuren)",
+    format_sequential: "2 (unabhängige Pattern)",
+    format_single_track_hint: "Alle Stimmen dieser Datei liegen in einer einzigen Spur, daher würde nur eine Datei erstellt. Mit dem Aufteilen der Spuren nach Kanal entsteht eine Datei für jede Stimme.",
+    timing_division: "Zeitbasis",
+    timing_metrical: |ticks| format!("{} Ticks pro Viertelnote (PPQ)", ticks),
+    timing_timecode: |fps, ticks| format!("SMPTE {} fps, {} Ticks pro Frame", fps, ticks),
+    track_count: "Spuren",
+    file_size: "Größe",
+    own_file_for: |track| format!("Eine Datei für Spur {} erstellen", track),
+    track_end_at: |bar, time| format!("Takt {} ({})", bar, time),
+    zoom: "Zoom",
+    tempo: "Tempo",
+    tempo_constant: |bpm| format!("Durchgehend {:.0} BPM", bpm),
+    tempo_changing: |initial, changes, min, max| {
+        format!(
+            "{:.0} BPM zu Beginn, ändert sich {}-mal zwischen {:.0} und {:.0} BPM",
+            initial, changes, min, max
+        )
+    },
+    time_signatures: "Taktarten",
+    key: "Tonart",
+    major_tonics: [
+        "C", "Des", "D", "Es", "E", "F", "Fis", "G", "As", "A", "B", "H",
+    ],
+    minor_tonics: [
+        "c", "cis", "d", "es", "e", "f", "fis", "g", "gis", "a", "b", "h",
+    ],
+    major_key: |tonic| format!("{}-Dur", tonic),
+    minor_key: |tonic| format!("{}-Moll", tonic),
+    key_estimated: |key| format!("{} (aus den Noten geschätzt)", key),
+    key_from_signature: |key| format!("{} (laut Vorzeichen)", key),
+    key_signature_differs: |key| format!("laut Vorzeichen {}", key),
+    from_bar: |bar| format!("ab Takt {}", bar),
+    anomalies: "Die Datei kann verarbeitet werden, hat aber einige Probleme:",
+    anomaly_unmatched_note_ons: |track, count| {
+        format!(
+            "Spur {}: Noten, die nie enden und bis zum Ende der Spur klingen ({})",
+            track, count
+        )
+    },
+    anomaly_unmatched_note_offs: |track, count| {
+        format!(
+            "Spur {}: Noten, die enden, ohne begonnen zu haben ({})",
+            track, count
+        )
+    },
+    anomaly_overlapping_notes: |track, count| {
+        format!(
+            "Spur {}: Noten, die beginnen, während dieselbe Taste noch klingt ({})",
+            track, count
+        )
+    },
+    anomaly_zero_length_notes: |track, count| {
+        format!(
+            "Spur {}: Noten, die sofort enden und nicht zu hören sind ({})",
+            track, count
+        )
+    },
+    anomaly_missing_end_of_track: |track| format!("Spur {}: das Ende der Spur fehlt", track),
+    anomaly_unnamed_track: |track| {
+        format!("Spur {}: die Spur hat keinen Namen, daher werden ihre Dateien nach dem Instrument benannt", track)
+    },
+    listen: "Anhören",
+    compare: "Vergleichen",
+    compare_with_original: |file_name| format!("{} mit dem Original vergleichen", file_name),
+    changes_in: |file_name| format!("Änderungen in {}", file_name),
+    change_count: |count| match count {
+        1 => "1 Änderung".to_string(),
+        count => format!("{} Änderungen", count),
+    },
+    no_changes: "Es wurden keine Ereignisse geändert",
+    more_changes: |count| format!("und {} weitere Änderungen", count),
+    diff_position: "Takt.Zählzeit",
+    diff_channel: "Kanal",
+    diff_event: "Ereignis",
+    diff_original: "Original",
+    diff_processed: "Verarbeitet",
+    diff_inserted: "eingefügt",
+    diff_removed: "entfernt",
+    event_note_on: "Note On",
+    event_note_off: "Note Off",
+    event_aftertouch: "Aftertouch",
+    event_controller: "Controller",
+    event_program_change: "Programmwechsel",
+    event_channel_aftertouch: "Kanal-Aftertouch",
+    event_pitch_bend: "Pitch Bend",
+    event_meta: "Meta-Ereignis",
+    event_sysex: "SysEx",
+    playback: "Wiedergabe",
+    mute: "Beim Anhören stummschalten",
+    solo: "Beim Anhören allein spielen",
+    listen_to: |file_name| format!("{} anhören", file_name),
+    piano_roll: "Noten aller Spuren im zeitlichen Verlauf",
+    files_ready: |count| format!("{} Dateien wurden erstellt", count),
+    preparing_playback: "Wiedergabe wird vorbereitet",
+    listening_to: |file_name| format!("Wiedergabe von {}", file_name),
+    listening_to_original: "Wiedergabe des Originals",
+    switch_to_generated: "Zur erstellten Datei wechseln",
+    switch_to_original: "Zum Original wechseln",
+
+    created_files: "Die folgenden Dateien wurden erstellt:",
+    skipped_parts: "Diese Teile der hochgeladenen Dateien konnten nicht gelesen werden und wurden weggelassen:",
+    skipped_leading_bytes: |length| format!("{} Bytes vor dem Beginn der MIDI-Daten", length),
+    skipped_trailing_bytes: |length| format!("{} Bytes nach dem Ende der MIDI-Daten", length),
+    skipped_unknown_chunk: |length| format!("Ein unbekannter Abschnitt mit {} Bytes", length),
+    skipped_invalid_format: "Das in der Datei angegebene MIDI-Format, das es nicht gibt",
+    skipped_invalid_frame_rate: "Die in der Datei angegebene Bildrate, die es nicht gibt. Stattdessen werden 25 Bilder pro Sekunde verwendet.",
+    skipped_malformed_events: |track, length| {
+        format!("{} Bytes beschädigter Ereignisse am Ende von Spur {}", length, track)
+    },
+    skipped_truncated_track: |track, length| {
+        format!("{} fehlende Bytes am Ende von Spur {}", length, track)
+    },
+    repaired_mistakes: "Diese Fehler der hochgeladenen Dateien wurden behoben:",
+    repaired_track_count: |declared, found| {
+        format!("Der Dateikopf gibt {} Spuren an, die Datei enthält aber {}", declared, found)
+    },
+    repaired_single_track_format: "Der Dateikopf gibt das Format mit einer Spur für mehrere Spuren an, die stattdessen als parallele Spuren geschrieben werden",
+    repaired_missing_end_of_track: |track| format!("Spur {} endete nicht richtig", track),
+    repaired_duplicate_end_of_track: |track, count| {
+        format!("Spur {} hatte {} Enden zu viel", track, count)
+    },
+    repaired_dangling_notes: |track, count| {
+        format!("{} Noten klangen am Ende von Spur {} noch und enden jetzt dort", count, track)
+    },
+    share: "Teilen",
+    download: "Herunterladen",
+    save_to_folder: "In einem Ordner speichern",
+    saved_files: |saved, total| format!("{} von {} Dateien gespeichert", saved, total),
+
+    restore_title: "Die ursprünglichen Anschlagstärken einer erstellten Datei wiederherstellen",
+    restore_upload:
+        "Eine Datei hochladen, in der die ursprünglichen Anschlagstärken gespeichert sind",
+    download_file: |file_name| format!("{} herunterladen", file_name),
+
+    progress_file: |file, files| format!("Datei {} von {}: ", file, files),
+    progress_loading: |file_name| format!("{} wird geladen", file_name),
+    progress_parsing: "Die Datei wird gelesen",
+    progress_transforming: "Die Spuren werden vorbereitet",
+    progress_generating: |track, tracks| format!("Spur {} von {} wird erstellt", track, tracks),
+    progress_archiving: "Das Archiv wird erstellt",
+
+    number_out_of_range: |label, min, max| {
+        format!("{} muss zwischen {} und {} liegen", label, min, max)
+    },
+    invalid_number: |label| format!("Ungültige Zahl eingegeben für: {}", label),
+    number_errors_pending: "Die Datei kann erst verarbeitet werden, wenn alle Zahlen gültig sind",
+    only_midi_files: "Nur MIDI-Dateien können verarbeitet werden",
+    play_failed: |file_name, error| {
+        format!("{} konnte nicht abgespielt werden: {}", file_name, error)
+    },
+    copy_link_failed: |error| format!("Der Link konnte nicht kopiert werden: {}", error),
+    load_settings_failed: |file_name, error| {
+        format!("{} konnte nicht geladen werden: {}", file_name, error)
+    },
+    save_file_failed: |file_name, error| {
+        format!("{} konnte nicht gespeichert werden: {}", file_name, error)
+    },
+    share_unsupported: |file_name| {
+        format!("Dieser Browser kann {} nicht teilen", file_name)
+    },
+    worker_start_failed: |error| format!("Der Worker konnte nicht gestartet werden: {}", error),
+    worker_failed: |error| format!("Der Worker ist fehlgeschlagen: {}", error),
+    worker_send_failed: |error| {
+        format!("Die Dateien konnten nicht an den Worker gesendet werden: {}", error)
+    },
+    worker_no_result: "Der Worker wurde ohne Ergebnis beendet",
+    invalid_tempo_percentage: |percent, max| {
+        format!(
+            "Ungültiger Tempo-Prozentsatz \"{}\". Muss zwischen 1 und {} liegen",
+            percent, max
+        )
+    },
+    unreadable_file: |file_name| format!("{} konnte nicht gelesen werden", file_name),
+    unreadable_upload: "Die hochgeladene Datei konnte nicht gelesen werden",
+    corrupted_at: |offset| format!("Das Lesen brach bei Byte {} ab.", offset),
+    non_utf8_track_name: |file_name, track| {
+        format!("Der Name von Spur {} in {} ist kein Text", track, file_name)
+    },
+    suggestion_non_utf8_track_name: "In der Spurliste einen Namen für die Spur eingeben, der den gespeicherten ersetzt.",
+    invalid_tempo_variant: |percent, maximum| {
+        format!("Die Tempovariante mit {} % kann nicht erstellt werden. Tempovarianten müssen zwischen 1 % und {} % liegen.", percent, maximum)
+    },
+    invalid_midi_value: |setting, value| {
+        format!("Die Einstellung {} ist {}, muss aber zwischen 0 und 127 liegen.", setting, value)
+    },
+    too_large_bytes: |file_name, size, maximum| {
+        format!("{} ist {} groß, mehr als die {}, unter denen MIDI-Dateien üblicherweise bleiben", file_name, size, maximum)
+    },
+    too_large_tracks: |file_name, tracks, maximum| {
+        format!("{} hat {} Spuren, mehr als die {}, unter denen MIDI-Dateien üblicherweise bleiben", file_name, tracks, maximum)
+    },
+    too_large_events: |file_name, events, maximum| {
+        format!("{} hat {} Ereignisse, mehr als die {}, unter denen MIDI-Dateien üblicherweise bleiben", file_name, events, maximum)
+    },
+    too_large_duration: |file_name, minutes, maximum| {
+        format!("{} dauert {} Minuten, länger als die {}, unter denen MIDI-Dateien üblicherweise bleiben", file_name, minutes, maximum)
+    },
+    too_large_beats: |file_name, beats, maximum| {
+        format!("{} hat {} Schläge, mehr als die {}, unter denen MIDI-Dateien üblicherweise bleiben", file_name, beats, maximum)
+    },
+    suggestion_too_large: "Es ist vielleicht keine MIDI-Datei oder sie ist beschädigt. Die Verarbeitung kann sehr lange dauern und die Seite nicht mehr reagieren lassen.",
+    process_anyway: "Trotzdem verarbeiten",
+    problem_invalid_extension: "Nur Dateien mit der Endung .mid, .midi, .kar oder .rmi können verarbeitet werden.",
+    problem_not_midi: "Die Datei enthält keine MIDI-Daten. Vielleicht ist es eine Audiodatei oder eine Webseite, die unter einem MIDI-Namen gespeichert wurde.",
+    problem_truncated: "Die Datei endet zu früh, vermutlich weil ein Download oder Kopiervorgang unterbrochen wurde.",
+    problem_unsupported_format: "Die Datei verwendet ein MIDI-Format oder eine Bildrate, die es im MIDI-Standard nicht gibt.",
+    problem_corrupted: "Der Aufbau der Datei ist beschädigt.",
+    suggestion_invalid_extension: "Eine MIDI-Datei so umbenennen, dass sie auf .mid endet, oder das Lied als Standard-MIDI-Datei exportieren.",
+    suggestion_not_midi: "Das Lied im Notensatzprogramm oder in der DAW als Standard-MIDI-Datei exportieren und diese hochladen.",
+    suggestion_truncated: "Die Datei erneut herunterladen oder kopieren und die vollständige Datei hochladen.",
+    suggestion_unsupported_format: "Die Datei in einem MIDI-Editor öffnen und erneut als Standard-MIDI-Datei vom Typ 0 oder 1 speichern.",
+    suggestion_corrupted: "Die Datei in einem MIDI-Editor öffnen und erneut speichern oder eine andere Kopie des Liedes verwenden.",
+    choose_other_files: "Andere Dateien auswählen",
+    option_help: &[
+        ("ensemble_preset_input", "Füllt alle Einstellungen für eine typische Probe des Ensembles aus. Jede Einstellung kann danach noch geändert werden."),
+        ("mode_input", "Was jede erstellte Datei enthält: alle Spuren mit einer lauteren, nur eine Spur oder alle Spuren bis auf eine zum Mitspielen."),
+        ("normalize_input", "Gleicht unterschiedlich laut aufgenommene Spuren an, bevor etwas anderes geändert wird, damit eine leise Spur beim Hervorheben nicht untergeht."),
+        ("normalize_target_input", "Anschlagstärke, auf die die Spuren skaliert werden. 64 ist die Hälfte der höchsten Anschlagstärke von 127."),
+        ("normalize_level_input", "Ob die durchschnittliche Anschlagstärke einer Spur oder ihr lautester Ton auf den Zielwert skaliert wird."),
+        ("velocity_mapping_input", "Verringern zieht von jedem Ton denselben Betrag ab. Komprimieren zieht laute und leise Töne zum Zielwert, sodass die anderen Spuren etwas von ihrem Ausdruck behalten."),
+        ("compress_target_input", "Anschlagstärke, zu der die Töne der anderen Spuren gezogen werden."),
+        ("compress_ratio_input", "Wie stark die Töne zum Zielwert gezogen werden. Bei einem Verhältnis von 4 liegt ein Ton, der 40 über dem Zielwert lag, danach 10 darüber."),
+        ("compress_makeup_input", "Wird danach zu jeder komprimierten Anschlagstärke addiert. Negative Werte machen die anderen Spuren insgesamt leiser."),
+        ("vol_input", "Wird von der Anschlagstärke jedes Tons der anderen Spuren abgezogen. Bei 40 wird ein Ton mit 100 mit 60 gespielt."),
+        ("reduction_threshold_input", "Töne mit dieser oder einer geringeren Anschlagstärke bleiben unverändert und kein Ton wird darunter verringert. Bei 20 bleiben leise Töne hörbar."),
+        ("boost_input", "Wird zur Anschlagstärke jedes Tons der hervorgehobenen Spur addiert, höchstens bis 127."),
+        ("dynamics_mode_input", "Die Anschlagstärke bestimmt, wie stark jeder Ton angeschlagen wird. Lautstärkeregler ändern die Lautstärke des ganzen Kanals, was auch gehaltene Klänge wie Streicher betrifft."),
+        ("volume_scale_input", "Lautstärke der anderen Spuren in Prozent ihrer ursprünglichen Lautstärke. 50 % halbiert die Kanallautstärke."),
+        ("scale_aftertouch_input", "Verringert auch den Aftertouch der anderen Spuren, mit dem manche Instrumente Töne anschwellen lassen."),
+        ("aftertouch_scale_input", "Aftertouch der anderen Spuren in Prozent des ursprünglichen Drucks."),
+        ("practice_panning_input", "Mit Kopfhörern ist die hervorgehobene Spur links und die Begleitung rechts zu hören."),
+        ("keep_drums_input", "Schlagzeugspuren behalten ihre ursprünglichen Anschlagstärken, damit der Rhythmus so deutlich bleibt wie vorher."),
+        ("background_program_input", "Spielt alle anderen Spuren mit einem Instrument, z. B. einem leisen Klavier, damit sich die hervorgehobene Spur durch ihren Klang abhebt."),
+        ("humanize_input", "Verändert die Anschlagstärken der anderen Spuren zufällig ein wenig, damit wiederholte Töne weniger mechanisch klingen."),
+        ("stamp_velocities_input", "Speichert die ursprünglichen Anschlagstärken in den erstellten Dateien, damit das Werkzeug unten auf der Seite sie wiederherstellen kann."),
+        ("humanize_range_input", "Größte Änderung einer Anschlagstärke in beide Richtungen. Bei 5 wird ein Ton mit 80 zwischen 75 und 85 gespielt."),
+        ("humanize_seed_input", "Derselbe Startwert ergibt immer dieselben zufälligen Änderungen, sodass Dateien genau gleich wieder erstellt werden können."),
+        ("transpose_input", "Verschiebt jeden Ton um Halbtöne nach oben oder unten, z. B. -2 für ein B-Instrument, das in klingender Notation liest. Schlagzeug wird nicht transponiert."),
+        ("skip_empty_tracks_input", "Spuren ohne Töne, etwa Tempo- oder Liedtextspuren, bekommen keine eigene Datei."),
+        ("split_hands_input", "Teilt eine Klavierspur an einer Taste in eine Spur für jede Hand, damit jede Hand einzeln geübt werden kann."),
+        ("hand_split_track_input", "Nummer der zu teilenden Spur, gezählt ab 0. Die erste Spur der Vorschau ist 0."),
+        ("hand_split_key_input", "Töne ab dieser Taste aufwärts gehören zur rechten Hand. 60 ist das mittlere C."),
+        ("split_voices_input", "Teilt die Akkorde einer Spur in eine Spur mit dem höchsten Ton und eine mit den übrigen, z. B. für zwei Stimmen in einem System."),
+        ("voice_split_track_input", "Nummer der zu teilenden Spur, gezählt ab 0. Die erste Spur der Vorschau ist 0."),
+        ("split_channels_input", "Manche Dateien legen mehrere Instrumente auf verschiedenen Kanälen in eine Spur. Jeder Kanal bekommt dann eine eigene Spur."),
+        ("click_track_input", "Fügt jeder erstellten Datei ein Metronom hinzu, das dem Tempo und der Taktart des Liedes folgt."),
+        ("click_downbeat_velocity_input", "Lautstärke des Klicks auf der ersten Zählzeit jedes Takts."),
+        ("click_beat_velocity_input", "Lautstärke des Klicks auf den anderen Zählzeiten."),
+        ("strip_events_input", "Entfernt Ereignisse, die zum Abspielen nicht nötig sind. Das kann älteren Geräten helfen, die sie nicht verarbeiten können."),
+        ("export_lyrics_input", "Sammelt die Liedtexte von Karaoke-Dateien in einer Textdatei neben den MIDI-Dateien."),
+        ("export_musicxml_input", "Fügt Noten jeder Spur hinzu, die Notensatzprogramme wie MuseScore öffnen können."),
+        ("export_abc_input", "Fügt jede einstimmige Spur als ABC hinzu, einem Textformat für Noten."),
+        ("export_note_csv_input", "Listet jeden Ton mit Beginn, Länge, Taste und Anschlagstärke in einer Tabelle für Tabellenkalkulationen auf."),
+        ("render_audio_input", "Fügt eine Aufnahme jeder Datei mit dem eingebauten Synthesizer hinzu, für Abspielgeräte ohne MIDI."),
+        ("single_track_output_input", "Fasst jede erstellte Datei in einer einzigen Spur zusammen. Manche älteren Keyboards und Sequenzer spielen nur solche Dateien ab."),
+        ("include_all_tracks_input", "Fügt eine Kopie des Liedes mit allen Spuren wie zuvor hinzu, z. B. um das ganze Arrangement zu hören."),
+        ("all_tracks_suffix_input", "Wird an den Namen der Kopie mit allen Spuren angehängt, z. B. ergibt \"Alle\" Lied_Alle.mid."),
+        ("include_original_input", "Fügt die hochgeladene Datei selbst hinzu, sodass das Archiv alles enthält, um neu anzufangen."),
+        ("cue_sheet_input", "Fügt eine Seite mit den Spuren, ihren Instrumenten, Längen und Dateien hinzu, die für Proben ausgedruckt werden kann."),
+        ("stamp_provenance_input", "Schreibt die Version dieses Werkzeugs und die Einstellungen in jede Datei, damit später zu sehen ist, wie sie erstellt wurde."),
+        ("lenient_parsing_input", "Dateien, die leicht vom MIDI-Standard abweichen, werden trotzdem verarbeitet. Die Teile, die nicht gelesen werden können, werden weggelassen und bei den erstellten Dateien aufgeführt."),
+        ("repair_input", "Behebt vor dem Aufteilen Spuren ohne richtiges Ende, Noten, die nie enden, und eine falsche Anzahl an Spuren im Dateikopf. Die Korrekturen werden bei den erstellten Dateien aufgeführt."),
+        ("manifest_input", "Fügt eine Liste aller erstellten Dateien mit ihren Spuren hinzu, z. B. zum Import in andere Programme."),
+        ("archive_format_input", "Zip-Archive lassen sich auf jedem System öffnen. Tar.gz-Archive sind unter Linux üblich und oft kleiner."),
+        ("reproducible_archive_input", "Das Archiv hat jedes Mal denselben Inhalt in derselben Reihenfolge, was beim Vergleichen von Archiven oder in der Versionsverwaltung hilft."),
+        ("bar_range_input", "Nur die angegebenen Takte werden behalten, z. B. um eine schwierige Stelle zu üben."),
+        ("first_bar_input", "Erster Takt, der behalten wird, gezählt ab 1."),
+        ("last_bar_input", "Letzter Takt, der behalten wird, gezählt ab 1."),
+        ("tempo_variants_input", "Prozentsätze des ursprünglichen Tempos, durch Kommas getrennt. 50, 75 fügt Kopien mit halber und dreiviertel Geschwindigkeit hinzu."),
+        ("name_template_input", "Muster für die Namen der erstellten Dateien, z. B. ergibt {song} - {track} \"Lied - Violine.mid\"."),
+        ("number_files_input", "Setzt die Nummer der Spur vor jeden Dateinamen, damit Dateimanager sie in der Reihenfolge des Liedes anzeigen."),
+    ],
+    show_help: "Was bewirkt diese Option?",
+    first_run_hint: "Das ⓘ neben einer Option auswählen, um zu sehen, was sie bewirkt, mit einem Beispiel.",
+    dismiss_hint: "Verstanden",
+    update_available: "Eine neue Version der App wurde heruntergeladen. Zum Verwenden neu laden, die gewählten Dateien müssen dann erneut ausgewählt werden.",
+    reload: "Neu laden",
+    later: "Später",
+    guided_mode: "Schritt für Schritt führen",
+    single_page_mode: "Alles auf einer Seite zeigen",
+    steps: "Schritte",
+    step_upload: "Hochladen",
+    step_pick_parts: "Stimmen auswählen",
+    step_settings: "Hervorhebung wählen",
+    step_download: "Herunterladen",
+    back: "Zurück",
+    next: "Weiter",
+    create_files: "Dateien erstellen",
+    start_over: "Von vorne beginnen",
+};
@@ -0,0 +1,54 @@
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{Array, Function, Promise, Reflect},
+    wasm_bindgen::{JsCast, JsValue},
+};
+
+/// Call a method returning a promise and wait for its result
+pub async fn call_async(
+    target: &JsValue,
+    method: &str,
+    args: &[JsValue],
+) -> Result<JsValue, JsValue> {
+    let function: Function = Reflect::get(target, &method.into())?.dyn_into()?;
+    let promise = function.apply(target, &args.iter().collect::<Array>())?;
+    JsFuture::from(Promise::from(promise)).await
+}
+
+/// Message of a JavaScript exception
+pub fn error_message(error: &JsValue) -> String {
+    Reflect::get(error, &"message".into())
+        .ok()
+        .and_then(|message| message.as_string())
+        .unwrap_or_else(|| format!("{:?}", error))
+}
+
+/// Whether the exception was thrown because the user cancelled a dialog
+pub fn is_abort(error: &JsValue) -> bool {
+    Reflect::get(error, &"name".into())
+        .ok()
+        .and_then(|name| name.as_string())
+        .as_deref()
+        == Some("AbortError")
+}
+
+/// Let the browser handle events and repaint before continuing
+pub async fn yield_now() {
+    let promise = Promise::new(&mut |resolve, _| {
+        leptos::window()
+            .set_timeout_with_callback(&resolve)
+            .expect("Failed to set a timeout");
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Copy the text to the clipboard
+pub async fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let navigator =
+        Reflect::get(&leptos::window(), &"navigator".into()).map_err(|e| error_message(&e))?;
+    let clipboard = Reflect::get(&navigator, &"clipboard".into()).map_err(|e| error_message(&e))?;
+    call_async(&clipboard, "writeText", &[text.into()])
+        .await
+        .map(|_| ())
+        .map_err(|e| error_message(&e))
+}
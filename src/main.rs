@@ -1,335 +1,3764 @@
-use std::io::{Cursor, Write};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Display,
+    str::FromStr,
+};
 
-use futures_channel::oneshot;
+use i18n::{Locale, Strings};
 use leptos::*;
-use midly::{num::u7, MetaMessage, MidiMessage, Smf, TrackEventKind};
+use midi_splitter_core::{
+    archive::ArchiveFormat, diagnosis::ReadProblem, gm, lenient::Skipped, manifest::ManifestFormat,
+    repair::Repair, restore_velocities, riff, sanitize_file_name, synth, transform,
+    validate_name_template, ClickTrack, DynamicsMode, File, HandSplit, Humanize, Limit, Limits,
+    Mode, Normalize, Phase, ProcessOptions, Progress, SplitError, VelocityMapping,
+    DEFAULT_NAME_TEMPLATE, MAX_TEMPO_PERCENT, MIDI_EXTENSIONS,
+};
+use midly::{Format, Smf, Timing};
+use preview::FilePreview;
+use settings::BuiltinPreset;
+use shortcuts::Shortcut;
+use theme::Theme;
+use wasm_bindgen_futures::JsFuture;
 use web_sys::{
-    js_sys::{Array, Uint8Array},
-    wasm_bindgen::{closure::Closure, JsCast},
-    Blob, BlobPropertyBag, Url,
+    js_sys::{encode_uri_component, Array, Uint8Array},
+    wasm_bindgen::JsValue,
+    Blob, BlobPropertyBag, FileList, Url,
 };
-use zip::{write::FileOptions, ZipWriter};
+use wizard::WizardStep;
+use worker::CancelHandle;
 
-struct File {
-    name: String,
-    data: Vec<u8>,
+mod anomalies;
+mod desktop;
+mod diff;
+mod directory;
+mod help;
+mod history;
+mod i18n;
+mod js;
+mod offline;
+mod preview;
+mod protocol;
+mod settings;
+mod share;
+mod shortcuts;
+mod theme;
+mod wizard;
+mod worker;
+
+/// Size of the parts files are read in, so a large upload is not held in the browser a second
+/// time while it is copied into the app
+const READ_CHUNK_SIZE: f64 = 8.0 * 1024.0 * 1024.0;
+
+/// Read the contents of a file picked by the user part by part, reporting the share read so far
+async fn read_file(file: web_sys::File, on_progress: impl Fn(f64)) -> File {
+    let size = file.size();
+    let mut data = Vec::with_capacity(size as usize);
+    let mut start = 0.0;
+    while start < size {
+        let end = (start + READ_CHUNK_SIZE).min(size);
+        let chunk = file
+            .slice_with_f64_and_f64(start, end)
+            .expect("Failed to read file");
+        let buffer = JsFuture::from(chunk.array_buffer())
+            .await
+            .expect("Failed to read file");
+        let chunk = Uint8Array::new(&buffer);
+        let offset = data.len();
+        data.resize(offset + chunk.length() as usize, 0);
+        chunk.copy_to(&mut data[offset..]);
+        start = end;
+        on_progress(start / size);
+    }
+
+    File {
+        name: file.name(),
+        data,
+    }
 }
 
-/// Load the file from the input element
+/// Load the first file from the input element
 async fn load_file(file_input: HtmlElement<html::Input>) -> File {
-    let file_reader = web_sys::FileReader::new().expect("FileReader not supported");
-    let file_reader_2 = file_reader.clone();
-    let (sender, receiver) = oneshot::channel();
-    let mut sender = Some(sender);
-
-    let on_file_upload: Closure<dyn FnMut()> = Closure::new(move || {
-        let result_blob = file_reader_2.result().expect("Failed to read file");
-        let result_vec = Uint8Array::new(&result_blob).to_vec();
-        sender
-            .take()
-            .expect("Could not take the channel. Closure called twice")
-            .send(result_vec)
-            .expect("Failed to send file from the callback");
-    });
-
     let file = file_input
         .files()
         .expect("No files")
         .item(0)
         .expect("No files");
-    file_reader.set_onload(Some(on_file_upload.as_ref().unchecked_ref()));
-    on_file_upload.forget();
-    file_reader
-        .read_as_array_buffer(&file)
-        .expect("Failed to read file");
-
-    let name = file.name();
-    let data = receiver
-        .await
-        .expect("Failed to receive file from the callback");
-
-    File { name, data }
+    read_file(file, |_| ()).await
 }
 
-struct MidiProcessResult {
-    zip_name: String,
-    file_names: Vec<String>,
-    zip_file: Vec<u8>,
+/// Files of a file list, keeping only MIDI files
+fn midi_files(file_list: &FileList) -> Vec<web_sys::File> {
+    (0..file_list.length())
+        .filter_map(|index| file_list.item(index))
+        .filter(|file| {
+            let name = file.name().to_lowercase();
+            MIDI_EXTENSIONS
+                .iter()
+                .any(|extension| name.ends_with(&format!(".{}", extension)))
+        })
+        .collect()
 }
 
-/// Write the given smf to the zip file
-fn write_midi_file_to_zip(
-    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
-    smf: &Smf,
-    file_name: &str,
-) -> anyhow::Result<()> {
-    let mut midi_file: Vec<u8> = Vec::new();
-    smf.write(&mut midi_file)
-        .map_err(|e| anyhow::anyhow!("Failed to write midi file: {}", e))?;
+/// Load all given files, reporting which file is being read and how much of it
+async fn load_files(uploads: Vec<web_sys::File>, on_progress: impl Fn(Loading)) -> Vec<File> {
+    let mut files = Vec::new();
+    let total = uploads.len();
+    for (index, upload) in uploads.into_iter().enumerate() {
+        let name = upload.name();
+        let file = read_file(upload, |fraction| {
+            on_progress(Loading {
+                file: index,
+                files: total,
+                name: name.clone(),
+                fraction,
+            })
+        })
+        .await;
+        files.push(file);
+    }
+    files
+}
 
-    zip.start_file(file_name, FileOptions::default())?;
-    zip.write_all(&midi_file)?;
+/// How far reading the uploads has got
+#[derive(Clone)]
+struct Loading {
+    /// Index of the upload being read
+    file: usize,
+    files: usize,
+    name: String,
+    /// Share of the upload read so far
+    fraction: f64,
+}
 
-    Ok(())
+/// Error shown in the banner at the top of the page
+#[derive(Clone)]
+struct ErrorMessage {
+    message: String,
+    /// Why an upload could not be read and the name of the upload, if known.
+    /// The banner then explains the problem instead of showing the message.
+    problem: Option<(Option<String>, ReadProblem)>,
+    /// Failure of the processing, which the banner describes in the language of the page
+    error: Option<SplitError>,
 }
 
-/// Reduce note velocities for a given file
-fn process_file(file: File, velocity_reduction: u8) -> anyhow::Result<MidiProcessResult> {
-    let (file_name, extension) = file
-        .name
-        .rsplit_once(".")
-        .ok_or(anyhow::anyhow!("No file extension"))?;
+impl From<String> for ErrorMessage {
+    fn from(message: String) -> ErrorMessage {
+        ErrorMessage {
+            message,
+            problem: None,
+            error: None,
+        }
+    }
+}
 
-    let smf = Smf::parse(&file.data)?;
+impl From<SplitError> for ErrorMessage {
+    fn from(error: SplitError) -> ErrorMessage {
+        ErrorMessage {
+            message: error.to_string(),
+            problem: error
+                .read_problem()
+                .map(|problem| (error.file_name().map(str::to_string), problem)),
+            error: Some(error),
+        }
+    }
+}
 
-    let zip_file: Cursor<Vec<u8>> = Cursor::new(Vec::new());
-    let mut file_names: Vec<String> = Vec::new();
+impl From<anyhow::Error> for ErrorMessage {
+    fn from(error: anyhow::Error) -> ErrorMessage {
+        match error.downcast::<SplitError>() {
+            Ok(error) => error.into(),
+            Err(error) => error.to_string().into(),
+        }
+    }
+}
 
-    let mut zip = ZipWriter::new(zip_file);
+/// File being listened to
+#[derive(Clone)]
+struct Playback {
+    name: String,
+    url: String,
+    /// Rendering of the upload a generated file was created from, to compare against
+    original_url: Option<String>,
+}
 
-    for i in 0..smf.tracks.len() {
-        // Clone the smf so we can modify it
-        let mut track_smf = smf.clone();
-        let current_track = &track_smf.tracks[i];
+/// Tracks muted or soloed while listening to an upload, which does not change the generated files
+#[derive(Clone, Default, PartialEq)]
+struct TrackMix {
+    muted: BTreeSet<usize>,
+    soloed: BTreeSet<usize>,
+}
 
-        let mut track_name: Option<&str> = None;
+impl TrackMix {
+    /// Whether the track cannot be heard, because it is muted or other tracks are soloed
+    fn is_silenced(&self, track: usize) -> bool {
+        self.muted.contains(&track) || (!self.soloed.is_empty() && !self.soloed.contains(&track))
+    }
+}
 
-        // Find the track name
-        for event in current_track {
-            match event.kind {
-                TrackEventKind::Meta(meta) => match meta {
-                    MetaMessage::TrackName(name) => {
-                        track_name = Some(std::str::from_utf8(&name)?);
-                        break;
-                    }
-                    _ => {}
-                },
-                _ => {}
-            }
+/// Render a MIDI file with the built-in synthesizer so it can be listened to
+fn render_playback(file: &File, mix: &TrackMix) -> anyhow::Result<Vec<u8>> {
+    let data = if riff::is_riff_midi(&file.data) {
+        riff::unwrap(&file.data)?
+    } else {
+        file.data.as_slice()
+    };
+    let mut smf = Smf::parse(data)?;
+    for (index, track) in smf.tracks.iter_mut().enumerate() {
+        if mix.is_silenced(index) {
+            transform::remove_channel_messages(track);
         }
+    }
+    Ok(synth::render_wav(&smf))
+}
 
-        // Reduce the velocity for all tracks except the current one
-        for (index, track) in track_smf.tracks.iter_mut().enumerate() {
-            if index == i {
-                continue;
-            }
+/// Duration as minutes and seconds
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
 
-            for event in track {
-                match &mut event.kind {
-                    TrackEventKind::Midi {
-                        channel: _,
-                        message,
-                    } => match message {
-                        MidiMessage::NoteOn { key: _, vel } => {
-                            *vel = vel.as_int().saturating_sub(velocity_reduction).into();
+/// Size of a file as shown next to it, using decimal units like file managers do
+fn format_size(bytes: usize) -> String {
+    if bytes < 1000 {
+        format!("{} B", bytes)
+    } else if bytes < 1_000_000 {
+        format!("{:.1} kB", bytes as f64 / 1e3)
+    } else {
+        format!("{:.1} MB", bytes as f64 / 1e6)
+    }
+}
+
+/// MIME type of a generated file
+fn mime_type(file_name: &str) -> &'static str {
+    match file_name.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("txt") => "text/plain",
+        Some("html") => "text/html",
+        Some("abc") => "text/vnd.abc",
+        Some("csv") => "text/csv",
+        Some("wav") => "audio/wav",
+        Some("musicxml") => "application/vnd.recordare.musicxml+xml",
+        _ => "audio/midi",
+    }
+}
+
+/// Create a URL the given data can be downloaded from
+fn create_object_url(data: &[u8], mime_type: &str) -> String {
+    let u8array = Uint8Array::from(data);
+    let array = Array::new();
+    array.push(&u8array.buffer());
+    let blob = Blob::new_with_u8_array_sequence_and_options(
+        &array,
+        BlobPropertyBag::new().type_(mime_type),
+    )
+    .expect("Failed to create blob from file");
+    Url::create_object_url_with_blob(&blob).expect("Failed to create object URL")
+}
+
+/// Parse a comma separated list of tempo percentages
+fn parse_tempo_variants(value: &str, text: &Strings) -> Result<Vec<u8>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|percent| !percent.is_empty())
+        .map(
+            |percent| match percent.trim_end_matches('%').parse::<u8>() {
+                Ok(percent) if (1..=MAX_TEMPO_PERCENT).contains(&percent) => Ok(percent),
+                _ => Err((text.invalid_tempo_percentage)(percent, MAX_TEMPO_PERCENT)),
+            },
+        )
+        .collect()
+}
+
+/// Number input which validates the entered value against the given range
+#[component]
+fn NumberInput<T>(
+    text: &'static Strings,
+    id: &'static str,
+    label: &'static str,
+    min: T,
+    max: T,
+    value: ReadSignal<T>,
+    set_value: WriteSignal<T>,
+    set_number_errors: WriteSignal<BTreeMap<&'static str, String>>,
+) -> impl IntoView
+where
+    T: FromStr + PartialOrd + Display + Copy + 'static,
+{
+    // A hidden input can no longer be corrected, so forget about its error
+    on_cleanup(move || {
+        set_number_errors.update(|errors| {
+            errors.remove(id);
+        })
+    });
+
+    view! {
+        <div class="flex flex-col gap-2">
+            <OptionLabel text=text id=id label=label/>
+            <input
+                class="border-2 rounded p-2 text-slate-900"
+                id=id
+                type="number"
+                min=min.to_string()
+                max=max.to_string()
+                on:input=move |ev| {
+                    let error = match event_target_value(&ev).parse::<T>() {
+                        Ok(value) if value < min || value > max => {
+                            Some((text.number_out_of_range)(label, &min.to_string(), &max.to_string()))
+                        }
+                        Ok(value) => {
+                            set_value(value);
+                            None
                         }
-                        _ => {}
-                    },
-                    _ => {}
+                        Err(_) => Some((text.invalid_number)(label)),
+                    };
+                    set_number_errors
+                        .update(|errors| {
+                            match error {
+                                Some(error) => errors.insert(id, error),
+                                None => errors.remove(id),
+                            };
+                        });
                 }
-            }
-        }
 
-        let default_track_name = format!("track-{}", i);
-        let track_name = track_name.unwrap_or(&default_track_name);
+                prop:value=move || value().to_string()
+            />
+        </div>
+    }
+}
+
+/// Velocity picked with a slider or typed exactly, with errors shown next to the field
+#[component]
+fn VelocityInput(
+    text: &'static Strings,
+    id: &'static str,
+    label: &'static str,
+    #[prop(default = 0)] min: u8,
+    #[prop(default = 127)] max: u8,
+    value: ReadSignal<u8>,
+    set_value: WriteSignal<u8>,
+    set_number_errors: WriteSignal<BTreeMap<&'static str, String>>,
+) -> impl IntoView {
+    let (field_error, set_field_error) = create_signal(None::<String>);
+    let set_error = move |error: Option<String>| {
+        set_number_errors.update(|errors| {
+            match &error {
+                Some(error) => errors.insert(id, error.clone()),
+                None => errors.remove(id),
+            };
+        });
+        set_field_error(error);
+    };
+
+    // A hidden input can no longer be corrected, so forget about its error
+    on_cleanup(move || {
+        set_number_errors.update(|errors| {
+            errors.remove(id);
+        })
+    });
 
-        let name = format!("{}_{}.{}", file_name, track_name, extension);
-        file_names.push(name.clone());
+    view! {
+        <div class="flex flex-col gap-2">
+            <OptionLabel text=text id=id label=label/>
+            <div class="flex gap-2 items-center">
+                <input
+                    class="grow cursor-pointer"
+                    type="range"
+                    aria-label=label
+                    min=min.to_string()
+                    max=max.to_string()
+                    on:input=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse() {
+                            set_value(value);
+                            set_error(None);
+                        }
+                    }
+                    prop:value=move || value().to_string()
+                />
+                <input
+                    class="w-20 border-2 rounded p-2 text-slate-900"
+                    class=("border-danger", move || field_error().is_some())
+                    id=id
+                    type="number"
+                    inputmode="numeric"
+                    min=min.to_string()
+                    max=max.to_string()
+                    on:input=move |ev| {
+                        let error = match event_target_value(&ev).trim().parse::<i64>() {
+                            Ok(entered) if entered < min.into() || entered > max.into() => {
+                                Some((text.number_out_of_range)(label, &min.to_string(), &max.to_string()))
+                            }
+                            Ok(entered) => {
+                                set_value(entered as u8);
+                                None
+                            }
+                            Err(_) => Some((text.invalid_number)(label)),
+                        };
+                        set_error(error);
+                    }
+                    on:change=move |ev| {
+                        // Leaving the field clamps the value rather than keeping an error around
+                        let clamped = match event_target_value(&ev).trim().parse::<i64>() {
+                            Ok(entered) => entered.clamp(min.into(), max.into()) as u8,
+                            Err(_) => value.get_untracked(),
+                        };
+                        set_value(clamped);
+                        set_error(None);
+                        // The signal may not change, so the field has to be reset by hand
+                        event_target::<web_sys::HtmlInputElement>(&ev)
+                            .set_value(&clamped.to_string());
+                    }
+                    prop:value=move || value().to_string()
+                />
+            </div>
+            {move || field_error().map(|error| view! { <p class="text-sm text-danger">{error}</p> })}
+        </div>
+    }
+}
+
+/// Checkbox with a label next to it
+#[component]
+fn Checkbox(
+    text: &'static Strings,
+    id: &'static str,
+    label: &'static str,
+    checked: ReadSignal<bool>,
+    set_checked: WriteSignal<bool>,
+) -> impl IntoView {
+    view! {
+        <div class="flex gap-2 items-center">
+            <input
+                class="cursor-pointer"
+                id=id
+                type="checkbox"
+                on:change=move |ev| set_checked(event_target_checked(&ev))
+                prop:checked=checked
+            />
+            <label class="text-sm font-medium cursor-pointer" for=id>
+                {label}
+            </label>
+            <HelpTip text=text id=id/>
+        </div>
+    }
+}
 
-        write_midi_file_to_zip(&mut zip, &track_smf, &name)?;
+/// Label of an option with the button explaining it next to it
+#[component]
+fn OptionLabel(text: &'static Strings, id: &'static str, label: &'static str) -> impl IntoView {
+    view! {
+        <div class="mb-2 flex gap-2 items-center">
+            <label class="text-sm font-medium" for=id>
+                {label}
+            </label>
+            <HelpTip text=text id=id/>
+        </div>
     }
+}
 
-    let name = format!("{}_All.{}", file_name, extension);
-    write_midi_file_to_zip(&mut zip, &smf, &name)?;
-    file_names.push(name);
+/// Button showing the explanation of an option, if it has one
+#[component]
+fn HelpTip(text: &'static Strings, id: &'static str) -> impl IntoView {
+    let explanation = help::explanation(text, id)?;
+    let (open, set_open) = create_signal(false);
+    let help_id = format!("{}_help", id);
 
-    Ok(MidiProcessResult {
-        zip_name: file_name.to_string(),
-        file_names,
-        zip_file: zip.finish()?.into_inner(),
+    Some(view! {
+        <span class="relative">
+            <button
+                type="button"
+                class="text-sm hover:text-link-hover"
+                aria-label=text.show_help
+                aria-expanded=move || open().to_string()
+                aria-describedby=move || open().then(|| help_id.clone())
+                on:click=move |_| set_open.update(|open| *open = !*open)
+                on:blur=move |_| set_open(false)
+                on:keydown=move |ev| {
+                    if ev.key() == "Escape" {
+                        set_open(false);
+                    }
+                }
+            >
+                "ⓘ"
+            </button>
+            <Show when=open>
+                <span
+                    id=format!("{}_help", id)
+                    role="tooltip"
+                    class="absolute left-0 top-full z-10 mt-1 w-64 p-2 rounded border-2 bg-surface text-sm font-normal"
+                >
+                    {explanation}
+                </span>
+            </Show>
+        </span>
     })
 }
 
+/// Table of the tracks of an uploaded file
 #[component]
-fn App() -> impl IntoView {
-    let file_input_ref: NodeRef<html::Input> = create_node_ref();
-    let (error, set_error) = create_signal(None::<String>);
-    let (number_error, set_number_error) = create_signal(None::<String>);
+fn TrackPreview(
+    text: &'static Strings,
+    file_name: String,
+    preview: FilePreview,
+    /// Names the user has given to the tracks by their index
+    names: BTreeMap<usize, String>,
+    /// Tracks muted or soloed while listening
+    mix: Signal<TrackMix>,
+    /// Tracks which do not get files of their own
+    excluded: Signal<BTreeSet<usize>>,
+    /// Indices of all tracks in the order the files get numbered in
+    order: Signal<Vec<usize>>,
+    /// Called when the user wants to hear the file
+    on_listen: Callback<()>,
+    /// Called with the index of a track to mute or unmute while listening
+    on_mute: Callback<usize>,
+    /// Called with the index of a track to solo or unsolo while listening
+    on_solo: Callback<usize>,
+    /// Called with the index and the new name of a renamed track, which is empty to restore
+    /// the original name
+    on_rename: Callback<(usize, String)>,
+    /// Called with the index of a track to leave out of or include in the generated files again
+    on_toggle_file: Callback<usize>,
+    /// Called with the indices of all tracks in their new order
+    on_reorder: Callback<Vec<usize>>,
+) -> impl IntoView {
+    let listen_label = (text.listen_to)(&file_name);
+    let tracks = store_value(preview.tracks);
+    let (dragged, set_dragged) = create_signal(None::<usize>);
+    let position =
+        move |track: usize| order.with(|order| order.iter().position(|&other| other == track));
+    // Puts a track where another one is, moving that one and the tracks after it back
+    let move_track = move |track: usize, position: usize| {
+        let mut new_order = order.get_untracked();
+        new_order.retain(|&other| other != track);
+        new_order.insert(position.min(new_order.len()), track);
+        on_reorder(new_order);
+    };
+    view! {
+        <div class="w-full flex flex-col gap-2 p-4 border-2 overflow-x-auto">
+            <div class="flex gap-4 items-center mb-2">
+                <p class="text-lg">{file_name}</p>
+                <button
+                    class="text-sm underline hover:text-link-hover"
+                    aria-label=listen_label
+                    on:click=move |_| on_listen(())
+                >
+                    {text.listen}
+                </button>
+            </div>
+            {(!preview.anomalies.is_empty())
+                .then(|| {
+                    view! {
+                        <div class="flex flex-col gap-1 p-2 rounded bg-sunken text-sm" role="status">
+                            <p class="font-medium">{text.anomalies}</p>
+                            <ul class="list-disc pl-5">
+                                {preview
+                                    .anomalies
+                                    .iter()
+                                    .map(|anomaly| view! { <li>{anomaly.message(text)}</li> })
+                                    .collect_view()}
+                            </ul>
+                        </div>
+                    }
+                })}
+            {(preview.header.format == Format::SingleTrack
+                && tracks.with_value(|tracks| tracks.first().is_some_and(|track| track.channels.len() > 1)))
+                .then(|| {
+                    view! {
+                        <p class="p-2 rounded bg-sunken text-sm" role="note">
+                            {text.format_single_track_hint}
+                        </p>
+                    }
+                })}
+            <dl class="grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 text-sm">
+                <dt class="font-medium">{text.file_format}</dt>
+                <dd>
+                    {match preview.header.format {
+                        Format::SingleTrack => text.format_single_track,
+                        Format::Parallel => text.format_parallel,
+                        Format::Sequential => text.format_sequential,
+                    }}
+                </dd>
+                <dt class="font-medium">{text.timing_division}</dt>
+                <dd>
+                    {match preview.header.timing {
+                        Timing::Metrical(ticks_per_beat) => (text.timing_metrical)(ticks_per_beat.as_int()),
+                        Timing::Timecode(fps, subframes) => (text.timing_timecode)(fps.as_f32(), subframes),
+                    }}
+                </dd>
+                <dt class="font-medium">{text.track_count}</dt>
+                <dd>{preview.header.track_count}</dd>
+                <dt class="font-medium">{text.file_size}</dt>
+                <dd>{format_size(preview.header.size)}</dd>
+                <dt class="font-medium">{text.tempo}</dt>
+                <dd>
+                    {if preview.tempo.changes == 0 {
+                        (text.tempo_constant)(preview.tempo.initial_bpm)
+                    } else {
+                        (text.tempo_changing)(
+                            preview.tempo.initial_bpm,
+                            preview.tempo.changes,
+                            preview.tempo.min_bpm,
+                            preview.tempo.max_bpm,
+                        )
+                    }}
+                </dd>
+                {match (preview.estimated_key, preview.signature_key) {
+                    (Some(estimated), signature) => {
+                        let mut description = (text.key_estimated)(&text.key_name(estimated));
+                        if let Some(signature) = signature.filter(|signature| *signature != estimated) {
+                            description.push_str(", ");
+                            description.push_str(&(text.key_signature_differs)(&text.key_name(signature)));
+                        }
+                        Some(description)
+                    }
+                    (None, Some(signature)) => Some((text.key_from_signature)(&text.key_name(signature))),
+                    (None, None) => None,
+                }
+                    .map(|description| {
+                        view! {
+                            <dt class="font-medium">{text.key}</dt>
+                            <dd>{description}</dd>
+                        }
+                    })}
+                <dt class="font-medium">{text.time_signatures}</dt>
+                <dd>
+                    {preview
+                        .time_signatures
+                        .iter()
+                        .map(|time_signature| {
+                            let mut description = format!(
+                                "{}/{}",
+                                time_signature.numerator,
+                                time_signature.denominator,
+                            );
+                            if let Some(bar) = time_signature.bar {
+                                description.push_str(&format!(" ({})", (text.from_bar)(bar)));
+                            }
+                            description
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")}
+                </dd>
+            </dl>
+            <table class="text-sm text-left">
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th class="pr-4">{text.track}</th>
+                        <th class="pr-4">{text.track_name}</th>
+                        <th class="pr-4">{text.instrument}</th>
+                        <th class="pr-4">{text.channels}</th>
+                        <th class="pr-4">{text.notes}</th>
+                        <th class="pr-4">{text.track_end}</th>
+                        <th class="pr-4">{text.own_file}</th>
+                        <th>{text.playback}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    <For
+                        each=move || order()
+                        key=|index| *index
+                        children=move |index| {
+                            let track = tracks.with_value(|tracks| tracks[index].clone());
+                            let channels = track
+                                .channels
+                                .iter()
+                                .map(|channel| (channel + 1).to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            view! {
+                                <tr
+                                    class=("opacity-50", move || dragged() == Some(index))
+                                    on:dragover=move |ev| {
+                                        // Allows dropping tracks of this file
+                                        if dragged.get_untracked().is_some() {
+                                            ev.prevent_default();
+                                        }
+                                    }
+                                    on:drop=move |ev| {
+                                        ev.prevent_default();
+                                        if let (Some(track), Some(position)) = (dragged.get_untracked(), position(index)) {
+                                            move_track(track, position);
+                                        }
+                                        set_dragged(None);
+                                    }
+                                >
+                                    <td class="pr-2 whitespace-nowrap">
+                                        <span
+                                            class="cursor-grab"
+                                            style:color=preview::track_colour(track.index)
+                                            title=text.drag_to_reorder
+                                            draggable="true"
+                                            on:dragstart=move |ev| {
+                                                // Firefox only starts dragging with some data
+                                                if let Some(data_transfer) = ev.data_transfer() {
+                                                    let _ = data_transfer.set_data("text/plain", &index.to_string());
+                                                }
+                                                set_dragged(Some(index));
+                                            }
+                                            on:dragend=move |_| set_dragged(None)
+                                        >
+                                            "■"
+                                        </span>
+                                        <button
+                                            class="px-1"
+                                            aria-label=(text.move_track_up)(index + 1)
+                                            disabled=move || position(index).is_none_or(|position| position == 0)
+                                            on:click=move |_| {
+                                                if let Some(position) = position(index) {
+                                                    move_track(index, position.saturating_sub(1));
+                                                }
+                                            }
+                                        >
+                                            "↑"
+                                        </button>
+                                        <button
+                                            class="px-1"
+                                            aria-label=(text.move_track_down)(index + 1)
+                                            disabled=move || {
+                                                position(index).is_none_or(|position| position + 1 >= order.with(Vec::len))
+                                            }
+                                            on:click=move |_| {
+                                                if let Some(position) = position(index) {
+                                                    move_track(index, position + 1);
+                                                }
+                                            }
+                                        >
+                                            "↓"
+                                        </button>
+                                    </td>
+                                    <td class="pr-4">{track.index + 1}</td>
+                                    <td class="pr-4">
+                                        <input
+                                            class="border-2 rounded px-2 py-1 text-slate-900"
+                                            type="text"
+                                            aria-label=text.track_name
+                                            placeholder=track.name.clone()
+                                            value=names
+                                                .get(&track.index)
+                                                .cloned()
+                                                .or(track.name)
+                                            on:change=move |ev| {
+                                                on_rename((track.index, event_target_value(&ev)))
+                                            }
+                                        />
+                                    </td>
+                                    <td class="pr-4 whitespace-nowrap">
+                                        <span aria-hidden="true">{track.icon}</span>
+                                        " "
+                                        {track.instrument.unwrap_or_default()}
+                                    </td>
+                                    <td class="pr-4">{channels}</td>
+                                    <td class="pr-4">{track.note_count}</td>
+                                    <td class="pr-4 whitespace-nowrap">
+                                        {track
+                                            .end_seconds
+                                            .map(|end_seconds| {
+                                                let duration = format_duration(end_seconds);
+                                                match track.end_bar {
+                                                    Some(bar) => (text.track_end_at)(bar, &duration),
+                                                    None => duration,
+                                                }
+                                            })}
+                                    </td>
+                                    <td class="pr-4">
+                                        <input
+                                            type="checkbox"
+                                            aria-label=(text.own_file_for)(index + 1)
+                                            prop:checked=move || excluded.with(|excluded| !excluded.contains(&index))
+                                            on:change=move |_| on_toggle_file(index)
+                                        />
+                                    </td>
+                                    <td>
+                                        <div class="flex gap-1">
+                                            <button
+                                                class="px-2 rounded border-2 font-bold"
+                                                class=("bg-danger", move || mix.with(|mix| mix.muted.contains(&index)))
+                                                title=text.mute
+                                                aria-label=text.mute
+                                                aria-pressed=move || mix.with(|mix| mix.muted.contains(&index)).to_string()
+                                                on:click=move |_| on_mute(index)
+                                            >
+                                                "M"
+                                            </button>
+                                            <button
+                                                class="px-2 rounded border-2 font-bold"
+                                                class=("bg-accent", move || mix.with(|mix| mix.soloed.contains(&index)))
+                                                title=text.solo
+                                                aria-label=text.solo
+                                                aria-pressed=move || mix.with(|mix| mix.soloed.contains(&index)).to_string()
+                                                on:click=move |_| on_solo(index)
+                                            >
+                                                "S"
+                                            </button>
+                                        </div>
+                                    </td>
+                                </tr>
+                            }
+                        }
+                    />
+                </tbody>
+            </table>
+            <PianoRoll
+                text=text
+                notes=preview.notes
+                length_seconds=preview.length_seconds
+            />
+        </div>
+    }
+}
 
-    let (velocity_reduction, set_velocity_reduction) = create_signal(30);
+/// Notes of all tracks drawn over time with zooming and scrolling
+#[component]
+fn PianoRoll(
+    text: &'static Strings,
+    notes: Vec<preview::RollNote>,
+    length_seconds: f64,
+) -> impl IntoView {
+    // Pixels per second
+    let (zoom, set_zoom) = create_signal(40u32);
 
-    let (file_url, set_file_url) = create_signal(None::<String>);
-    let (zip_name, set_zip_name) = create_signal(None::<String>);
-    let (file_names, set_file_names) = create_signal(Vec::new());
+    let lowest = notes.iter().map(|note| note.key).min().unwrap_or(60);
+    let highest = notes.iter().map(|note| note.key).max().unwrap_or(60);
+    let keys = (highest - lowest) as u32 + 1;
+    // The notes are drawn in hundredths of a second and scaled to the zoom by the browser
+    let width = (length_seconds * 100.0).max(1.0);
+
+    view! {
+        <div class="flex flex-col gap-2">
+            <label class="text-sm font-medium">
+                {text.zoom}
+                " "
+                <input
+                    type="range"
+                    min="10"
+                    max="200"
+                    prop:value=zoom
+                    on:input=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse() {
+                            set_zoom(value);
+                        }
+                    }
+                />
+
+            </label>
+            <div class="overflow-x-auto bg-sunken rounded">
+                <svg
+                    width=move || (length_seconds * zoom() as f64).ceil().max(1.0)
+                    height=keys * 4
+                    viewBox=format!("0 0 {} {}", width, keys)
+                    preserveAspectRatio="none"
+                    role="img"
+                    aria-label=text.piano_roll
+                >
+                    {notes
+                        .into_iter()
+                        .map(|note| {
+                            view! {
+                                <rect
+                                    x=note.start_seconds * 100.0
+                                    y=(highest - note.key) as u32
+                                    width=((note.end_seconds - note.start_seconds) * 100.0).max(1.0)
+                                    height="1"
+                                    fill=preview::track_colour(note.track)
+                                ></rect>
+                            }
+                        })
+                        .collect_view()}
+                </svg>
+            </div>
+        </div>
+    }
+}
+
+/// Velocities grouped into each bar of the velocity histogram
+const HISTOGRAM_BIN_SIZE: usize = 8;
+
+/// Number of velocities falling into each bar of the velocity histogram
+fn velocity_bins(velocities: impl Iterator<Item = u8>) -> Vec<usize> {
+    let mut bins = vec![0; 128 / HISTOGRAM_BIN_SIZE];
+    for vel in velocities {
+        bins[vel as usize / HISTOGRAM_BIN_SIZE] += 1;
+    }
+    bins
+}
+
+/// Histogram of the note velocities before and after the velocity mapping
+#[component]
+fn VelocityHistogram(
+    text: &'static Strings,
+    velocities: Vec<u8>,
+    #[prop(into)] mapping: Signal<VelocityMapping>,
+) -> impl IntoView {
+    let before = velocity_bins(velocities.iter().copied());
+    let total = velocities.len();
+    let after = create_memo(move |_| {
+        let mapping = mapping();
+        let mapped = velocities.iter().map(|vel| mapping.map(*vel));
+        let silenced = mapped.clone().filter(|vel| *vel == 0).count();
+        (velocity_bins(mapped), silenced)
+    });
+    let bar_height =
+        move |count: usize, highest: usize| count as f64 * 100.0 / highest.max(1) as f64;
+
+    view! {
+        <div class="flex flex-col gap-2">
+            <p class="text-sm font-medium">{text.velocity_histogram}</p>
+            <div class="bg-sunken rounded p-2">
+                {move || {
+                    let (after, _) = after();
+                    let highest = before.iter().chain(&after).copied().max().unwrap_or(0);
+                    // Drawn with one unit per velocity and the tallest bar at a height of 100
+                    view! {
+                        <svg
+                            class="w-full h-24"
+                            viewBox="0 0 128 100"
+                            preserveAspectRatio="none"
+                            role="img"
+                            aria-label=text.velocity_histogram
+                        >
+                            {before
+                                .iter()
+                                .zip(&after)
+                                .enumerate()
+                                .map(|(bin, (before, after))| {
+                                    let x = (bin * HISTOGRAM_BIN_SIZE) as f64;
+                                    let before = bar_height(*before, highest);
+                                    let after = bar_height(*after, highest);
+                                    view! {
+                                        <rect
+                                            x=x
+                                            y=100.0 - before
+                                            width=HISTOGRAM_BIN_SIZE
+                                            height=before
+                                            class="fill-content/30"
+                                        ></rect>
+                                        <rect
+                                            x=x + 1.0
+                                            y=100.0 - after
+                                            width=HISTOGRAM_BIN_SIZE - 2
+                                            height=after
+                                            class="fill-accent/80"
+                                        ></rect>
+                                    }
+                                })
+                                .collect_view()}
+                        </svg>
+                    }
+                }}
+            </div>
+            <div class="flex gap-4 text-sm">
+                <span class="flex gap-1 items-center">
+                    <span class="inline-block w-3 h-3 rounded bg-content/30"></span>
+                    {text.histogram_before}
+                </span>
+                <span class="flex gap-1 items-center">
+                    <span class="inline-block w-3 h-3 rounded bg-accent/80"></span>
+                    {text.histogram_after}
+                </span>
+            </div>
+            {move || {
+                let (_, silenced) = after();
+                (silenced > 0)
+                    .then(|| {
+                        view! {
+                            <p class="text-sm text-danger">{(text.silenced_notes)(silenced, total)}</p>
+                        }
+                    })
+            }}
+        </div>
+    }
+}
+
+/// Events of a generated file which differ from its original, listed per track
+#[component]
+fn EventDiff(
+    text: &'static Strings,
+    file_name: String,
+    diffs: Vec<diff::TrackDiff>,
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let heading = (text.changes_in)(&file_name);
+    let note_name = |key: u8| {
+        format!(
+            "{}{}",
+            text.major_tonics[key as usize % 12],
+            key as i32 / 12 - 1
+        )
+    };
 
-    let process_file_action = create_action(move |_| async move {
+    view! {
+        <section class="w-full flex flex-col gap-2 p-4 border-2" aria-label=heading.clone()>
+            <div class="flex gap-4 items-center justify-between">
+                <p class="text-lg">{heading}</p>
+                <button
+                    class="text-sm underline hover:text-link-hover"
+                    on:click=move |_| on_close(())
+                >
+                    {text.close}
+                </button>
+            </div>
+            {diffs
+                .into_iter()
+                .enumerate()
+                .map(|(index, track)| {
+                    let name = track
+                        .name
+                        .unwrap_or_else(|| format!("{} {}", text.track, index + 1));
+                    let count = track.changes.len();
+                    let hidden = count.saturating_sub(diff::MAX_LISTED_CHANGES);
+                    view! {
+                        <details>
+                            <summary class="cursor-pointer text-sm font-medium">
+                                {name} " – " {(text.change_count)(count)}
+                            </summary>
+                            {if count == 0 {
+                                view! { <p class="text-sm">{text.no_changes}</p> }.into_view()
+                            } else {
+                                view! {
+                                    <div class="max-h-96 overflow-y-auto">
+                                        <table class="w-full text-sm text-left">
+                                            <thead class="sticky top-0 bg-page">
+                                                <tr>
+                                                    <th class="pr-4">{text.diff_position}</th>
+                                                    <th class="pr-4">{text.diff_channel}</th>
+                                                    <th class="pr-4">{text.diff_event}</th>
+                                                    <th class="pr-4">{text.diff_original}</th>
+                                                    <th>{text.diff_processed}</th>
+                                                </tr>
+                                            </thead>
+                                            <tbody>
+                                                {track
+                                                    .changes
+                                                    .into_iter()
+                                                    .take(diff::MAX_LISTED_CHANGES)
+                                                    .map(|change| {
+                                                        let position = match change.position {
+                                                            diff::Position::Beat(bar, beat) => format!("{}.{}", bar, beat),
+                                                            diff::Position::Seconds(seconds) => format!("{:.2} s", seconds),
+                                                        };
+                                                        let event = match (change.event, change.number) {
+                                                            (
+                                                                diff::EventType::NoteOn
+                                                                | diff::EventType::NoteOff
+                                                                | diff::EventType::Aftertouch,
+                                                                Some(key),
+                                                            ) => format!("{} {}", change.event.name(text), note_name(key)),
+                                                            (_, Some(number)) => {
+                                                                format!("{} {}", change.event.name(text), number)
+                                                            }
+                                                            (_, None) => change.event.name(text).to_string(),
+                                                        };
+                                                        view! {
+                                                            <tr>
+                                                                <td class="pr-4">{position}</td>
+                                                                <td class="pr-4">
+                                                                    {change.channel.map(|channel| channel + 1)}
+                                                                </td>
+                                                                <td class="pr-4">{event}</td>
+                                                                <td class="pr-4 break-all">
+                                                                    {change
+                                                                        .original
+                                                                        .unwrap_or_else(|| text.diff_inserted.to_string())}
+                                                                </td>
+                                                                <td class="break-all">
+                                                                    {change
+                                                                        .processed
+                                                                        .unwrap_or_else(|| text.diff_removed.to_string())}
+                                                                </td>
+                                                            </tr>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                            </tbody>
+                                        </table>
+                                    </div>
+                                    {(hidden > 0)
+                                        .then(|| view! { <p class="text-sm">{(text.more_changes)(hidden)}</p> })}
+                                }
+                                    .into_view()
+                            }}
+                        </details>
+                    }
+                })
+                .collect_view()}
+        </section>
+    }
+}
+
+/// Tool restoring the original velocities of a previously generated file
+#[component]
+fn RestoreVelocities(text: &'static Strings) -> impl IntoView {
+    let file_input_ref: NodeRef<html::Input> = create_node_ref();
+    let (error, set_error) = create_signal(None::<String>);
+    let (restored_file, set_restored_file) = create_signal(None::<(String, String)>);
+
+    let restore_action = create_action(move |_| async move {
         let file_input = file_input_ref.get_untracked().expect("<input> not mounted");
 
         let file = load_file(file_input).await;
-        let process_result = process_file(file, velocity_reduction.get_untracked());
-        let process_result = match process_result {
-            Ok(process_result) => {
+        match restore_velocities(file) {
+            Ok(file) => {
                 set_error(None);
-                process_result
+                let url = create_object_url(&file.data, "audio/midi");
+                set_restored_file(Some((file.name, url)));
             }
             Err(e) => {
                 set_error(Some(e.to_string()));
-                return;
+                set_restored_file(None);
             }
-        };
-
-        let u8array = Uint8Array::from(process_result.zip_file.as_slice());
-        let array = Array::new();
-        array.push(&u8array.buffer());
-        let blob = Blob::new_with_u8_array_sequence_and_options(
-            &array,
-            &BlobPropertyBag::new().type_("application/zip"),
-        )
-        .expect("Failed to create blob from MIDI file");
-        let url = Url::create_object_url_with_blob(&blob).expect("Failed to create object URL");
-        set_zip_name(Some(process_result.zip_name));
-        set_file_names(process_result.file_names);
-        set_file_url(Some(url));
+        }
     });
 
     view! {
-        <div class="min-h-screen p-10 flex flex-col items-center gap-4 bg-slate-800 text-slate-200">
-            <p class="text-lg mb-4">
-                Create files for each MIDI track with reduced note velocities for other tracks
-            </p>
+        <div class="w-full flex flex-col gap-2 p-4 border-2">
+            <p class="text-lg mb-2">{text.restore_title}</p>
+            <div role="alert">
+                {move || {
+                    error()
+                        .map(|error| {
+                            view! {
+                                <div class="w-full bg-danger text-on-accent p-4 rounded">
+                                    <p class="text-lg">{text.error}</p>
+                                    <p class="text-sm">{error}</p>
+                                </div>
+                            }
+                        })
+                }}
+            </div>
+
+            <label class="mb-2 text-sm font-medium" for="restore_input">
+                {text.restore_upload}
+            </label>
+            <input
+                class="border-2 rounded p-2 cursor-pointer"
+                id="restore_input"
+                type="file"
+                accept=".mid,.midi,.kar"
+                node_ref=file_input_ref
+                on:change=move |_ev| restore_action.dispatch("")
+            />
+
             {move || {
-                error()
-                    .map(|error| {
+                restored_file()
+                    .map(|(name, url)| {
                         view! {
-                            <div id="error" class="w-full bg-red-500 p-4 rounded">
-                                <p class="text-lg">Error</p>
-                                <p class="text-sm">{error}</p>
-                            </div>
+                            <a
+                                class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded text-center"
+                                href=url
+                                download=name.clone()
+                            >
+                                {(text.download_file)(&name)}
+                            </a>
                         }
                     })
             }}
 
-            {move || {
-                number_error()
-                    .map(|error| {
-                        view! {
-                            <div id="error" class="w-full bg-red-500 p-4 rounded">
-                                <p class="text-lg">Number Error</p>
-                                <p class="text-sm">{error}</p>
-                            </div>
-                        }
+        </div>
+    }
+}
+
+#[component]
+fn App() -> impl IntoView {
+    let file_input_ref: NodeRef<html::Input> = create_node_ref();
+    let (error, set_error) = create_signal(None::<ErrorMessage>);
+    let (number_errors, set_number_errors) = create_signal(BTreeMap::<&'static str, String>::new());
+
+    let (mode, set_mode) = create_signal(Mode::Emphasize);
+    let (transpose, set_transpose) = create_signal(0i8);
+    let (strip_non_essential_events, set_strip_non_essential_events) = create_signal(false);
+    let (skip_empty_tracks, set_skip_empty_tracks) = create_signal(true);
+    let (export_lyrics, set_export_lyrics) = create_signal(false);
+    let (split_hands, set_split_hands) = create_signal(false);
+    let (hand_split_track, set_hand_split_track) = create_signal(0usize);
+    let (hand_split_key, set_hand_split_key) = create_signal(60u8);
+    let (split_voices, set_split_voices) = create_signal(false);
+    let (voice_split_track, set_voice_split_track) = create_signal(0usize);
+    let (add_click_track, set_add_click_track) = create_signal(false);
+    let (click_downbeat_velocity, set_click_downbeat_velocity) = create_signal(100u8);
+    let (click_beat_velocity, set_click_beat_velocity) = create_signal(70u8);
+    let (split_channels, set_split_channels) = create_signal(false);
+    let (use_bar_range, set_use_bar_range) = create_signal(false);
+    let (first_bar, set_first_bar) = create_signal(1u32);
+    let (last_bar, set_last_bar) = create_signal(1u32);
+    let (normalize, set_normalize) = create_signal(false);
+    let (normalize_target, set_normalize_target) = create_signal(80u8);
+    let (normalize_peak, set_normalize_peak) = create_signal(false);
+    let (compress_velocities, set_compress_velocities) = create_signal(false);
+    let (velocity_reduction, set_velocity_reduction) = create_signal(30);
+    let (reduction_threshold, set_reduction_threshold) = create_signal(0u8);
+    let (compress_target, set_compress_target) = create_signal(50u8);
+    let (compress_ratio, set_compress_ratio) = create_signal(3u8);
+    let (compress_makeup, set_compress_makeup) = create_signal(0i8);
+    let (velocity_boost, set_velocity_boost) = create_signal(0);
+    let (dynamics_mode, set_dynamics_mode) = create_signal(DynamicsMode::Velocity);
+    let (volume_scale, set_volume_scale) = create_signal(50);
+    let (scale_aftertouch, set_scale_aftertouch) = create_signal(false);
+    let (aftertouch_scale, set_aftertouch_scale) = create_signal(50);
+    let (practice_panning, set_practice_panning) = create_signal(false);
+    let (keep_drums, set_keep_drums) = create_signal(false);
+    let (background_program, set_background_program) = create_signal(None::<u8>);
+    let (humanize, set_humanize) = create_signal(false);
+    let (humanize_range, set_humanize_range) = create_signal(5u8);
+    let (humanize_seed, set_humanize_seed) = create_signal(1u64);
+    let (tempo_variants, set_tempo_variants) = create_signal(Vec::<u8>::new());
+    let (tempo_variants_text, set_tempo_variants_text) = create_signal(String::new());
+    let (stamp_original_velocities, set_stamp_original_velocities) = create_signal(false);
+    let (name_template, set_name_template) = create_signal(DEFAULT_NAME_TEMPLATE.to_string());
+    let (include_all_tracks, set_include_all_tracks) = create_signal(true);
+    let (include_original, set_include_original) = create_signal(false);
+    let (manifest, set_manifest) = create_signal(None::<ManifestFormat>);
+    let (archive_format, set_archive_format) = create_signal(ArchiveFormat::Zip);
+    let (export_musicxml, set_export_musicxml) = create_signal(false);
+    let (render_audio, set_render_audio) = create_signal(false);
+    let (export_note_csv, set_export_note_csv) = create_signal(false);
+    let (export_abc, set_export_abc) = create_signal(false);
+    let (reproducible_archive, set_reproducible_archive) = create_signal(false);
+    let (single_track_output, set_single_track_output) = create_signal(false);
+    let (number_files, set_number_files) = create_signal(false);
+    let (cue_sheet, set_cue_sheet) = create_signal(false);
+    let (stamp_provenance, set_stamp_provenance) = create_signal(true);
+    let (lenient_parsing, set_lenient_parsing) = create_signal(false);
+    let (repair, set_repair) = create_signal(false);
+    // Only changed by imported settings
+    let (limits, set_limits) = create_signal(Some(Limits::default()));
+    let (track_names, set_track_names) =
+        create_signal(HashMap::<String, BTreeMap<usize, String>>::new());
+    let (excluded_tracks, set_excluded_tracks) =
+        create_signal(HashMap::<String, BTreeSet<usize>>::new());
+    let (track_orders, set_track_orders) = create_signal(HashMap::<String, Vec<usize>>::new());
+    let (all_tracks_suffix, set_all_tracks_suffix) = create_signal("All".to_string());
+
+    let (file_url, set_file_url) = create_signal(None::<String>);
+    let (archive_name, set_archive_name) = create_signal(None::<String>);
+    // Names of the generated files together with the URLs they can be downloaded from
+    // Name, object URL and size of each generated file
+    let (files, set_files) = create_signal(Vec::<(String, String, usize)>::new());
+    let (archive_size, set_archive_size) = create_signal(0);
+    let (skipped, set_skipped) = create_signal(Vec::<Skipped>::new());
+    let (repairs, set_repairs) = create_signal(Vec::<Repair>::new());
+    let generated_files = store_value(Vec::<File>::new());
+    let generated_archive = store_value(None::<(File, &'static str)>);
+    // Number of files saved to a picked directory and the number of files to save
+    let (save_progress, set_save_progress) = create_signal(None::<(usize, usize)>);
+    let (dragging_over, set_dragging_over) = create_signal(false);
+    let (theme, set_theme) = create_signal(theme::load());
+    create_effect(move |_| theme::apply(theme()));
+    let (locale, set_locale) = create_signal(i18n::load());
+    create_effect(move |_| i18n::apply(locale()));
+    let (progress, set_progress) = create_signal(None::<Progress>);
+    let (loading, set_loading) = create_signal(None::<Loading>);
+    let cancel_handle = store_value(CancelHandle::default());
+    let (history, set_history) = create_signal(history::load());
+    let uploaded_files = store_value(Vec::<File>::new());
+    let (has_uploads, set_has_uploads) = create_signal(false);
+    // Users who neither processed files nor changed settings before are guided step by step
+    let (guided, set_guided) = create_signal(wizard::load(
+        history.get_untracked().is_empty() && settings::load_saved().is_none(),
+    ));
+    create_effect(move |_| wizard::save(guided()));
+    let (wizard_step, set_wizard_step) = create_signal(WizardStep::Upload);
+    // Later steps need uploads, and the download needs the files created from them
+    let step_reachable = move |step: WizardStep| match step {
+        WizardStep::Upload => true,
+        WizardStep::PickParts | WizardStep::Settings => has_uploads(),
+        WizardStep::Download => !files().is_empty(),
+    };
+    // Whether a section belongs on the page, which shows one step at a time when guided
+    let shown_in = move |step: WizardStep| !guided() || wizard_step() == step;
+    let upload_folders = store_value(Vec::<String>::new());
+    let (playback, set_playback) = create_signal(None::<Playback>);
+    let (playing_original, set_playing_original) = create_signal(false);
+    let audio_ref: NodeRef<html::Audio> = create_node_ref();
+    let download_ref: NodeRef<html::A> = create_node_ref();
+    // Position and whether it was playing when switching between the generated file and the original
+    let resume_position = store_value(None::<(f64, bool)>);
+    let (previews, set_previews) = create_signal(Vec::<(String, FilePreview)>::new());
+    let (track_mixes, set_track_mixes) = create_signal(HashMap::<String, TrackMix>::new());
+
+    let current_options = move || ProcessOptions {
+        mode: mode.get(),
+        normalize: normalize.get().then(|| Normalize {
+            target: normalize_target.get(),
+            peak: normalize_peak.get(),
+        }),
+        velocity_mapping: if compress_velocities.get() {
+            VelocityMapping::Compress {
+                target: compress_target.get(),
+                ratio: compress_ratio.get(),
+                makeup: compress_makeup.get(),
+            }
+        } else {
+            VelocityMapping::Reduce {
+                amount: velocity_reduction.get(),
+                threshold: reduction_threshold.get(),
+            }
+        },
+        velocity_boost: velocity_boost.get(),
+        dynamics_mode: dynamics_mode.get(),
+        volume_scale: volume_scale.get(),
+        aftertouch_scale: scale_aftertouch.get().then(|| aftertouch_scale.get()),
+        practice_panning: practice_panning.get(),
+        keep_drums: keep_drums.get(),
+        background_program: background_program.get(),
+        humanize: humanize.get().then(|| Humanize {
+            range: humanize_range.get(),
+            seed: humanize_seed.get(),
+        }),
+        tempo_variants: tempo_variants.get(),
+        transpose: transpose.get(),
+        bar_range: use_bar_range
+            .get()
+            .then(|| (first_bar.get(), last_bar.get())),
+        hand_split: split_hands.get().then(|| HandSplit {
+            track: hand_split_track.get(),
+            key: hand_split_key.get(),
+        }),
+        voice_split: split_voices.get().then(|| voice_split_track.get()),
+        split_channels: split_channels.get(),
+        stamp_original_velocities: stamp_original_velocities.get(),
+        click_track: add_click_track.get().then(|| ClickTrack {
+            downbeat_velocity: click_downbeat_velocity.get(),
+            beat_velocity: click_beat_velocity.get(),
+        }),
+        strip_non_essential_events: strip_non_essential_events.get(),
+        skip_empty_tracks: skip_empty_tracks.get(),
+        export_lyrics: export_lyrics.get(),
+        name_template: name_template.get(),
+        all_tracks_suffix: include_all_tracks.get().then(|| all_tracks_suffix.get()),
+        include_original: include_original.get(),
+        manifest: manifest.get(),
+        archive_format: archive_format.get(),
+        export_musicxml: export_musicxml.get(),
+        render_audio: render_audio.get(),
+        export_note_csv: export_note_csv.get(),
+        export_abc: export_abc.get(),
+        reproducible_archive: reproducible_archive.get(),
+        single_track_output: single_track_output.get(),
+        number_files: number_files.get(),
+        cue_sheet: cue_sheet.get(),
+        stamp_provenance: stamp_provenance.get(),
+        lenient_parsing: lenient_parsing.get(),
+        repair: repair.get(),
+        limits: limits.get(),
+        track_names: track_names.get(),
+        excluded_tracks: excluded_tracks.get(),
+        track_orders: track_orders.get(),
+        // The files are offered on their own as well as in the archive
+        keep_files: true,
+    };
+    let velocity_mapping = create_memo(move |_| current_options().velocity_mapping);
+
+    // Sets the inputs to the given settings
+    let apply_options = move |options: ProcessOptions| {
+        set_mode(options.mode);
+        set_normalize(options.normalize.is_some());
+        if let Some(normalize) = options.normalize {
+            set_normalize_target(normalize.target);
+            set_normalize_peak(normalize.peak);
+        }
+        match options.velocity_mapping {
+            VelocityMapping::Reduce { amount, threshold } => {
+                set_compress_velocities(false);
+                set_velocity_reduction(amount);
+                set_reduction_threshold(threshold);
+            }
+            VelocityMapping::Compress {
+                target,
+                ratio,
+                makeup,
+            } => {
+                set_compress_velocities(true);
+                set_compress_target(target);
+                set_compress_ratio(ratio);
+                set_compress_makeup(makeup);
+            }
+        }
+        set_velocity_boost(options.velocity_boost);
+        set_dynamics_mode(options.dynamics_mode);
+        set_volume_scale(options.volume_scale);
+        set_scale_aftertouch(options.aftertouch_scale.is_some());
+        if let Some(scale) = options.aftertouch_scale {
+            set_aftertouch_scale(scale);
+        }
+        set_practice_panning(options.practice_panning);
+        set_keep_drums(options.keep_drums);
+        set_background_program(options.background_program);
+        set_humanize(options.humanize.is_some());
+        if let Some(humanize) = options.humanize {
+            set_humanize_range(humanize.range);
+            set_humanize_seed(humanize.seed);
+        }
+        set_tempo_variants_text(
+            options
+                .tempo_variants
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        set_tempo_variants(options.tempo_variants);
+        set_transpose(options.transpose);
+        set_use_bar_range(options.bar_range.is_some());
+        if let Some((first, last)) = options.bar_range {
+            set_first_bar(first);
+            set_last_bar(last);
+        }
+        set_split_hands(options.hand_split.is_some());
+        if let Some(hand_split) = options.hand_split {
+            set_hand_split_track(hand_split.track);
+            set_hand_split_key(hand_split.key);
+        }
+        set_split_voices(options.voice_split.is_some());
+        if let Some(track) = options.voice_split {
+            set_voice_split_track(track);
+        }
+        set_split_channels(options.split_channels);
+        set_stamp_original_velocities(options.stamp_original_velocities);
+        set_add_click_track(options.click_track.is_some());
+        if let Some(click_track) = options.click_track {
+            set_click_downbeat_velocity(click_track.downbeat_velocity);
+            set_click_beat_velocity(click_track.beat_velocity);
+        }
+        set_strip_non_essential_events(options.strip_non_essential_events);
+        set_skip_empty_tracks(options.skip_empty_tracks);
+        set_export_lyrics(options.export_lyrics);
+        set_name_template(options.name_template);
+        set_include_all_tracks(options.all_tracks_suffix.is_some());
+        if let Some(suffix) = options.all_tracks_suffix {
+            set_all_tracks_suffix(suffix);
+        }
+        set_include_original(options.include_original);
+        set_manifest(options.manifest);
+        set_archive_format(options.archive_format);
+        set_export_musicxml(options.export_musicxml);
+        set_render_audio(options.render_audio);
+        set_export_note_csv(options.export_note_csv);
+        set_export_abc(options.export_abc);
+        set_reproducible_archive(options.reproducible_archive);
+        set_single_track_output(options.single_track_output);
+        set_number_files(options.number_files);
+        set_cue_sheet(options.cue_sheet);
+        set_stamp_provenance(options.stamp_provenance);
+        set_lenient_parsing(options.lenient_parsing);
+        set_repair(options.repair);
+        set_limits(options.limits);
+        // The imported values replace whatever invalid input there was
+        set_number_errors(BTreeMap::new());
+    };
+
+    // Settings from a link take precedence over the ones of the last session.
+    // Every change gets saved and put into the URL.
+    let linked = settings::from_url_fragment(&window().location().hash().unwrap_or_default())
+        .unwrap_or_else(|e| {
+            set_error(Some(e.to_string().into()));
+            None
+        });
+    if let Some(options) = linked.or_else(settings::load_saved) {
+        apply_options(options);
+    }
+    let (link_copied, set_link_copied) = create_signal(false);
+    create_effect(move |_| {
+        let options = current_options();
+        settings::save(&options);
+        settings::update_url(&options);
+        set_link_copied(false);
+    });
+
+    let copy_link_action = create_action(move |_: &()| async move {
+        let link = window().location().href().unwrap_or_default();
+        match js::copy_to_clipboard(&link).await {
+            Ok(()) => set_link_copied(true),
+            Err(e) => set_error(Some(
+                (locale.get_untracked().strings().copy_link_failed)(&e).into(),
+            )),
+        }
+    });
+
+    let settings_input_ref: NodeRef<html::Input> = create_node_ref();
+    let import_settings_action = create_action(move |_: &()| async move {
+        let settings_input = settings_input_ref
+            .get_untracked()
+            .expect("<input> not mounted");
+
+        let file = load_file(settings_input).await;
+        match settings::import(&file.data) {
+            Ok(options) => {
+                set_error(None);
+                apply_options(options);
+            }
+            Err(e) => set_error(Some(
+                (locale.get_untracked().strings().load_settings_failed)(&file.name, &e.to_string())
+                    .into(),
+            )),
+        }
+    });
+
+    // Settings are downloaded from a data URL so no object URL has to be kept around
+    let settings_url = move || {
+        let json = settings::export(&current_options()).expect("Failed to serialise settings");
+        format!(
+            "data:application/json,{}",
+            String::from(encode_uri_component(&String::from_utf8_lossy(&json)))
+        )
+    };
+
+    // Generated file being compared with its original
+    let (compared_file, set_compared_file) = create_signal(None::<String>);
+    // Set by the user to process uploads which are bigger than the limits, until other files
+    // are uploaded
+    let ignore_limits = store_value(false);
+    let process_file_action = create_action(move |uploads: &Vec<File>| {
+        let uploads = uploads.clone();
+        let cancel = CancelHandle::default();
+        cancel_handle.set_value(cancel.clone());
+        async move {
+            let mut options = current_options();
+            if ignore_limits.get_value() {
+                options.limits = None;
+            }
+            let process_result = worker::process(
+                uploads.clone(),
+                &options,
+                move |progress| set_progress(Some(progress)),
+                &cancel,
+                locale.get_untracked().strings(),
+            )
+            .await;
+            set_progress(None);
+            let process_result = match process_result {
+                Ok(process_result) => {
+                    set_error(None);
+                    set_history(history::add(&uploads, &options));
+                    process_result
+                }
+                // The results of the previous run are kept as they were
+                Err(SplitError::Cancelled) => return,
+                Err(e) => {
+                    set_error(Some(e.into()));
+                    return;
+                }
+            };
+
+            // The files of the previous run are no longer needed
+            for (_, url, _) in files.get_untracked() {
+                let _ = Url::revoke_object_url(&url);
+            }
+            if let Some(url) = file_url.get_untracked() {
+                let _ = Url::revoke_object_url(&url);
+            }
+
+            let archive_mime_type = options.archive_format.mime_type();
+            let url = create_object_url(&process_result.archive, archive_mime_type);
+            set_archive_size(process_result.archive.len());
+            generated_archive.set_value(Some((
+                File {
+                    name: process_result.archive_name.clone(),
+                    data: process_result.archive,
+                },
+                archive_mime_type,
+            )));
+            set_archive_name(Some(process_result.archive_name));
+            set_save_progress(None);
+            set_files(
+                process_result
+                    .files
+                    .iter()
+                    .map(|file| {
+                        let url = create_object_url(&file.data, mime_type(&file.name));
+                        (file.name.clone(), url, file.data.len())
                     })
-            }}
+                    .collect(),
+            );
+            generated_files.set_value(process_result.files);
+            upload_folders.set_value(process_result.folders);
+            set_skipped(process_result.skipped);
+            set_repairs(process_result.repairs);
+            set_compared_file(None);
+            set_file_url(Some(url));
+            // Screen readers continue at the results rather than the start of the page
+            request_animation_frame(move || {
+                if let Some(download) = download_ref.get_untracked() {
+                    let _ = download.focus();
+                }
+            });
+        }
+    });
 
-            <div class="flex flex-col gap-2">
-                <label class="mb-2 text-sm font-medium" for="vol_input">
-                    Reduce the note velocities by (0-127)
-                </label>
-                <input
-                    class="border-2 rounded p-2 text-slate-900"
-                    id="vol_input"
-                    type="number"
-                    min="0"
-                    max="127"
-                    on:input=move |ev| {
-                        let value = event_target_value(&ev);
-                        match value.parse::<u8>() {
-                            Ok(value) => {
-                                if value > u7::max_value() {
-                                    set_number_error(
-                                        Some(
-                                            "The number entered is too large. Must be between 0 and 127"
-                                                .to_string(),
-                                        ),
-                                    );
-                                    return;
-                                }
-                                set_number_error(None);
-                                set_velocity_reduction(value);
-                            }
-                            Err(_) => {
-                                set_number_error(
-                                    Some(
-                                        "Invalid number entered for note velocity reduction"
-                                            .to_string(),
-                                    ),
-                                )
+    // Uploads are kept in memory so they can be processed again with different settings
+    let process_uploads = move |uploads: Vec<File>| {
+        uploaded_files.set_value(uploads.clone());
+        set_has_uploads(true);
+        ignore_limits.set_value(false);
+        let max_bytes = limits.get_untracked().map(|limits| limits.max_bytes);
+        set_previews(
+            uploads
+                .iter()
+                .filter_map(|upload| {
+                    // Files which cannot be read are reported by the processing, as are files
+                    // too big to read them here without the page stopping to respond
+                    if max_bytes.is_some_and(|max_bytes| upload.data.len() > max_bytes) {
+                        return None;
+                    }
+                    let preview = preview::preview(upload).ok()?;
+                    Some((upload.name.clone(), preview))
+                })
+                .collect(),
+        );
+        process_file_action.dispatch(uploads);
+        if guided.get_untracked() {
+            set_wizard_step(WizardStep::PickParts);
+        }
+    };
+
+    let upload_action = create_action(move |uploads: &Vec<web_sys::File>| {
+        let uploads = uploads.clone();
+        async move {
+            let files = load_files(uploads, |loading| set_loading(Some(loading))).await;
+            set_loading(None);
+            process_uploads(files)
+        }
+    });
+
+    // Repeats a previous run with its files and settings
+    let process_from_history = move |entry: &history::HistoryEntry| {
+        let Some(uploads) = entry.files() else {
+            return;
+        };
+        apply_options(entry.options.clone());
+        set_error(None);
+        process_uploads(uploads);
+    };
+
+    let listen_action = create_action(
+        move |(file, original, mix): &(File, Option<File>, TrackMix)| {
+            let (file, original, mix) = (file.clone(), original.clone(), mix.clone());
+            async move {
+                // Show that the file is being rendered before the work blocks the page
+                js::yield_now().await;
+                let rendered = render_playback(&file, &mix).and_then(|wav| {
+                    let original = original
+                        .as_ref()
+                        .map(|original| render_playback(original, &TrackMix::default()))
+                        .transpose()?;
+                    Ok((wav, original))
+                });
+                match rendered {
+                    Ok((wav, original)) => {
+                        if let Some(playback) = playback.get_untracked() {
+                            let _ = Url::revoke_object_url(&playback.url);
+                            if let Some(url) = playback.original_url {
+                                let _ = Url::revoke_object_url(&url);
                             }
                         }
+                        set_playing_original(false);
+                        set_playback(Some(Playback {
+                            name: file.name,
+                            url: create_object_url(&wav, "audio/wav"),
+                            original_url: original.map(|wav| create_object_url(&wav, "audio/wav")),
+                        }));
                     }
+                    Err(e) => set_error(Some(
+                        (locale.get_untracked().strings().play_failed)(&file.name, &e.to_string())
+                            .into(),
+                    )),
+                }
+            }
+        },
+    );
+    // Listens to the uploaded or generated file with the given name.
+    // Generated files can be compared with the upload they were generated from.
+    let generated_file = move |file_name: &str| {
+        generated_files
+            .with_value(|files| files.iter().find(|file| file.name == file_name).cloned())
+    };
 
-                    prop:value=velocity_reduction
-                />
-            </div>
+    // Upload a generated file was created from, found by the folder of its song
+    let original_upload = move |file_name: &str| {
+        let folder = file_name.rsplit_once('/').map_or("", |(folder, _)| folder);
+        upload_folders
+            .with_value(|folders| {
+                folders
+                    .iter()
+                    .position(|upload_folder| upload_folder == folder)
+            })
+            .and_then(|index| uploaded_files.with_value(|files| files.get(index).cloned()))
+    };
 
-            <div class="w-full flex flex-col">
-                <label class="mb-2 text-sm font-medium" for="file_input">
-                    Upload file
-                </label>
-                <input
-                    class="border-2 rounded p-2 cursor-pointer"
-                    id="file_input"
-                    type="file"
-                    node_ref=file_input_ref
-                    on:change=move |_ev| {
-                        if number_error().is_some() {
-                            set_error(
-                                Some(
-                                    "Cannot process file until a valid number is entered"
-                                        .to_string(),
-                                ),
-                            );
-                            return;
-                        }
-                        set_error(None);
-                        process_file_action.dispatch("");
+    let listen = move |file_name: &str| {
+        let upload = uploaded_files
+            .with_value(|files| files.iter().find(|file| file.name == file_name).cloned());
+        if let Some(upload) = upload {
+            let mix = track_mixes.with_untracked(|mixes| mixes.get(file_name).cloned());
+            listen_action.dispatch((upload, None, mix.unwrap_or_default()));
+            return;
+        }
+        let Some(file) = generated_file(file_name) else {
+            return;
+        };
+        listen_action.dispatch((file, original_upload(file_name), TrackMix::default()));
+    };
+
+    let file_diff = move || {
+        let file_name = compared_file()?;
+        let file = generated_file(&file_name)?;
+        let original = original_upload(&file_name)?;
+        let diffs = diff::diff_files(&original, &file).ok()?;
+        Some((file_name, diffs))
+    };
+
+    // Mutes or solos a track of an upload, continuing at the same position if it is playing
+    let change_mix = move |file_name: &str, change: &dyn Fn(&mut TrackMix)| {
+        set_track_mixes.update(|mixes| change(mixes.entry(file_name.to_string()).or_default()));
+        if playback.with_untracked(|playback| {
+            playback
+                .as_ref()
+                .is_some_and(|playback| playback.name == file_name)
+        }) {
+            if let Some(audio) = audio_ref.get_untracked() {
+                resume_position.set_value(Some((audio.current_time(), !audio.paused())));
+            }
+            listen(file_name);
+        }
+    };
+
+    // Switches between the generated file and its original at the same position
+    let toggle_original = move |_| {
+        if let Some(audio) = audio_ref.get_untracked() {
+            resume_position.set_value(Some((audio.current_time(), !audio.paused())));
+        }
+        set_playing_original.update(|playing_original| *playing_original = !*playing_original);
+    };
+
+    let settings_valid = move || {
+        if !number_errors.get_untracked().is_empty() {
+            set_error(Some(
+                locale
+                    .get_untracked()
+                    .strings()
+                    .number_errors_pending
+                    .to_string()
+                    .into(),
+            ));
+            return false;
+        }
+        set_error(None);
+        true
+    };
+
+    let start_processing = move |uploads: Vec<web_sys::File>| {
+        if uploads.is_empty() {
+            set_error(Some(ErrorMessage {
+                message: locale.get_untracked().strings().only_midi_files.to_string(),
+                problem: Some((None, ReadProblem::InvalidExtension)),
+                error: None,
+            }));
+            return;
+        }
+        if settings_valid() {
+            upload_action.dispatch(uploads);
+        }
+    };
+
+    // The desktop app opens files with the dialog of the system and takes over drops, which
+    // may include folders
+    let desktop_open_action = create_action(move |paths: &Option<JsValue>| {
+        let paths = paths.clone();
+        async move {
+            let dropped = paths.is_some();
+            let result = match paths {
+                Some(paths) => desktop::read_files(paths).await,
+                None => desktop::open_files().await,
+            };
+            match result {
+                // The user closed the dialog
+                Ok(uploads) if uploads.is_empty() && !dropped => {}
+                Ok(uploads) if uploads.is_empty() => set_error(Some(ErrorMessage {
+                    message: locale.get_untracked().strings().only_midi_files.to_string(),
+                    problem: Some((None, ReadProblem::InvalidExtension)),
+                    error: None,
+                })),
+                Ok(uploads) => {
+                    if settings_valid() {
+                        process_uploads(uploads);
                     }
-                />
+                }
+                Err(e) => set_error(Some(e.into())),
+            }
+        }
+    });
+    if desktop::is_desktop() {
+        spawn_local(async move {
+            let listening =
+                desktop::on_drop(move |paths| desktop_open_action.dispatch(Some(paths))).await;
+            if let Err(e) = listening {
+                set_error(Some(e.into()));
+            }
+        });
+    }
 
-            </div>
+    let reprocess = move || {
+        if has_uploads.get_untracked() && settings_valid() {
+            process_file_action.dispatch(uploaded_files.get_value());
+        }
+    };
 
+    let save_action = create_action(move |_: &()| async move {
+        let files = generated_files.get_value();
+        let total = files.len();
+        let result = if desktop::is_desktop() {
+            desktop::save_to_picked_folder(&files).await.map(|saved| {
+                if saved {
+                    set_save_progress(Some((total, total)));
+                }
+            })
+        } else {
+            directory::save_to_picked_directory(
+                &files,
+                |saved| set_save_progress(Some((saved, total))),
+                locale.get_untracked().strings(),
+            )
+            .await
+            .map(|_| ())
+        };
+        if let Err(e) = result {
+            set_error(Some(e.into()));
+        }
+    });
+
+    // Generated file with the given name or the archive if there is none
+    let generated_file = move |file_name: &Option<String>| match file_name {
+        Some(file_name) => generated_files.with_value(|files| {
+            files
+                .iter()
+                .find(|file| &file.name == file_name)
+                .map(|file| (file.clone(), mime_type(&file.name)))
+        }),
+        None => generated_archive.get_value(),
+    };
+
+    // The desktop app saves downloads with the save dialog of the system
+    let download_action = create_action(move |file_name: &Option<String>| {
+        let file = generated_file(file_name);
+        async move {
+            let Some((mut file, _)) = file else {
+                return;
+            };
+            // The files of songs are offered without their folder, as browsers do
+            if let Some((_, name)) = file.name.rsplit_once('/') {
+                file.name = name.to_string();
+            }
+            if let Err(e) = desktop::save_file(&file).await {
+                set_error(Some(e.into()));
+            }
+        }
+    });
+
+    // Shares the file with the given name or the archive if there is none
+    let share_action = create_action(move |file_name: &Option<String>| {
+        let file = generated_file(file_name);
+        async move {
+            let Some((file, mime_type)) = file else {
+                return;
+            };
+            if let Err(e) = share::share(&file, mime_type, locale.get_untracked().strings()).await {
+                set_error(Some(e.into()));
+            }
+        }
+    });
+
+    let (shortcut_help, set_shortcut_help) = create_signal(false);
+    let (hints_dismissed, set_hints_dismissed) = create_signal(help::hints_dismissed());
+    // The new version is only used after a reload, which the user may not want in the middle
+    // of a rehearsal
+    let (update_available, set_update_available) = create_signal(false);
+    offline::register(move || set_update_available(true));
+    // The advanced options start out open if any of them is in use
+    let uses_advanced_options = create_memo(move |_| current_options().uses_advanced_options());
+    let close_help_ref: NodeRef<html::Button> = create_node_ref();
+    // The help takes the focus so it can be closed straight away
+    create_effect(move |_| {
+        if shortcut_help() {
+            request_animation_frame(move || {
+                if let Some(close) = close_help_ref.get_untracked() {
+                    let _ = close.focus();
+                }
+            });
+        }
+    });
+    let _ = window_event_listener(ev::keydown, move |ev| {
+        if ev.key() == "Escape" {
+            set_shortcut_help(false);
+            return;
+        }
+        let Some(shortcut) = Shortcut::from_event(&ev) else {
+            return;
+        };
+        ev.prevent_default();
+        match shortcut {
+            Shortcut::OpenFiles => {
+                if let Some(file_input) = file_input_ref.get_untracked() {
+                    file_input.click();
+                }
+            }
+            Shortcut::Reprocess => reprocess(),
+            Shortcut::Download => {
+                if let Some(download) = download_ref.get_untracked() {
+                    download.click();
+                }
+            }
+            Shortcut::PlayPause => {
+                if let Some(audio) = audio_ref.get_untracked() {
+                    if audio.paused() {
+                        let _ = audio.play();
+                    } else {
+                        let _ = audio.pause();
+                    }
+                }
+            }
+            Shortcut::Help => set_shortcut_help.update(|shown| *shown = !*shown),
+        }
+    });
+
+    // Phones start with the settings collapsed so the upload is visible straight away
+    let wide_screen = leptos::window()
+        .inner_width()
+        .ok()
+        .and_then(|width| width.as_f64())
+        .is_none_or(|width| width >= 640.0);
+
+    view! {
+        <div class="min-h-screen p-4 sm:p-10 flex flex-col items-center gap-4 bg-page text-content">
             {move || {
-                if file_names().len() > 0 {
-                    Some(
-                        view! {
-                            <div
-                                class="flex flex-col gap-2 p-4 border-2"
-                                hidden=move || file_names().len() == 0
+                let text = locale().strings();
+                view! {
+                    <div class="self-end flex flex-wrap justify-end gap-2 items-center">
+                        <button
+                            class="text-sm underline hover:text-link-hover"
+                            on:click=move |_| set_guided.update(|guided| *guided = !*guided)
+                        >
+                            {move || if guided() { text.single_page_mode } else { text.guided_mode }}
+                        </button>
+                        <button
+                            class="text-sm underline hover:text-link-hover"
+                            on:click=move |_| set_shortcut_help(true)
+                        >
+                            {text.shortcuts}
+                        </button>
+                        <label class="text-sm font-medium" for="language_input">
+                            {text.language}
+                        </label>
+                        <select
+                            class="border-2 rounded p-2 text-slate-900"
+                            id="language_input"
+                            on:change=move |ev| {
+                                if let Some(locale) = Locale::from_code(&event_target_value(&ev)) {
+                                    set_locale(locale);
+                                }
+                            }
+                        >
+                            {Locale::ALL
+                                .into_iter()
+                                .map(|option| {
+                                    view! {
+                                        <option
+                                            value=option.code()
+                                            selected=move || locale() == option
+                                        >
+                                            {option.name()}
+                                        </option>
+                                    }
+                                })
+                                .collect_view()}
+                        </select>
+                        <label class="text-sm font-medium" for="theme_input">
+                            {text.theme}
+                        </label>
+                        <select
+                            class="border-2 rounded p-2 text-slate-900"
+                            id="theme_input"
+                            on:change=move |ev| set_theme(Theme::from_name(&event_target_value(&ev)))
+                        >
+                            {[
+                                (Theme::System, text.theme_system),
+                                (Theme::Light, text.theme_light),
+                                (Theme::Dark, text.theme_dark),
+                            ]
+                                .into_iter()
+                                .map(|(option, label)| {
+                                    view! {
+                                        <option value=option.name() selected=move || theme() == option>
+                                            {label}
+                                        </option>
+                                    }
+                                })
+                                .collect_view()}
+                        </select>
+                    </div>
+                    <p class="text-lg mb-4">
+                        {text.intro}
+                    </p>
+                    <Show when=guided>
+                        <nav class="w-full" aria-label=text.steps>
+                            <ol class="flex flex-wrap gap-2">
+                                {WizardStep::ALL
+                                    .into_iter()
+                                    .map(|step| {
+                                        let current = move || wizard_step() == step;
+                                        view! {
+                                            <li>
+                                                <button
+                                                    class="px-3 py-1 rounded border-2 text-sm"
+                                                    class=("bg-accent", current)
+                                                    class=("text-on-accent", current)
+                                                    aria-current=move || current().then_some("step")
+                                                    disabled=move || !step_reachable(step)
+                                                    on:click=move |_| set_wizard_step(step)
+                                                >
+                                                    {step.number()}
+                                                    ". "
+                                                    {step.label(text)}
+                                                </button>
+                                            </li>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </ol>
+                        </nav>
+                    </Show>
+                    <Show when=update_available>
+                        <div class="w-full flex flex-wrap gap-4 items-center p-4 rounded bg-sunken" role="status">
+                            <p class="text-sm grow">{text.update_available}</p>
+                            <button
+                                class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-2 rounded"
+                                on:click=move |_| offline::reload()
+                            >
+                                {text.reload}
+                            </button>
+                            <button
+                                class="text-sm underline hover:text-link-hover"
+                                on:click=move |_| set_update_available(false)
                             >
-                                <p class="text-lg mb-2">The following files have been created:</p>
-                                <For
-                                    each=file_names
-                                    key=|file_name| file_name.clone()
-                                    children=|file_name| {
-                                        view! { <p class="text-m">{file_name}</p> }
+                                {text.later}
+                            </button>
+                        </div>
+                    </Show>
+                    // Errors are announced as soon as they appear
+                    <div class="w-full flex flex-col gap-4" role="alert">
+                        {move || {
+                            error()
+                                .map(|error| {
+                                    view! {
+                                        <div id="error" class="w-full bg-danger text-on-accent p-4 rounded">
+                                            <p class="text-lg">{text.error}</p>
+                                            {match error.problem {
+                                                Some((file_name, problem)) => {
+                                                    view! {
+                                                        <p class="text-sm font-bold">
+                                                            {file_name
+                                                                .map_or(
+                                                                    text.unreadable_upload.to_string(),
+                                                                    |file_name| (text.unreadable_file)(&file_name),
+                                                                )}
+                                                        </p>
+                                                        <p class="text-sm">{text.problem_description(problem)}</p>
+                                                        {match error.error {
+                                                            Some(SplitError::ParseError { offset: Some(offset), .. }) => {
+                                                                Some(view! { <p class="text-sm">{(text.corrupted_at)(offset)}</p> })
+                                                            }
+                                                            _ => None,
+                                                        }}
+                                                        <p class="text-sm">{text.problem_suggestion(problem)}</p>
+                                                        <button
+                                                            class="mt-2 text-sm font-bold underline"
+                                                            on:click=move |_| {
+                                                                if let Some(file_input) = file_input_ref.get_untracked() {
+                                                                    file_input.click();
+                                                                }
+                                                            }
+                                                        >
+                                                            {text.choose_other_files}
+                                                        </button>
+                                                    }
+                                                        .into_view()
+                                                }
+                                                None => {
+                                                    match error.error {
+                                                        Some(SplitError::NonUtf8TrackName { file_name, track }) => {
+                                                            view! {
+                                                                <p class="text-sm font-bold">
+                                                                    {(text.non_utf8_track_name)(&file_name, track + 1)}
+                                                                </p>
+                                                                <p class="text-sm">{text.suggestion_non_utf8_track_name}</p>
+                                                            }
+                                                                .into_view()
+                                                        }
+                                                        Some(SplitError::TooLarge { file_name, limit, size, maximum }) => {
+                                                            view! {
+                                                                <p class="text-sm font-bold">
+                                                                    {match limit {
+                                                                        Limit::Bytes => {
+                                                                            (text.too_large_bytes)(
+                                                                                &file_name,
+                                                                                &format_size(size),
+                                                                                &format_size(maximum),
+                                                                            )
+                                                                        }
+                                                                        Limit::Tracks => (text.too_large_tracks)(&file_name, size, maximum),
+                                                                        Limit::Events => (text.too_large_events)(&file_name, size, maximum),
+                                                                        Limit::Duration => {
+                                                                            (text.too_large_duration)(
+                                                                                &file_name,
+                                                                                size.div_ceil(60),
+                                                                                maximum / 60,
+                                                                            )
+                                                                        }
+                                                                        Limit::Beats => (text.too_large_beats)(&file_name, size, maximum),
+                                                                    }}
+                                                                </p>
+                                                                <p class="text-sm">{text.suggestion_too_large}</p>
+                                                                <button
+                                                                    class="mt-2 text-sm font-bold underline"
+                                                                    on:click=move |_| {
+                                                                        ignore_limits.set_value(true);
+                                                                        reprocess();
+                                                                    }
+                                                                >
+                                                                    {text.process_anyway}
+                                                                </button>
+                                                            }
+                                                                .into_view()
+                                                        }
+                                                        Some(SplitError::InvalidTempoVariant { percent }) => {
+                                                            view! {
+                                                                <p class="text-sm">
+                                                                    {(text.invalid_tempo_variant)(percent, MAX_TEMPO_PERCENT)}
+                                                                </p>
+                                                            }
+                                                                .into_view()
+                                                        }
+                                                        Some(SplitError::InvalidMidiValue { setting, value }) => {
+                                                            view! {
+                                                                <p class="text-sm">{(text.invalid_midi_value)(&setting, value)}</p>
+                                                            }
+                                                                .into_view()
+                                                        }
+                                                        _ => view! { <p class="text-sm">{error.message}</p> }.into_view(),
+                                                    }
+                                                }
+                                            }}
+                                        </div>
+                                    }
+                                })
+                        }}
+
+                        {move || {
+                            let errors = number_errors();
+                            (!errors.is_empty())
+                                .then(|| {
+                                    view! {
+                                        <div id="error" class="w-full bg-danger text-on-accent p-4 rounded">
+                                            <p class="text-lg">{text.number_error}</p>
+                                            {errors
+                                                .into_values()
+                                                .map(|error| view! { <p class="text-sm">{error}</p> })
+                                                .collect_view()}
+                                        </div>
                                     }
+                                })
+                        }}
+                    </div>
+
+                    <details
+                        class="w-full"
+                        class=("hidden", move || !shown_in(WizardStep::Settings))
+                        open=wide_screen || guided.get_untracked()
+                    >
+                        <summary class="py-3 text-lg font-medium cursor-pointer">
+                            {text.processing_settings}
+                        </summary>
+                        <div class="flex flex-col items-center gap-4">
+                            <Show when=move || !hints_dismissed()>
+                                <div class="flex gap-4 items-center p-4 rounded bg-sunken" role="note">
+                                    <p class="text-sm">{text.first_run_hint}</p>
+                                    <button
+                                        class="text-sm underline hover:text-link-hover"
+                                        on:click=move |_| {
+                                            help::dismiss_hints();
+                                            set_hints_dismissed(true);
+                                        }
+                                    >
+                                        {text.dismiss_hint}
+                                    </button>
+                                </div>
+                            </Show>
+                            <div class="flex flex-col gap-2">
+                                <OptionLabel
+                                    text=text
+                                    id="ensemble_preset_input"
+                                    label=text.ensemble_preset
                                 />
+                                <select
+                                    class="border-2 rounded p-2 text-slate-900"
+                                    id="ensemble_preset_input"
+                                    on:change=move |ev| {
+                                        if let Some(preset) = BuiltinPreset::from_id(&event_target_value(&ev)) {
+                                            apply_options(preset.options());
+                                        }
+                                        // The settings can be changed afterwards, so the picker does not
+                                        // keep showing the preset
+                                        event_target::<web_sys::HtmlSelectElement>(&ev).set_value("");
+                                    }
+                                >
+                                    <option value="" selected>
+                                        {text.choose_preset}
+                                    </option>
+                                    {BuiltinPreset::ALL
+                                        .into_iter()
+                                        .map(|preset| {
+                                            view! { <option value=preset.id()>{preset.name(text)}</option> }
+                                        })
+                                        .collect_view()}
+                                </select>
+                            </div>
+
+                            <div class="flex flex-col gap-2">
+                                <OptionLabel text=text id="mode_input" label=text.mode/>
+                                <select
+                                    class="border-2 rounded p-2 text-slate-900"
+                                    id="mode_input"
+                                    on:change=move |ev| {
+                                        set_mode(
+                                            match event_target_value(&ev).as_str() {
+                                                "solo" => Mode::Solo,
+                                                "minus_one" => Mode::MinusOne,
+                                                _ => Mode::Emphasize,
+                                            },
+                                        )
+                                    }
+                                >
 
+                                    <option value="emphasize" selected=move || mode() == Mode::Emphasize>
+                                        {text.mode_emphasize}
+                                    </option>
+                                    <option value="solo" selected=move || mode() == Mode::Solo>
+                                        {text.mode_solo}
+                                    </option>
+                                    <option value="minus_one" selected=move || mode() == Mode::MinusOne>
+                                        {text.mode_minus_one}
+                                    </option>
+                                </select>
                             </div>
-                        },
-                    )
-                } else {
-                    None
-                }
-            }}
 
-            {move || {
-                file_url()
-                    .map(|url| {
-                        Some(
-                            view! {
+                            // Casual users only pick how much quieter the other tracks get
+                            {move || {
+                                (mode() == Mode::Emphasize && !compress_velocities())
+                                    .then(|| {
+                                        view! {
+                                            <VelocityInput
+                                                text=text
+                                                id="vol_input"
+                                                label=text.velocity_reduction
+                                                min=0
+                                                max=127
+                                                value=velocity_reduction
+                                                set_value=set_velocity_reduction
+                                                set_number_errors=set_number_errors
+                                            />
+                                        }
+                                    })
+                            }}
+
+                            {move || {
+                                let velocities: Vec<u8> = previews()
+                                    .into_iter()
+                                    .flat_map(|(_, preview)| preview.velocities)
+                                    .collect();
+                                (mode() == Mode::Emphasize && !velocities.is_empty())
+                                    .then(|| {
+                                        view! {
+                                            <VelocityHistogram
+                                                text=text
+                                                velocities=velocities
+                                                mapping=velocity_mapping
+                                            />
+                                        }
+                                    })
+                            }}
+
+                            <details class="w-full" open=uses_advanced_options.get_untracked()>
+                                <summary class="py-3 text-lg font-medium cursor-pointer">
+                                    {text.advanced_options}
+                                    {move || {
+                                        uses_advanced_options()
+                                            .then(|| {
+                                                view! {
+                                                    <span class="ml-2 text-sm font-normal">
+                                                        {text.advanced_options_changed}
+                                                    </span>
+                                                }
+                                            })
+                                    }}
+                                </summary>
+                                <div class="flex flex-col items-center gap-4">
+                                    {move || {
+                                        (mode() == Mode::Emphasize)
+                                            .then(|| {
+                                                view! {
+                                                    <div class="flex flex-col items-center gap-4">
+                                                        <Checkbox
+                                                            text=text
+                                                            id="normalize_input"
+                                                            label=text.normalize
+                                                            checked=normalize
+                                                            set_checked=set_normalize
+                                                        />
+
+                                                        {move || {
+                                                            normalize()
+                                                                .then(|| {
+                                                                    view! {
+                                                                        <div class="flex gap-4 items-end">
+                                                                            <NumberInput
+                                                                                text=text
+                                                                                id="normalize_target_input"
+                                                                                label=text.target_velocity
+                                                                                min=1
+                                                                                max=127
+                                                                                value=normalize_target
+                                                                                set_value=set_normalize_target
+                                                                                set_number_errors=set_number_errors
+                                                                            />
+                                                                            <select
+                                                                                class="border-2 rounded p-2 text-slate-900"
+                                                                                id="normalize_level_input"
+                                                                                on:change=move |ev| {
+                                                                                    set_normalize_peak(event_target_value(&ev) == "peak")
+                                                                                }
+                                                                            >
+
+                                                                                <option value="average" selected=move || !normalize_peak()>
+                                                                                    {text.normalize_average}
+                                                                                </option>
+                                                                                <option value="peak" selected=normalize_peak>
+                                                                                    {text.normalize_peak}
+                                                                                </option>
+                                                                            </select>
+                                                                        </div>
+                                                                    }
+                                                                })
+                                                        }}
+
+                                                        <div class="flex flex-col gap-2">
+                                                            <OptionLabel
+                                                                text=text
+                                                                id="velocity_mapping_input"
+                                                                label=text.velocity_mapping
+                                                            />
+                                                            <select
+                                                                class="border-2 rounded p-2 text-slate-900"
+                                                                id="velocity_mapping_input"
+                                                                on:change=move |ev| {
+                                                                    set_compress_velocities(event_target_value(&ev) == "compress")
+                                                                }
+                                                            >
+
+                                                                <option value="reduce" selected=move || !compress_velocities()>
+                                                                    {text.mapping_reduce}
+                                                                </option>
+                                                                <option value="compress" selected=compress_velocities>
+                                                                    {text.mapping_compress}
+                                                                </option>
+                                                            </select>
+                                                        </div>
+
+                                                        {move || {
+                                                            if compress_velocities() {
+                                                                view! {
+                                                                    <div class="flex gap-4">
+                                                                        <NumberInput
+                                                                            text=text
+                                                                            id="compress_target_input"
+                                                                            label=text.target_velocity
+                                                                            min=1
+                                                                            max=127
+                                                                            value=compress_target
+                                                                            set_value=set_compress_target
+                                                                            set_number_errors=set_number_errors
+                                                                        />
+                                                                        <NumberInput
+                                                                            text=text
+                                                                            id="compress_ratio_input"
+                                                                            label=text.compress_ratio
+                                                                            min=1
+                                                                            max=20
+                                                                            value=compress_ratio
+                                                                            set_value=set_compress_ratio
+                                                                            set_number_errors=set_number_errors
+                                                                        />
+                                                                        <NumberInput
+                                                                            text=text
+                                                                            id="compress_makeup_input"
+                                                                            label=text.compress_makeup
+                                                                            min=-64
+                                                                            max=64
+                                                                            value=compress_makeup
+                                                                            set_value=set_compress_makeup
+                                                                            set_number_errors=set_number_errors
+                                                                        />
+                                                                    </div>
+                                                                }
+                                                                    .into_view()
+                                                            } else {
+                                                                view! {
+                                                                    <VelocityInput
+                                                                        text=text
+                                                                        id="reduction_threshold_input"
+                                                                        label=text.reduction_threshold
+                                                                        min=0
+                                                                        max=127
+                                                                        value=reduction_threshold
+                                                                        set_value=set_reduction_threshold
+                                                                        set_number_errors=set_number_errors
+                                                                    />
+                                                                }
+                                                                    .into_view()
+                                                            }
+                                                        }}
+
+                                                        <VelocityInput
+                                                            text=text
+                                                            id="boost_input"
+                                                            label=text.velocity_boost
+                                                            min=0
+                                                            max=127
+                                                            value=velocity_boost
+                                                            set_value=set_velocity_boost
+                                                            set_number_errors=set_number_errors
+                                                        />
+
+                                                        <div class="flex flex-col gap-2">
+                                                            <OptionLabel
+                                                                text=text
+                                                                id="dynamics_mode_input"
+                                                                label=text.dynamics_mode
+                                                            />
+                                                            <select
+                                                                class="border-2 rounded p-2 text-slate-900"
+                                                                id="dynamics_mode_input"
+                                                                on:change=move |ev| {
+                                                                    set_dynamics_mode(
+                                                                        match event_target_value(&ev).as_str() {
+                                                                            "controllers" => DynamicsMode::Controllers,
+                                                                            "both" => DynamicsMode::Both,
+                                                                            _ => DynamicsMode::Velocity,
+                                                                        },
+                                                                    )
+                                                                }
+                                                            >
+
+                                                                <option
+                                                                    value="velocity"
+                                                                    selected=move || dynamics_mode() == DynamicsMode::Velocity
+                                                                >
+                                                                    {text.dynamics_velocity}
+                                                                </option>
+                                                                <option
+                                                                    value="controllers"
+                                                                    selected=move || dynamics_mode() == DynamicsMode::Controllers
+                                                                >
+                                                                    {text.dynamics_controllers}
+                                                                </option>
+                                                                <option value="both" selected=move || dynamics_mode() == DynamicsMode::Both>
+                                                                    {text.dynamics_both}
+                                                                </option>
+                                                            </select>
+                                                        </div>
+
+                                                        {move || {
+                                                            (dynamics_mode() != DynamicsMode::Velocity)
+                                                                .then(|| {
+                                                                    view! {
+                                                                        <NumberInput
+                                                                            text=text
+                                                                            id="volume_scale_input"
+                                                                            label=text.volume_scale
+                                                                            min=0
+                                                                            max=100
+                                                                            value=volume_scale
+                                                                            set_value=set_volume_scale
+                                                                            set_number_errors=set_number_errors
+                                                                        />
+                                                                    }
+                                                                })
+                                                        }}
+
+                                                        <Checkbox
+                                                            text=text
+                                                            id="scale_aftertouch_input"
+                                                            label=text.scale_aftertouch
+                                                            checked=scale_aftertouch
+                                                            set_checked=set_scale_aftertouch
+                                                        />
+
+                                                        {move || {
+                                                            scale_aftertouch()
+                                                                .then(|| {
+                                                                    view! {
+                                                                        <NumberInput
+                                                                            text=text
+                                                                            id="aftertouch_scale_input"
+                                                                            label=text.aftertouch_scale
+                                                                            min=0
+                                                                            max=100
+                                                                            value=aftertouch_scale
+                                                                            set_value=set_aftertouch_scale
+                                                                            set_number_errors=set_number_errors
+                                                                        />
+                                                                    }
+                                                                })
+                                                        }}
+
+                                                        <Checkbox
+                                                            text=text
+                                                            id="practice_panning_input"
+                                                            label=text.practice_panning
+                                                            checked=practice_panning
+                                                            set_checked=set_practice_panning
+                                                        />
+
+                                                        <Checkbox
+                                                            text=text
+                                                            id="keep_drums_input"
+                                                            label=text.keep_drums
+                                                            checked=keep_drums
+                                                            set_checked=set_keep_drums
+                                                        />
+
+                                                        <div class="flex flex-col gap-2">
+                                                            <OptionLabel
+                                                                text=text
+                                                                id="background_program_input"
+                                                                label=text.background_program
+                                                            />
+                                                            <select
+                                                                class="border-2 rounded p-2 text-slate-900"
+                                                                id="background_program_input"
+                                                                on:change=move |ev| {
+                                                                    set_background_program(event_target_value(&ev).parse::<u8>().ok())
+                                                                }
+                                                            >
+
+                                                                <option value="">{text.keep_instruments}</option>
+                                                                {gm::INSTRUMENT_NAMES
+                                                                    .iter()
+                                                                    .enumerate()
+                                                                    .map(|(program, name)| {
+                                                                        view! {
+                                                                            <option
+                                                                                value=program
+                                                                                selected=move || background_program() == Some(program as u8)
+                                                                            >
+                                                                                {*name}
+                                                                            </option>
+                                                                        }
+                                                                    })
+                                                                    .collect_view()}
+                                                            </select>
+                                                        </div>
+
+                                                        <Checkbox
+                                                            text=text
+                                                            id="humanize_input"
+                                                            label=text.humanize
+                                                            checked=humanize
+                                                            set_checked=set_humanize
+                                                        />
+
+                                                        <Checkbox
+                                                            text=text
+                                                            id="stamp_velocities_input"
+                                                            label=text.stamp_velocities
+                                                            checked=stamp_original_velocities
+                                                            set_checked=set_stamp_original_velocities
+                                                        />
+
+                                                        {move || {
+                                                            humanize()
+                                                                .then(|| {
+                                                                    view! {
+                                                                        <div class="flex gap-4">
+                                                                            <NumberInput
+                                                                                text=text
+                                                                                id="humanize_range_input"
+                                                                                label=text.humanize_range
+                                                                                min=1
+                                                                                max=30
+                                                                                value=humanize_range
+                                                                                set_value=set_humanize_range
+                                                                                set_number_errors=set_number_errors
+                                                                            />
+                                                                            <NumberInput
+                                                                                text=text
+                                                                                id="humanize_seed_input"
+                                                                                label=text.humanize_seed
+                                                                                min=0
+                                                                                max=u64::MAX
+                                                                                value=humanize_seed
+                                                                                set_value=set_humanize_seed
+                                                                                set_number_errors=set_number_errors
+                                                                            />
+                                                                        </div>
+                                                                    }
+                                                                })
+                                                        }}
+                                                    </div>
+                                                }
+                                            })
+                                    }}
+
+                                    <NumberInput
+                                        text=text
+                                        id="transpose_input"
+                                        label=text.transpose
+                                        min=-12
+                                        max=12
+                                        value=transpose
+                                        set_value=set_transpose
+                                        set_number_errors=set_number_errors
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="skip_empty_tracks_input"
+                                        label=text.skip_empty_tracks
+                                        checked=skip_empty_tracks
+                                        set_checked=set_skip_empty_tracks
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="split_hands_input"
+                                        label=text.split_hands
+                                        checked=split_hands
+                                        set_checked=set_split_hands
+                                    />
+
+                                    {move || {
+                                        split_hands()
+                                            .then(|| {
+                                                view! {
+                                                    <div class="flex gap-4">
+                                                        <NumberInput
+                                                            text=text
+                                                            id="hand_split_track_input"
+                                                            label=text.split_track
+                                                            min=0
+                                                            max=999
+                                                            value=hand_split_track
+                                                            set_value=set_hand_split_track
+                                                            set_number_errors=set_number_errors
+                                                        />
+                                                        <NumberInput
+                                                            text=text
+                                                            id="hand_split_key_input"
+                                                            label=text.hand_split_key
+                                                            min=0
+                                                            max=127
+                                                            value=hand_split_key
+                                                            set_value=set_hand_split_key
+                                                            set_number_errors=set_number_errors
+                                                        />
+                                                    </div>
+                                                }
+                                            })
+                                    }}
+
+                                    <Checkbox
+                                        text=text
+                                        id="split_voices_input"
+                                        label=text.split_voices
+                                        checked=split_voices
+                                        set_checked=set_split_voices
+                                    />
+
+                                    {move || {
+                                        split_voices()
+                                            .then(|| {
+                                                view! {
+                                                    <NumberInput
+                                                        text=text
+                                                        id="voice_split_track_input"
+                                                        label=text.split_track
+                                                        min=0
+                                                        max=999
+                                                        value=voice_split_track
+                                                        set_value=set_voice_split_track
+                                                        set_number_errors=set_number_errors
+                                                    />
+                                                }
+                                            })
+                                    }}
+
+                                    <Checkbox
+                                        text=text
+                                        id="split_channels_input"
+                                        label=text.split_channels
+                                        checked=split_channels
+                                        set_checked=set_split_channels
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="click_track_input"
+                                        label=text.click_track
+                                        checked=add_click_track
+                                        set_checked=set_add_click_track
+                                    />
+
+                                    {move || {
+                                        add_click_track()
+                                            .then(|| {
+                                                view! {
+                                                    <div class="flex gap-4">
+                                                        <NumberInput
+                                                            text=text
+                                                            id="click_downbeat_velocity_input"
+                                                            label=text.click_downbeat_velocity
+                                                            min=1
+                                                            max=127
+                                                            value=click_downbeat_velocity
+                                                            set_value=set_click_downbeat_velocity
+                                                            set_number_errors=set_number_errors
+                                                        />
+                                                        <NumberInput
+                                                            text=text
+                                                            id="click_beat_velocity_input"
+                                                            label=text.click_beat_velocity
+                                                            min=1
+                                                            max=127
+                                                            value=click_beat_velocity
+                                                            set_value=set_click_beat_velocity
+                                                            set_number_errors=set_number_errors
+                                                        />
+                                                    </div>
+                                                }
+                                            })
+                                    }}
+
+                                    <Checkbox
+                                        text=text
+                                        id="strip_events_input"
+                                        label=text.strip_events
+                                        checked=strip_non_essential_events
+                                        set_checked=set_strip_non_essential_events
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="export_lyrics_input"
+                                        label=text.export_lyrics
+                                        checked=export_lyrics
+                                        set_checked=set_export_lyrics
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="export_musicxml_input"
+                                        label=text.export_musicxml
+                                        checked=export_musicxml
+                                        set_checked=set_export_musicxml
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="export_abc_input"
+                                        label=text.export_abc
+                                        checked=export_abc
+                                        set_checked=set_export_abc
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="export_note_csv_input"
+                                        label=text.export_note_csv
+                                        checked=export_note_csv
+                                        set_checked=set_export_note_csv
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="render_audio_input"
+                                        label=text.render_audio
+                                        checked=render_audio
+                                        set_checked=set_render_audio
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="single_track_output_input"
+                                        label=text.single_track_output
+                                        checked=single_track_output
+                                        set_checked=set_single_track_output
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="include_all_tracks_input"
+                                        label=text.include_all_tracks
+                                        checked=include_all_tracks
+                                        set_checked=set_include_all_tracks
+                                    />
+
+                                    {move || {
+                                        include_all_tracks()
+                                            .then(|| {
+                                                // An invalid suffix does not matter once the file is left out
+                                                on_cleanup(move || {
+                                                    set_number_errors
+                                                        .update(|errors| {
+                                                            errors.remove("all_tracks_suffix_input");
+                                                        });
+                                                });
+                                                view! {
+                                                    <div class="flex flex-col gap-2">
+                                                        <OptionLabel
+                                                            text=text
+                                                            id="all_tracks_suffix_input"
+                                                            label=text.all_tracks_suffix
+                                                        />
+                                                        <input
+                                                            class="border-2 rounded p-2 text-slate-900"
+                                                            id="all_tracks_suffix_input"
+                                                            type="text"
+                                                            prop:value=all_tracks_suffix
+                                                            on:input=move |ev| {
+                                                                let suffix = event_target_value(&ev);
+                                                                set_number_errors
+                                                                    .update(|errors| {
+                                                                        if sanitize_file_name(&suffix).is_empty() {
+                                                                            errors
+                                                                                .insert(
+                                                                                    "all_tracks_suffix_input",
+                                                                                    text.empty_all_tracks_suffix.to_string(),
+                                                                                );
+                                                                        } else {
+                                                                            errors.remove("all_tracks_suffix_input");
+                                                                            set_all_tracks_suffix(suffix);
+                                                                        }
+                                                                    });
+                                                            }
+
+                                                        />
+
+                                                    </div>
+                                                }
+                                            })
+                                    }}
+
+                                    <Checkbox
+                                        text=text
+                                        id="include_original_input"
+                                        label=text.include_original
+                                        checked=include_original
+                                        set_checked=set_include_original
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="cue_sheet_input"
+                                        label=text.cue_sheet
+                                        checked=cue_sheet
+                                        set_checked=set_cue_sheet
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="stamp_provenance_input"
+                                        label=text.stamp_provenance
+                                        checked=stamp_provenance
+                                        set_checked=set_stamp_provenance
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="lenient_parsing_input"
+                                        label=text.lenient_parsing
+                                        checked=lenient_parsing
+                                        set_checked=set_lenient_parsing
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="repair_input"
+                                        label=text.repair
+                                        checked=repair
+                                        set_checked=set_repair
+                                    />
+
+                                    <div class="flex flex-col gap-2">
+                                        <OptionLabel text=text id="manifest_input" label=text.manifest/>
+                                        <select
+                                            class="border-2 rounded p-2 text-slate-900"
+                                            id="manifest_input"
+                                            on:change=move |ev| {
+                                                set_manifest(
+                                                    match event_target_value(&ev).as_str() {
+                                                        "json" => Some(ManifestFormat::Json),
+                                                        "csv" => Some(ManifestFormat::Csv),
+                                                        _ => None,
+                                                    },
+                                                )
+                                            }
+                                        >
+
+                                            <option value="none" selected=move || manifest().is_none()>
+                                                {text.manifest_none}
+                                            </option>
+                                            <option value="json" selected=move || manifest() == Some(ManifestFormat::Json)>
+                                                JSON
+                                            </option>
+                                            <option value="csv" selected=move || manifest() == Some(ManifestFormat::Csv)>
+                                                CSV
+                                            </option>
+                                        </select>
+                                    </div>
+
+                                    <div class="flex flex-col gap-2">
+                                        <OptionLabel
+                                            text=text
+                                            id="archive_format_input"
+                                            label=text.archive_format
+                                        />
+                                        <select
+                                            class="border-2 rounded p-2 text-slate-900"
+                                            id="archive_format_input"
+                                            on:change=move |ev| {
+                                                set_archive_format(
+                                                    match event_target_value(&ev).as_str() {
+                                                        "tar_gz" => ArchiveFormat::TarGz,
+                                                        _ => ArchiveFormat::Zip,
+                                                    },
+                                                )
+                                            }
+                                        >
+
+                                            <option value="zip" selected=move || archive_format() == ArchiveFormat::Zip>
+                                                {text.archive_zip}
+                                            </option>
+                                            <option
+                                                value="tar_gz"
+                                                selected=move || archive_format() == ArchiveFormat::TarGz
+                                            >
+                                                {text.archive_tar_gz}
+                                            </option>
+                                        </select>
+                                    </div>
+
+                                    <Checkbox
+                                        text=text
+                                        id="reproducible_archive_input"
+                                        label=text.reproducible_archive
+                                        checked=reproducible_archive
+                                        set_checked=set_reproducible_archive
+                                    />
+
+                                    <Checkbox
+                                        text=text
+                                        id="bar_range_input"
+                                        label=text.bar_range
+                                        checked=use_bar_range
+                                        set_checked=set_use_bar_range
+                                    />
+
+                                    {move || {
+                                        use_bar_range()
+                                            .then(|| {
+                                                view! {
+                                                    <div class="flex gap-4">
+                                                        <NumberInput
+                                                            text=text
+                                                            id="first_bar_input"
+                                                            label=text.first_bar
+                                                            min=1
+                                                            max=9999
+                                                            value=first_bar
+                                                            set_value=set_first_bar
+                                                            set_number_errors=set_number_errors
+                                                        />
+                                                        <NumberInput
+                                                            text=text
+                                                            id="last_bar_input"
+                                                            label=text.last_bar
+                                                            min=1
+                                                            max=9999
+                                                            value=last_bar
+                                                            set_value=set_last_bar
+                                                            set_number_errors=set_number_errors
+                                                        />
+                                                    </div>
+                                                }
+                                            })
+                                    }}
+
+                                    <div class="flex flex-col gap-2">
+                                        <OptionLabel
+                                            text=text
+                                            id="tempo_variants_input"
+                                            label=text.tempo_variants
+                                        />
+                                        <input
+                                            class="border-2 rounded p-2 text-slate-900"
+                                            id="tempo_variants_input"
+                                            type="text"
+                                            placeholder=text.tempo_variants_placeholder
+                                            prop:value=tempo_variants_text
+                                            on:input=move |ev| {
+                                                let value = event_target_value(&ev);
+                                                let result = parse_tempo_variants(&value, text);
+                                                set_tempo_variants_text(value);
+                                                set_number_errors
+                                                    .update(|errors| {
+                                                        match result {
+                                                            Ok(variants) => {
+                                                                errors.remove("tempo_variants_input");
+                                                                set_tempo_variants(variants);
+                                                            }
+                                                            Err(error) => {
+                                                                errors.insert("tempo_variants_input", error);
+                                                            }
+                                                        }
+                                                    });
+                                            }
+                                        />
+
+                                    </div>
+
+                                    <div class="flex flex-col gap-2">
+                                        <OptionLabel
+                                            text=text
+                                            id="name_template_input"
+                                            label=text.name_template
+                                        />
+                                        <input
+                                            class="border-2 rounded p-2 text-slate-900"
+                                            id="name_template_input"
+                                            type="text"
+                                            prop:value=name_template
+                                            on:input=move |ev| {
+                                                let template = event_target_value(&ev);
+                                                let result = validate_name_template(&template);
+                                                set_number_errors
+                                                    .update(|errors| {
+                                                        match result {
+                                                            Ok(()) => {
+                                                                errors.remove("name_template_input");
+                                                                set_name_template(template);
+                                                            }
+                                                            Err(error) => {
+                                                                errors.insert("name_template_input", error);
+                                                            }
+                                                        }
+                                                    });
+                                            }
+                                        />
+
+                                    </div>
+
+                                    <Checkbox
+                                        text=text
+                                        id="number_files_input"
+                                        label=text.number_files
+                                        checked=number_files
+                                        set_checked=set_number_files
+                                    />
+                                </div>
+                            </details>
+
+                            <div class="w-full flex flex-col gap-2 p-4 border-2">
+                                <p class="text-lg mb-2">{text.settings}</p>
                                 <a
-                                    class="bg-blue-500 hover:bg-blue-700 font-bold p-4 rounded"
-                                    href=url
-                                    download=zip_name
+                                    class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded text-center"
+                                    href=settings_url
+                                    download=format!("{}_Settings.json", env!("CARGO_PKG_NAME"))
                                 >
-                                    Download
+                                    {text.download_settings}
                                 </a>
-                            },
-                        )
-                    })
-            }}
+                                <button
+                                    class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded"
+                                    on:click=move |_| copy_link_action.dispatch(())
+                                >
+                                    {move || {
+                                        if link_copied() { text.link_copied } else { text.copy_link }
+                                    }}
 
+                                </button>
+                                <button
+                                    class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded"
+                                    on:click=move |_| {
+                                        settings::clear_saved();
+                                        apply_options(ProcessOptions::default());
+                                    }
+                                >
+                                    {text.reset_settings}
+                                </button>
+                                <label class="mb-2 text-sm font-medium" for="settings_input">
+                                    {text.load_settings}
+                                </label>
+                                <input
+                                    class="border-2 rounded p-2 cursor-pointer"
+                                    id="settings_input"
+                                    type="file"
+                                    accept=".json"
+                                    node_ref=settings_input_ref
+                                    on:change=move |_ev| import_settings_action.dispatch(())
+                                />
+                            </div>
+                        </div>
+                    </details>
+
+                    <div
+                        class="w-full flex flex-col p-4 border-2 border-dashed rounded"
+                        class=("hidden", move || !shown_in(WizardStep::Upload))
+                        class=("bg-surface", dragging_over)
+                        class=("border-link-hover", dragging_over)
+                        on:dragover=move |ev| {
+                            // Allows dropping
+                            ev.prevent_default();
+                            set_dragging_over(true);
+                        }
+                        on:dragleave=move |_ev| set_dragging_over(false)
+                        on:drop=move |ev| {
+                            // Keeps the browser from opening the file
+                            ev.prevent_default();
+                            set_dragging_over(false);
+                            let uploads = ev
+                                .data_transfer()
+                                .and_then(|data_transfer| data_transfer.files())
+                                .as_ref()
+                                .map(midi_files)
+                                .unwrap_or_default();
+                            start_processing(uploads);
+                        }
+                    >
+                        <label class="mb-2 text-sm font-medium" for="file_input">
+                            {text.upload}
+                        </label>
+                        <input
+                            class="border-2 rounded p-2 cursor-pointer"
+                            id="file_input"
+                            type="file"
+                            accept=".mid,.midi,.kar,.rmi"
+                            multiple
+                            node_ref=file_input_ref
+                            on:click=move |ev| {
+                                if desktop::is_desktop() {
+                                    ev.prevent_default();
+                                    desktop_open_action.dispatch(None);
+                                }
+                            }
+                            on:change=move |_ev| {
+                                let file_input = file_input_ref
+                                    .get_untracked()
+                                    .expect("<input> not mounted");
+                                start_processing(
+                                    file_input.files().as_ref().map(midi_files).unwrap_or_default(),
+                                );
+                            }
+                        />
+
+                    </div>
+
+                    <Show when=move || has_uploads() && !guided()>
+                        <button
+                            class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded"
+                            disabled=move || process_file_action.pending().get()
+                            on:click=move |_| reprocess()
+                        >
+                            {text.reprocess}
+                        </button>
+                    </Show>
+
+                    <Show when=move || shown_in(WizardStep::PickParts)>
+                        {move || {
+                            previews()
+                                .into_iter()
+                                .map(|(file_name, preview)| {
+                                    let listen_name = file_name.clone();
+                                    let rename_file = file_name.clone();
+                                    let mix_file = file_name.clone();
+                                    let mute_file = file_name.clone();
+                                    let solo_file = file_name.clone();
+                                    let excluded_file = file_name.clone();
+                                    let toggle_file = file_name.clone();
+                                let order_file = file_name.clone();
+                                let reorder_file = file_name.clone();
+                                let track_count = preview.tracks.len();
+                                    let names = track_names
+                                        .with_untracked(|names| names.get(&file_name).cloned())
+                                        .unwrap_or_default();
+                                    view! {
+                                        <TrackPreview
+                                            text=text
+                                            file_name=file_name
+                                            preview=preview
+                                            names=names
+                                            mix=Signal::derive(move || {
+                                                track_mixes
+                                                    .with(|mixes| mixes.get(&mix_file).cloned())
+                                                    .unwrap_or_default()
+                                            })
+                                            on_listen=Callback::new(move |_| listen(&listen_name))
+                                            on_mute=Callback::new(move |track| {
+                                                change_mix(
+                                                    &mute_file,
+                                                    &|mix| {
+                                                        if !mix.muted.remove(&track) {
+                                                            mix.muted.insert(track);
+                                                        }
+                                                    },
+                                                )
+                                            })
+                                            on_solo=Callback::new(move |track| {
+                                                change_mix(
+                                                    &solo_file,
+                                                    &|mix| {
+                                                        if !mix.soloed.remove(&track) {
+                                                            mix.soloed.insert(track);
+                                                        }
+                                                    },
+                                                )
+                                            })
+                                            excluded=Signal::derive(move || {
+                                                excluded_tracks
+                                                    .with(|excluded| excluded.get(&excluded_file).cloned())
+                                                    .unwrap_or_default()
+                                            })
+                                            order=Signal::derive(move || {
+                                            track_orders
+                                                .with(|orders| orders.get(&order_file).cloned())
+                                                .unwrap_or_else(|| (0..track_count).collect())
+                                        })
+                                        on_reorder=Callback::new(move |order| {
+                                            set_track_orders
+                                                .update(|orders| {
+                                                    orders.insert(reorder_file.clone(), order);
+                                                })
+                                        })
+                                        on_toggle_file=Callback::new(move |track| {
+                                                set_excluded_tracks
+                                                    .update(|excluded| {
+                                                        let file_excluded = excluded
+                                                            .entry(toggle_file.clone())
+                                                            .or_default();
+                                                        if !file_excluded.remove(&track) {
+                                                            file_excluded.insert(track);
+                                                        }
+                                                    })
+                                            })
+                                            on_rename=Callback::new(move |(index, name): (usize, String)| {
+                                                set_track_names
+                                                    .update(|names| {
+                                                        let file_names = names
+                                                            .entry(rename_file.clone())
+                                                            .or_default();
+                                                        if name.trim().is_empty() {
+                                                            file_names.remove(&index);
+                                                        } else {
+                                                            file_names.insert(index, name.trim().to_string());
+                                                        }
+                                                    })
+                                            })
+                                        />
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </Show>
+
+                    {move || {
+                        listen_action
+                            .pending()
+                            .get()
+                            .then(|| view! { <p class="text-sm">{text.preparing_playback}</p> })
+                    }}
+
+                    {move || {
+                        playback()
+                            .map(|playback| {
+                                let original_url = playback.original_url.clone();
+                                let can_compare = original_url.is_some();
+                                view! {
+                                    <div class="w-full flex flex-col gap-2 p-4 border-2">
+                                        <p class="text-sm">
+                                            {move || {
+                                                if playing_original() {
+                                                    text.listening_to_original.to_string()
+                                                } else {
+                                                    (text.listening_to)(&playback.name)
+                                                }
+                                            }}
+
+                                        </p>
+                                        <audio
+                                            class="w-full"
+                                            controls
+                                            autoplay
+                                            node_ref=audio_ref
+                                            src=move || {
+                                                match (&original_url, playing_original()) {
+                                                    (Some(original_url), true) => original_url.clone(),
+                                                    _ => playback.url.clone(),
+                                                }
+                                            }
+
+                                            on:loadedmetadata=move |_| {
+                                                let (Some((position, playing)), Some(audio)) = (
+                                                    resume_position.get_value(),
+                                                    audio_ref.get_untracked(),
+                                                ) else {
+                                                    return;
+                                                };
+                                                resume_position.set_value(None);
+                                                audio.set_current_time(position);
+                                                if !playing {
+                                                    let _ = audio.pause();
+                                                }
+                                            }
+                                        ></audio>
+                                        {can_compare
+                                            .then(|| {
+                                                view! {
+                                                    <button
+                                                        class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-2 rounded"
+                                                        on:click=toggle_original
+                                                    >
+                                                        {move || {
+                                                            if playing_original() {
+                                                                text.switch_to_generated
+                                                            } else {
+                                                                text.switch_to_original
+                                                            }
+                                                        }}
+
+                                                    </button>
+                                                }
+                                            })}
+
+                                    </div>
+                                }
+                            })
+                    }}
+
+                    // The processing and its outcome are announced without moving the focus
+                    <div class="w-full" role="status">
+                        {move || {
+                            loading()
+                                .map(|loading| {
+                                    view! {
+                                        <div class="w-full flex flex-col gap-2">
+                                            <progress class="w-full" max="1" value=loading.fraction></progress>
+                                            <p class="text-sm">
+                                                {text.loading(loading.file, loading.files, &loading.name)}
+                                            </p>
+                                        </div>
+                                    }
+                                })
+                        }}
+                        {move || {
+                            progress()
+                                .map(|progress| {
+                                    let value = match progress.phase {
+                                        Phase::Generating { track, tracks } => {
+                                            Some((track as f64 / tracks.max(1) as f64).to_string())
+                                        }
+                                        _ => None,
+                                    };
+                                    view! {
+                                        <div class="w-full flex flex-col gap-2">
+                                            // Without a value the bar shows that the length is unknown
+                                            <progress class="w-full" max="1" value=value></progress>
+                                            <div class="flex gap-4 items-center">
+                                                <p class="text-sm">{text.progress(&progress)}</p>
+                                                <button
+                                                    class="bg-danger text-on-accent font-bold px-4 py-2 rounded"
+                                                    on:click=move |_| cancel_handle.with_value(CancelHandle::cancel)
+                                                >
+                                                    {text.cancel}
+                                                </button>
+                                            </div>
+                                        </div>
+                                    }
+                                })
+                        }}
+                        {move || {
+                            (progress().is_none() && !files().is_empty())
+                                .then(|| {
+                                    view! {
+                                        <p class="sr-only">{(text.files_ready)(files().len())}</p>
+                                    }
+                                })
+                        }}
+                    </div>
+
+                    <Show when=move || shown_in(WizardStep::Download)>
+                        {move || {
+                            if !files().is_empty() {
+                                Some(
+                                    view! {
+                                        <section
+                                            class="flex flex-col gap-2 p-4 border-2"
+                                            hidden=move || files().is_empty()
+                                            aria-label=text.created_files
+                                            aria-busy=move || process_file_action.pending().get().to_string()
+                                        >
+                                            <Show when=move || !skipped().is_empty()>
+                                                <div class="flex flex-col gap-1 mb-2">
+                                                    <p>{text.skipped_parts}</p>
+                                                    <ul class="list-disc pl-6 text-sm">
+                                                        {move || {
+                                                            skipped()
+                                                                .iter()
+                                                                .map(|skipped| view! { <li>{text.skipped(skipped)}</li> })
+                                                                .collect_view()
+                                                        }}
+                                                    </ul>
+                                                </div>
+                                            </Show>
+                                            <Show when=move || !repairs().is_empty()>
+                                                <div class="flex flex-col gap-1 mb-2">
+                                                    <p>{text.repaired_mistakes}</p>
+                                                    <ul class="list-disc pl-6 text-sm">
+                                                        {move || {
+                                                            repairs()
+                                                                .iter()
+                                                                .map(|repair| view! { <li>{text.repair(repair)}</li> })
+                                                                .collect_view()
+                                                        }}
+                                                    </ul>
+                                                </div>
+                                            </Show>
+                                            <p class="text-lg mb-2">{text.created_files}</p>
+                                            <For
+                                                each=files
+                                                key=|(_, url, _)| url.clone()
+                                                children=move |(file_name, url, size)| {
+                                                    let share_name = file_name.clone();
+                                                    let listen_name = file_name.clone();
+                                                    let compare_name = file_name.clone();
+                                                    let saved_name = file_name.clone();
+                                                    // Browsers do not create the folders of songs
+                                                    let download_name = file_name
+                                                        .rsplit('/')
+                                                        .next()
+                                                        .unwrap_or_default()
+                                                        .to_string();
+                                                    view! {
+                                                        <div class="flex flex-wrap gap-x-4 gap-y-1 items-center">
+                                                            <a
+                                                                class="text-m underline hover:text-link-hover break-all"
+                                                                href=url
+                                                                download=download_name
+                                                                on:click=move |ev| {
+                                                                    if desktop::is_desktop() {
+                                                                        ev.prevent_default();
+                                                                        download_action.dispatch(Some(saved_name.clone()));
+                                                                    }
+                                                                }
+                                                            >
+                                                                {file_name}
+                                                            </a>
+                                                            <span class="text-sm">{format_size(size)}</span>
+                                                            {(mime_type(&listen_name) == "audio/midi")
+                                                                .then(|| {
+                                                                    view! {
+                                                                        <button
+                                                                            class="text-sm underline hover:text-link-hover"
+                                                                            aria-label=(text.listen_to)(&listen_name)
+                                                                            on:click=move |_| listen(&listen_name)
+                                                                        >
+                                                                            {text.listen}
+                                                                        </button>
+                                                                        <button
+                                                                            class="text-sm underline hover:text-link-hover"
+                                                                            aria-label=(text.compare_with_original)(&compare_name)
+                                                                            on:click=move |_| {
+                                                                                set_compared_file(Some(compare_name.clone()))
+                                                                            }
+                                                                        >
+                                                                            {text.compare}
+                                                                        </button>
+                                                                    }
+                                                                })}
+                                                            {share::is_supported()
+                                                                .then(|| {
+                                                                    view! {
+                                                                        <button
+                                                                            class="text-sm underline hover:text-link-hover"
+                                                                            on:click=move |_| {
+                                                                                share_action.dispatch(Some(share_name.clone()))
+                                                                            }
+                                                                        >
+                                                                            {text.share}
+                                                                        </button>
+                                                                    }
+                                                                })}
+
+                                                        </div>
+                                                    }
+                                                }
+                                            />
+
+                                        </section>
+                                    },
+                                )
+                            } else {
+                                None
+                            }
+                        }}
+
+                        {move || {
+                            file_url()
+                                .map(|url| {
+                                    Some(
+                                        view! {
+                                            // Stays in view while scrolling through the created files
+                                            <div class="sticky bottom-0 z-10 w-full flex justify-center py-2 bg-page">
+                                                <a
+                                                    class="w-full sm:w-auto text-center bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded"
+                                                    href=url
+                                                    download=archive_name
+                                                    node_ref=download_ref
+                                                    on:click=move |ev| {
+                                                        if desktop::is_desktop() {
+                                                            ev.prevent_default();
+                                                            download_action.dispatch(None);
+                                                        }
+                                                    }
+                                                >
+                                                    {text.download}
+                                                    " ("
+                                                    {format_size(archive_size())}
+                                                    ")"
+                                                </a>
+                                            </div>
+                                        },
+                                    )
+                                })
+                        }}
+
+                        {move || {
+                            (file_url().is_some() && share::is_supported())
+                                .then(|| {
+                                    view! {
+                                        <button
+                                            class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded"
+                                            on:click=move |_| share_action.dispatch(None)
+                                        >
+                                            {text.share}
+                                        </button>
+                                    }
+                                })
+                        }}
+
+                        // Browsers without the File System Access API only offer the download
+                        {move || {
+                            (file_url().is_some()
+                                && (directory::is_supported() || desktop::is_desktop()))
+                                .then(|| {
+                                    view! {
+                                        <button
+                                            class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded"
+                                            on:click=move |_| save_action.dispatch(())
+                                        >
+                                            {text.save_to_folder}
+                                        </button>
+                                    }
+                                })
+                        }}
+
+                        {move || {
+                            save_progress()
+                                .map(|(saved, total)| {
+                                    view! {
+                                        <p class="text-sm" role="status">
+                                            {(text.saved_files)(saved, total)}
+                                        </p>
+                                    }
+                                })
+                        }}
+
+                        {move || {
+                            file_diff()
+                                .map(|(file_name, diffs)| {
+                                    view! {
+                                        <EventDiff
+                                            text=text
+                                            file_name=file_name
+                                            diffs=diffs
+                                            on_close=Callback::new(move |_| set_compared_file(None))
+                                        />
+                                    }
+                                })
+                        }}
+                    </Show>
+
+                    <Show when=move || shown_in(WizardStep::Upload)>
+
+                        {move || {
+                            (!history().is_empty())
+                                .then(|| {
+                                    view! {
+                                        <div class="w-full flex flex-col gap-2 p-4 border-2">
+                                            <p class="text-lg mb-2">{text.history}</p>
+                                            {history()
+                                                .into_iter()
+                                                .map(|entry| {
+                                                    let date = entry.date(locale.get_untracked().code());
+                                                    let file_names = entry.file_names.join(", ");
+                                                    let kept = entry.has_files();
+                                                    view! {
+                                                        <div class="flex flex-wrap gap-x-4 gap-y-1 items-center text-sm">
+                                                            <span>{date}</span>
+                                                            <span class="grow">{file_names}</span>
+                                                            {if kept {
+                                                                view! {
+                                                                    <button
+                                                                        class="underline hover:text-link-hover"
+                                                                        on:click=move |_| process_from_history(&entry)
+                                                                    >
+                                                                        {text.process_again}
+                                                                    </button>
+                                                                }
+                                                                    .into_view()
+                                                            } else {
+                                                                view! { <span>{text.history_files_too_large}</span> }
+                                                                    .into_view()
+                                                            }}
+                                                        </div>
+                                                    }
+                                                })
+                                                .collect_view()}
+                                            <button
+                                                class="self-start text-sm underline hover:text-link-hover"
+                                                on:click=move |_| {
+                                                    history::clear();
+                                                    set_history(Vec::new());
+                                                }
+                                            >
+                                                {text.clear_history}
+                                            </button>
+                                        </div>
+                                    }
+                                })
+                        }}
+                    </Show>
+
+                    <Show when=guided>
+                        <div class="w-full flex gap-4 justify-between">
+                            {move || {
+                                wizard_step()
+                                    .previous()
+                                    .map(|previous| {
+                                        view! {
+                                            <button
+                                                class="p-4 rounded border-2 font-bold"
+                                                on:click=move |_| set_wizard_step(previous)
+                                            >
+                                                {text.back}
+                                            </button>
+                                        }
+                                    })
+                            }}
+                            <span class="grow"></span>
+                            {move || match wizard_step() {
+                                WizardStep::Settings => {
+                                    view! {
+                                        <button
+                                            class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded"
+                                            disabled=move || process_file_action.pending().get()
+                                            on:click=move |_| {
+                                                if has_uploads.get_untracked() && settings_valid() {
+                                                    process_file_action.dispatch(uploaded_files.get_value());
+                                                    set_wizard_step(WizardStep::Download);
+                                                }
+                                            }
+                                        >
+                                            {text.create_files}
+                                        </button>
+                                    }
+                                }
+                                WizardStep::Download => {
+                                    view! {
+                                        <button
+                                            class="p-4 rounded border-2 font-bold"
+                                            on:click=move |_| set_wizard_step(WizardStep::Upload)
+                                        >
+                                            {text.start_over}
+                                        </button>
+                                    }
+                                }
+                                step => {
+                                    let next = step.next().unwrap_or(step);
+                                    view! {
+                                        <button
+                                            class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-4 rounded"
+                                            disabled=move || !step_reachable(next)
+                                            on:click=move |_| set_wizard_step(next)
+                                        >
+                                            {text.next}
+                                        </button>
+                                    }
+                                }
+                            }}
+                        </div>
+                    </Show>
+
+                    <Show when=move || !guided()>
+                        <RestoreVelocities text=text/>
+                    </Show>
+
+                    <Show when=shortcut_help>
+                        <div
+                            class="fixed inset-0 flex items-center justify-center bg-black/50"
+                            on:click=move |_| set_shortcut_help(false)
+                        >
+                            <div
+                                class="flex flex-col gap-4 p-6 rounded bg-surface"
+                                role="dialog"
+                                aria-modal="true"
+                                aria-label=text.shortcuts
+                                on:click=|ev| ev.stop_propagation()
+                            >
+                                <p class="text-lg font-bold">{text.shortcuts}</p>
+                                <table class="text-sm">
+                                    {Shortcut::ALL
+                                        .into_iter()
+                                        .map(|shortcut| {
+                                            view! {
+                                                <tr>
+                                                    <td class="pr-4 py-1">
+                                                        <kbd class="px-2 py-1 rounded bg-sunken font-mono">
+                                                            {shortcut.key()}
+                                                        </kbd>
+                                                    </td>
+                                                    <td>{shortcut.description(text)}</td>
+                                                </tr>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </table>
+                                <button
+                                    class="bg-accent hover:bg-accent-hover text-on-accent font-bold p-2 rounded"
+                                    node_ref=close_help_ref
+                                    on:click=move |_| set_shortcut_help(false)
+                                >
+                                    {text.close}
+                                </button>
+                            </div>
+                        </div>
+                    </Show>
+                }
+            }}
         </div>
     }
 }
@@ -0,0 +1,57 @@
+use web_sys::{
+    js_sys::Reflect,
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    EventTarget, MessageEvent,
+};
+
+use crate::{desktop, js::call_async};
+
+/// Script which caches the page so it also opens without a network, see public/sw.js
+const SERVICE_WORKER: &str = "sw.js";
+
+/// Message the service worker sends once it has cached a new version of the page
+const UPDATE_AVAILABLE: &str = "update-available";
+
+/// Service worker container of the browser, if it supports service workers.
+/// Browsers only offer it to pages served securely.
+fn service_workers() -> Option<JsValue> {
+    let navigator = Reflect::get(&leptos::window(), &"navigator".into()).ok()?;
+    Reflect::get(&navigator, &"serviceWorker".into())
+        .ok()
+        .filter(|container| !container.is_undefined())
+}
+
+/// Register the service worker which keeps the app usable offline, calling `on_update` when a
+/// new version was downloaded and is used after a reload
+pub fn register(on_update: impl Fn() + 'static) {
+    // The desktop app has its files with it
+    if desktop::is_desktop() {
+        return;
+    }
+    let Some(container) = service_workers() else {
+        return;
+    };
+
+    let listener = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+        let kind = Reflect::get(&event.data(), &"type".into()).ok();
+        if kind.and_then(|kind| kind.as_string()).as_deref() == Some(UPDATE_AVAILABLE) {
+            on_update();
+        }
+    });
+    if let Ok(target) = container.clone().dyn_into::<EventTarget>() {
+        let _ =
+            target.add_event_listener_with_callback("message", listener.as_ref().unchecked_ref());
+    }
+    // Listens for as long as the page is open
+    listener.forget();
+
+    // Without the service worker the app still works, it just needs a network to open
+    leptos::spawn_local(async move {
+        let _ = call_async(&container, "register", &[SERVICE_WORKER.into()]).await;
+    });
+}
+
+/// Load the page again to switch to the new version
+pub fn reload() {
+    let _ = leptos::window().location().reload();
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeSet;
+
+use midi_splitter_core::{
+    find_track_name, gm,
+    key::{self, Key},
+    notes, riff, timing, File,
+};
+use midly::{Format, Smf, Timing, TrackEventKind};
+
+use crate::anomalies::{self, Anomaly};
+
+/// Summary of a track shown before any files are generated
+#[derive(Clone)]
+pub struct TrackInfo {
+    pub index: usize,
+    pub name: Option<String>,
+    /// Channels used by the track counting from 0
+    pub channels: BTreeSet<u8>,
+    pub note_count: usize,
+    pub instrument: Option<&'static str>,
+    /// Symbol of the instrument family
+    pub icon: Option<&'static str>,
+    /// Bar in which the last note of the track ends, unknown for timecode files
+    pub end_bar: Option<usize>,
+    /// Time at which the last note of the track ends, if it has any notes
+    pub end_seconds: Option<f64>,
+}
+
+/// Note drawn on the piano roll
+#[derive(Clone, Copy)]
+pub struct RollNote {
+    pub track: usize,
+    pub key: u8,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
+/// Tempo changes of a file
+#[derive(Clone)]
+pub struct TempoInfo {
+    pub initial_bpm: f64,
+    /// Number of times the tempo changes after the start
+    pub changes: usize,
+    pub min_bpm: f64,
+    pub max_bpm: f64,
+}
+
+/// Time signature as shown in the preview
+#[derive(Clone)]
+pub struct TimeSignatureInfo {
+    /// Bar the time signature starts in counting from 1, unknown for timecode files
+    pub bar: Option<usize>,
+    pub numerator: u8,
+    pub denominator: u32,
+}
+
+/// Facts from the header of a file
+#[derive(Clone)]
+pub struct HeaderInfo {
+    pub format: Format,
+    pub timing: Timing,
+    pub track_count: usize,
+    /// Size of the upload in bytes
+    pub size: usize,
+}
+
+/// Everything shown about an uploaded file before any files are generated
+#[derive(Clone)]
+pub struct FilePreview {
+    pub header: HeaderInfo,
+    pub tracks: Vec<TrackInfo>,
+    pub notes: Vec<RollNote>,
+    /// Velocities of all notes in the file
+    pub velocities: Vec<u8>,
+    /// Problems found in the file which do not keep it from being processed
+    pub anomalies: Vec<Anomaly>,
+    pub length_seconds: f64,
+    pub tempo: TempoInfo,
+    pub time_signatures: Vec<TimeSignatureInfo>,
+    /// Key estimated from the notes, if there are any pitched ones
+    pub estimated_key: Option<Key>,
+    /// Key given by the first KeySignature event
+    pub signature_key: Option<Key>,
+}
+
+/// Colour a track is drawn with, spreading the hues of neighbouring tracks apart
+pub fn track_colour(index: usize) -> String {
+    format!("hsl({}, 70%, 60%)", index * 137 % 360)
+}
+
+/// Summarise the tracks of an uploaded file and collect its notes
+pub fn preview(file: &File) -> anyhow::Result<FilePreview> {
+    let data = if riff::is_riff_midi(&file.data) {
+        riff::unwrap(&file.data)?
+    } else {
+        file.data.as_slice()
+    };
+    let smf = Smf::parse(data)?;
+    let tempo_map = timing::TempoMap::new(&smf.tracks, smf.header.timing);
+    let time_signatures = timing::time_signatures(&smf.tracks);
+
+    let mut tracks = Vec::new();
+    let mut roll_notes = Vec::new();
+    let mut velocities = Vec::new();
+    let mut file_anomalies = Vec::new();
+    let ticks_per_beat = timing::ticks_per_beat(smf.header.timing).ok();
+    for (index, track) in smf.tracks.iter().enumerate() {
+        let track_notes = notes::notes(track);
+        velocities.extend(track_notes.iter().map(|note| note.velocity));
+        file_anomalies.extend(anomalies::check_track(index, track));
+        let end = track_notes.iter().map(|note| note.end).max();
+        roll_notes.extend(track_notes.iter().map(|note| RollNote {
+            track: index,
+            key: note.key,
+            start_seconds: tempo_map.seconds(note.start),
+            end_seconds: tempo_map.seconds(note.end),
+        }));
+
+        tracks.push(TrackInfo {
+            index,
+            // Tracks whose name is not text can still be given a name of their own
+            name: find_track_name(track).ok().flatten().map(str::to_string),
+            channels: track
+                .iter()
+                .filter_map(|event| match event.kind {
+                    TrackEventKind::Midi { channel, .. } => Some(channel.as_int()),
+                    _ => None,
+                })
+                .collect(),
+            note_count: track_notes.len(),
+            instrument: gm::track_instrument(track),
+            icon: gm::track_icon(track),
+            end_bar: end.zip(ticks_per_beat).map(|(end, ticks_per_beat)| {
+                timing::bar_count(&time_signatures, ticks_per_beat, end)
+            }),
+            end_seconds: end.map(|end| tempo_map.seconds(end)),
+        });
+    }
+
+    // The default tempo applies until the first Tempo event
+    let mut tempos = timing::tempos(&smf.tracks);
+    if tempos.first().is_none_or(|(tick, _)| *tick > 0) {
+        tempos.insert(0, (0, timing::DEFAULT_TEMPO));
+    }
+    // A later tempo at the same tick replaces the earlier one
+    tempos.reverse();
+    tempos.dedup_by_key(|(tick, _)| *tick);
+    tempos.reverse();
+    tempos.dedup_by_key(|(_, tempo)| *tempo);
+    let bpms = tempos.iter().map(|(_, tempo)| timing::bpm(*tempo));
+    let tempo = TempoInfo {
+        initial_bpm: timing::bpm(tempos[0].1),
+        changes: tempos.len() - 1,
+        min_bpm: bpms.clone().fold(f64::INFINITY, f64::min),
+        max_bpm: bpms.fold(0.0, f64::max),
+    };
+
+    let time_signatures = time_signatures
+        .iter()
+        .map(|time_signature| TimeSignatureInfo {
+            bar: ticks_per_beat.map(|ticks_per_beat| {
+                timing::bar_count(&time_signatures, ticks_per_beat, time_signature.tick) + 1
+            }),
+            numerator: time_signature.numerator,
+            denominator: 1 << time_signature.denominator_pow2.min(31),
+        })
+        .collect();
+
+    Ok(FilePreview {
+        header: HeaderInfo {
+            format: smf.header.format,
+            timing: smf.header.timing,
+            track_count: smf.tracks.len(),
+            size: file.data.len(),
+        },
+        tracks,
+        notes: roll_notes,
+        velocities,
+        anomalies: file_anomalies,
+        length_seconds: tempo_map.seconds(timing::end_tick(&smf.tracks)),
+        tempo,
+        time_signatures,
+        estimated_key: key::estimate_key(&smf.tracks),
+        signature_key: key::signature_key(&smf.tracks),
+    })
+}
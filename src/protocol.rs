@@ -0,0 +1,198 @@
+//! Messages between the page and the worker processing the uploads, which is a separate
+//! program sharing this module
+
+// The page and the worker each use one side of the messages
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use midi_splitter_core::{File, Outputs, ProcessOptions, Progress, SplitError};
+use serde::{Deserialize, Serialize};
+use web_sys::{
+    js_sys::{Array, Object, Reflect, Uint8Array},
+    wasm_bindgen::{JsCast, JsValue},
+};
+
+/// Uploads to process with the settings to use
+pub struct Request {
+    pub files: Vec<File>,
+    pub options: ProcessOptions,
+}
+
+/// Settings including those belonging to single uploads, which are not saved with the others
+#[derive(Serialize, Deserialize)]
+struct Settings {
+    options: ProcessOptions,
+    track_names: HashMap<String, BTreeMap<usize, String>>,
+    excluded_tracks: HashMap<String, BTreeSet<usize>>,
+    track_orders: HashMap<String, Vec<usize>>,
+}
+
+/// What the worker reports back
+pub enum Response {
+    Progress(Progress),
+    Done(Outputs),
+    Failed(SplitError),
+}
+
+fn set(object: &Object, key: &str, value: &JsValue) {
+    Reflect::set(object, &key.into(), value).expect("Failed to set a property");
+}
+
+fn get(object: &JsValue, key: &str) -> anyhow::Result<JsValue> {
+    Reflect::get(object, &key.into())
+        .ok()
+        .filter(|value| !value.is_undefined())
+        .ok_or_else(|| anyhow::anyhow!("The message has no {}", key))
+}
+
+fn get_string(object: &JsValue, key: &str) -> anyhow::Result<String> {
+    get(object, key)?
+        .as_string()
+        .ok_or_else(|| anyhow::anyhow!("{} of the message is not text", key))
+}
+
+fn get_bytes(object: &JsValue, key: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(get(object, key)?
+        .dyn_into::<Uint8Array>()
+        .map_err(|_| anyhow::anyhow!("{} of the message is not bytes", key))?
+        .to_vec())
+}
+
+/// Copy the bytes into JavaScript, adding their buffer to those handed over with the message
+/// instead of being copied once more
+fn bytes_to_js(data: &[u8], transfer: &Array) -> Uint8Array {
+    let bytes = Uint8Array::from(data);
+    transfer.push(&bytes.buffer());
+    bytes
+}
+
+fn files_to_js(files: &[File], transfer: &Array) -> Array {
+    files
+        .iter()
+        .map(|file| {
+            let object = Object::new();
+            set(&object, "name", &file.name.clone().into());
+            set(&object, "data", &bytes_to_js(&file.data, transfer));
+            JsValue::from(object)
+        })
+        .collect()
+}
+
+fn files_from_js(object: &JsValue, key: &str) -> anyhow::Result<Vec<File>> {
+    get(object, key)?
+        .dyn_into::<Array>()
+        .map_err(|_| anyhow::anyhow!("{} of the message is not a list", key))?
+        .iter()
+        .map(|file| {
+            Ok(File {
+                name: get_string(&file, "name")?,
+                data: get_bytes(&file, "data")?,
+            })
+        })
+        .collect()
+}
+
+impl Request {
+    /// The message to post and the buffers to transfer with it
+    pub fn to_js(&self) -> anyhow::Result<(JsValue, Array)> {
+        let settings = Settings {
+            options: self.options.clone(),
+            track_names: self.options.track_names.clone(),
+            excluded_tracks: self.options.excluded_tracks.clone(),
+            track_orders: self.options.track_orders.clone(),
+        };
+        let transfer = Array::new();
+        let message = Object::new();
+        set(
+            &message,
+            "settings",
+            &serde_json::to_string(&settings)?.into(),
+        );
+        set(&message, "files", &files_to_js(&self.files, &transfer));
+        Ok((message.into(), transfer))
+    }
+
+    pub fn from_js(message: &JsValue) -> anyhow::Result<Request> {
+        let settings: Settings = serde_json::from_str(&get_string(message, "settings")?)?;
+        Ok(Request {
+            files: files_from_js(message, "files")?,
+            options: ProcessOptions {
+                track_names: settings.track_names,
+                excluded_tracks: settings.excluded_tracks,
+                track_orders: settings.track_orders,
+                ..settings.options
+            },
+        })
+    }
+}
+
+impl Response {
+    /// The message to post and the buffers to transfer with it
+    pub fn to_js(&self) -> anyhow::Result<(JsValue, Array)> {
+        let transfer = Array::new();
+        let message = Object::new();
+        match self {
+            Response::Progress(progress) => {
+                set(&message, "kind", &"progress".into());
+                set(
+                    &message,
+                    "progress",
+                    &serde_json::to_string(progress)?.into(),
+                );
+            }
+            Response::Done(outputs) => {
+                set(&message, "kind", &"done".into());
+                set(
+                    &message,
+                    "archive_name",
+                    &outputs.archive_name.clone().into(),
+                );
+                set(
+                    &message,
+                    "archive",
+                    &bytes_to_js(&outputs.archive, &transfer),
+                );
+                set(&message, "files", &files_to_js(&outputs.files, &transfer));
+                set(
+                    &message,
+                    "folders",
+                    &serde_json::to_string(&outputs.folders)?.into(),
+                );
+                set(
+                    &message,
+                    "skipped",
+                    &serde_json::to_string(&outputs.skipped)?.into(),
+                );
+                set(
+                    &message,
+                    "repairs",
+                    &serde_json::to_string(&outputs.repairs)?.into(),
+                );
+            }
+            Response::Failed(error) => {
+                set(&message, "kind", &"failed".into());
+                set(&message, "error", &serde_json::to_string(error)?.into());
+            }
+        }
+        Ok((message.into(), transfer))
+    }
+
+    pub fn from_js(message: &JsValue) -> anyhow::Result<Response> {
+        Ok(match get_string(message, "kind")?.as_str() {
+            "progress" => {
+                Response::Progress(serde_json::from_str(&get_string(message, "progress")?)?)
+            }
+            "done" => Response::Done(Outputs {
+                archive_name: get_string(message, "archive_name")?,
+                files: files_from_js(message, "files")?,
+                archive: get_bytes(message, "archive")?,
+                folders: serde_json::from_str(&get_string(message, "folders")?)?,
+                skipped: serde_json::from_str(&get_string(message, "skipped")?)?,
+                repairs: serde_json::from_str(&get_string(message, "repairs")?)?,
+            }),
+            "failed" => Response::Failed(serde_json::from_str(&get_string(message, "error")?)?),
+            kind => anyhow::bail!("Unknown message {}", kind),
+        })
+    }
+}
@@ -0,0 +1,192 @@
+pub use midi_splitter_core::preset::{export, import};
+use midi_splitter_core::{ClickTrack, Mode, ProcessOptions, VelocityMapping};
+use web_sys::{
+    js_sys::{decode_uri_component, encode_uri_component},
+    wasm_bindgen::JsValue,
+    Storage,
+};
+
+use crate::i18n::Strings;
+
+/// Key of the settings saved in the local storage of the browser
+const STORAGE_KEY: &str = "midi_splitter_settings";
+
+/// Name of the URL fragment parameter holding the settings
+const FRAGMENT_KEY: &str = "settings=";
+
+/// Settings shipped with the tool for common ensembles
+#[derive(Clone, Copy, PartialEq)]
+pub enum BuiltinPreset {
+    SatbChoir,
+    ConcertBand,
+    PianoDuet,
+    WorshipTeam,
+}
+
+impl BuiltinPreset {
+    pub const ALL: [BuiltinPreset; 4] = [
+        BuiltinPreset::SatbChoir,
+        BuiltinPreset::ConcertBand,
+        BuiltinPreset::PianoDuet,
+        BuiltinPreset::WorshipTeam,
+    ];
+
+    /// Identifier used in the preset picker
+    pub fn id(&self) -> &'static str {
+        match self {
+            BuiltinPreset::SatbChoir => "satb_choir",
+            BuiltinPreset::ConcertBand => "concert_band",
+            BuiltinPreset::PianoDuet => "piano_duet",
+            BuiltinPreset::WorshipTeam => "worship_team",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<BuiltinPreset> {
+        BuiltinPreset::ALL
+            .into_iter()
+            .find(|preset| preset.id() == id)
+    }
+
+    pub fn name(&self, text: &Strings) -> &'static str {
+        match self {
+            BuiltinPreset::SatbChoir => text.preset_satb_choir,
+            BuiltinPreset::ConcertBand => text.preset_concert_band,
+            BuiltinPreset::PianoDuet => text.preset_piano_duet,
+            BuiltinPreset::WorshipTeam => text.preset_worship_team,
+        }
+    }
+
+    /// Settings of the preset. Anything the ensemble does not need keeps its default.
+    pub fn options(&self) -> ProcessOptions {
+        let defaults = ProcessOptions::default();
+        match self {
+            // Singers hear their part in one ear and the rest of the choir in the other
+            BuiltinPreset::SatbChoir => ProcessOptions {
+                velocity_mapping: VelocityMapping::Reduce {
+                    amount: 40,
+                    threshold: 20,
+                },
+                velocity_boost: 10,
+                practice_panning: true,
+                name_template: "{song} - {track}".to_string(),
+                ..defaults
+            },
+            // Bands have many parts, so the files are numbered in score order
+            BuiltinPreset::ConcertBand => ProcessOptions {
+                velocity_mapping: VelocityMapping::Reduce {
+                    amount: 30,
+                    threshold: 10,
+                },
+                keep_drums: true,
+                number_files: true,
+                cue_sheet: true,
+                ..defaults
+            },
+            // Each player practises along with the part of the other
+            BuiltinPreset::PianoDuet => ProcessOptions {
+                mode: Mode::MinusOne,
+                all_tracks_suffix: Some("Both".to_string()),
+                ..defaults
+            },
+            // The band plays to a click with the drums always audible
+            BuiltinPreset::WorshipTeam => ProcessOptions {
+                velocity_mapping: VelocityMapping::Compress {
+                    target: 50,
+                    ratio: 3,
+                    makeup: 0,
+                },
+                keep_drums: true,
+                click_track: Some(ClickTrack {
+                    downbeat_velocity: 100,
+                    beat_velocity: 70,
+                }),
+                ..defaults
+            },
+        }
+    }
+}
+
+/// Local storage of the browser if it is available
+fn local_storage() -> Option<Storage> {
+    leptos::window().local_storage().ok().flatten()
+}
+
+/// Settings saved by a previous session, if there are any which can still be read
+pub fn load_saved() -> Option<ProcessOptions> {
+    let saved = local_storage()?.get_item(STORAGE_KEY).ok()??;
+    import(saved.as_bytes()).ok()
+}
+
+/// Save the settings so they get restored in the next session
+pub fn save(options: &ProcessOptions) {
+    let (Some(storage), Ok(preset)) = (local_storage(), export(options)) else {
+        return;
+    };
+    // Saving fails if the storage is full or disabled, which only loses the convenience
+    let _ = storage.set_item(STORAGE_KEY, &String::from_utf8_lossy(&preset));
+}
+
+/// Forget the saved settings
+pub fn clear_saved() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(STORAGE_KEY);
+    }
+}
+
+/// Settings which differ from the defaults as a URL fragment, empty if there are none
+pub fn to_url_fragment(options: &ProcessOptions) -> anyhow::Result<String> {
+    let serde_json::Value::Object(mut changed) = serde_json::to_value(options)? else {
+        anyhow::bail!("The settings are not an object");
+    };
+    let serde_json::Value::Object(defaults) = serde_json::to_value(ProcessOptions::default())?
+    else {
+        anyhow::bail!("The settings are not an object");
+    };
+    // Missing settings keep their default when loaded, which keeps the links short
+    changed.retain(|key, value| defaults.get(key) != Some(value));
+    if changed.is_empty() {
+        return Ok(String::new());
+    }
+
+    let json = serde_json::to_string(&changed)?;
+    Ok(format!(
+        "{}{}",
+        FRAGMENT_KEY,
+        String::from(encode_uri_component(&json))
+    ))
+}
+
+/// Settings stored in a URL fragment by [to_url_fragment]
+pub fn from_url_fragment(fragment: &str) -> anyhow::Result<Option<ProcessOptions>> {
+    let Some(encoded) = fragment.trim_start_matches('#').strip_prefix(FRAGMENT_KEY) else {
+        return Ok(None);
+    };
+    let json: String = decode_uri_component(encoded)
+        .map_err(|_| anyhow::anyhow!("The settings in the link are not encoded correctly"))?
+        .into();
+    let options = serde_json::from_str(&json)
+        .map_err(|e| anyhow::anyhow!("The settings in the link are invalid: {}", e))?;
+    Ok(Some(options))
+}
+
+/// Put the settings into the URL of the page so it can be bookmarked, without adding to the
+/// history
+pub fn update_url(options: &ProcessOptions) {
+    let window = leptos::window();
+    let Ok(fragment) = to_url_fragment(options) else {
+        return;
+    };
+    let location = window.location();
+    let mut url = format!(
+        "{}{}",
+        location.pathname().unwrap_or_default(),
+        location.search().unwrap_or_default()
+    );
+    if !fragment.is_empty() {
+        url.push('#');
+        url.push_str(&fragment);
+    }
+    if let Ok(history) = window.history() {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+    }
+}
@@ -0,0 +1,56 @@
+use midi_splitter_core::File;
+use web_sys::{
+    js_sys::{Array, Function, Object, Reflect, Uint8Array},
+    wasm_bindgen::JsValue,
+    FilePropertyBag,
+};
+
+use crate::{
+    i18n::Strings,
+    js::{call_async, error_message, is_abort},
+};
+
+fn navigator() -> JsValue {
+    Reflect::get(&leptos::window(), &"navigator".into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+/// Whether the browser can share files at all
+pub fn is_supported() -> bool {
+    Reflect::has(&navigator(), &"canShare".into()).unwrap_or(false)
+}
+
+/// Hand a file to the share sheet of the operating system.
+/// Returns false if the user closed the share sheet.
+pub async fn share(file: &File, mime_type: &str, text: &Strings) -> Result<bool, String> {
+    let data = Array::of1(&Uint8Array::from(file.data.as_slice()));
+    let js_file = web_sys::File::new_with_u8_array_sequence_and_options(
+        &data,
+        &file.name,
+        FilePropertyBag::new().type_(mime_type),
+    )
+    .map_err(|e| error_message(&e))?;
+
+    let share_data = Object::new();
+    Reflect::set(&share_data, &"files".into(), &Array::of1(&js_file))
+        .map_err(|e| error_message(&e))?;
+
+    let navigator = navigator();
+    if !can_share(&navigator, &share_data) {
+        return Err((text.share_unsupported)(&file.name));
+    }
+
+    match call_async(&navigator, "share", &[share_data.into()]).await {
+        Ok(_) => Ok(true),
+        Err(error) if is_abort(&error) => Ok(false),
+        Err(error) => Err(error_message(&error)),
+    }
+}
+
+/// Whether the browser can share the given data
+fn can_share(navigator: &JsValue, share_data: &Object) -> bool {
+    Reflect::get(navigator, &"canShare".into())
+        .ok()
+        .and_then(|can_share| Function::from(can_share).call1(navigator, share_data).ok())
+        .and_then(|result| result.as_bool())
+        .unwrap_or(false)
+}
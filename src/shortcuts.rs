@@ -0,0 +1,68 @@
+use web_sys::{wasm_bindgen::JsCast, Element, KeyboardEvent};
+
+use crate::i18n::Strings;
+
+/// Action of the main workflow which can be triggered from the keyboard
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shortcut {
+    OpenFiles,
+    Reprocess,
+    Download,
+    PlayPause,
+    Help,
+}
+
+impl Shortcut {
+    /// Shortcuts in the order they are listed in the help
+    pub const ALL: [Shortcut; 5] = [
+        Shortcut::OpenFiles,
+        Shortcut::Reprocess,
+        Shortcut::Download,
+        Shortcut::PlayPause,
+        Shortcut::Help,
+    ];
+
+    /// Key triggering the shortcut as shown in the help
+    pub fn key(&self) -> &'static str {
+        match self {
+            Shortcut::OpenFiles => "O",
+            Shortcut::Reprocess => "R",
+            Shortcut::Download => "D",
+            Shortcut::PlayPause => "P",
+            Shortcut::Help => "?",
+        }
+    }
+
+    pub fn description(&self, text: &Strings) -> &'static str {
+        match self {
+            Shortcut::OpenFiles => text.shortcut_open_files,
+            Shortcut::Reprocess => text.shortcut_reprocess,
+            Shortcut::Download => text.shortcut_download,
+            Shortcut::PlayPause => text.shortcut_play_pause,
+            Shortcut::Help => text.shortcut_help,
+        }
+    }
+
+    /// Shortcut triggered by a key press, if any
+    pub fn from_event(ev: &KeyboardEvent) -> Option<Shortcut> {
+        // Leaves the shortcuts of the browser alone
+        if ev.ctrl_key() || ev.meta_key() || ev.alt_key() {
+            return None;
+        }
+        // Typing into a field must not trigger anything
+        let typing = ev
+            .target()
+            .and_then(|target| target.dyn_into::<Element>().ok())
+            .is_some_and(|element| {
+                matches!(element.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA")
+            });
+        if typing {
+            return None;
+        }
+
+        let key = ev.key();
+        Shortcut::ALL
+            .into_iter()
+            .find(|shortcut| shortcut.key().eq_ignore_ascii_case(&key))
+    }
+}
@@ -0,0 +1,54 @@
+/// Key of the theme saved in the local storage of the browser
+const STORAGE_KEY: &str = "midi_splitter_theme";
+
+/// Colour theme of the page
+#[derive(Clone, Copy, PartialEq)]
+pub enum Theme {
+    /// Follow the preference of the operating system
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Name used in the local storage and the theme picker
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Theme {
+        match name {
+            "light" => Theme::Light,
+            "dark" => Theme::Dark,
+            _ => Theme::System,
+        }
+    }
+}
+
+/// Theme picked in a previous session
+pub fn load() -> Theme {
+    leptos::window()
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .map_or(Theme::System, |name| Theme::from_name(&name))
+}
+
+/// Switch the page to the theme and remember it for the next session
+pub fn apply(theme: Theme) {
+    if let Some(root) = leptos::document().document_element() {
+        let _ = match theme {
+            // The stylesheet follows prefers-color-scheme without an explicit theme
+            Theme::System => root.remove_attribute("data-theme"),
+            _ => root.set_attribute("data-theme", theme.name()),
+        };
+    }
+    if let Ok(Some(storage)) = leptos::window().local_storage() {
+        let _ = storage.set_item(STORAGE_KEY, theme.name());
+    }
+}
@@ -0,0 +1,66 @@
+use crate::i18n::Strings;
+
+/// Key saved in the local storage of the browser once the user picked a mode
+const STORAGE_KEY: &str = "midi_splitter_guided";
+
+/// Step of the guided mode, which shows one part of the page at a time
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+pub enum WizardStep {
+    Upload,
+    PickParts,
+    Settings,
+    Download,
+}
+
+impl WizardStep {
+    pub const ALL: [WizardStep; 4] = [
+        WizardStep::Upload,
+        WizardStep::PickParts,
+        WizardStep::Settings,
+        WizardStep::Download,
+    ];
+
+    pub fn label(&self, text: &Strings) -> &'static str {
+        match self {
+            WizardStep::Upload => text.step_upload,
+            WizardStep::PickParts => text.step_pick_parts,
+            WizardStep::Settings => text.step_settings,
+            WizardStep::Download => text.step_download,
+        }
+    }
+
+    /// Counting from 1
+    pub fn number(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|step| step == self)
+            .unwrap_or_default()
+            + 1
+    }
+
+    pub fn previous(&self) -> Option<WizardStep> {
+        Self::ALL.get(self.number().checked_sub(2)?).copied()
+    }
+
+    pub fn next(&self) -> Option<WizardStep> {
+        Self::ALL.get(self.number()).copied()
+    }
+}
+
+/// Whether to guide the user step by step. Users who have not picked a mode yet are guided on
+/// their first visit, which is when they have neither history nor saved settings.
+pub fn load(first_visit: bool) -> bool {
+    leptos::window()
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .map_or(first_visit, |guided| guided == "true")
+}
+
+/// Remember the mode picked by the user for the next session
+pub fn save(guided: bool) {
+    if let Ok(Some(storage)) = leptos::window().local_storage() {
+        let _ = storage.set_item(STORAGE_KEY, &guided.to_string());
+    }
+}
@@ -0,0 +1,105 @@
+use std::{cell::RefCell, rc::Rc};
+
+use futures_channel::oneshot;
+use midi_splitter_core::{File, Outputs, ProcessOptions, Progress, SplitError};
+use web_sys::{
+    wasm_bindgen::{closure::Closure, JsCast},
+    ErrorEvent, MessageEvent, Worker,
+};
+
+use crate::{
+    i18n::Strings,
+    js,
+    protocol::{Request, Response},
+};
+
+/// Script trunk generates to start the worker program
+const WORKER_SCRIPT: &str = "./worker_loader.js";
+
+/// Stops the running processing
+type Cancel = Box<dyn FnOnce()>;
+
+/// Lets the user stop the processing at any time
+#[derive(Clone, Default)]
+pub struct CancelHandle(Rc<RefCell<Option<Cancel>>>);
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        if let Some(cancel) = self.0.borrow_mut().take() {
+            cancel();
+        }
+    }
+
+    fn on_cancel(&self, cancel: impl FnOnce() + 'static) {
+        *self.0.borrow_mut() = Some(Box::new(cancel));
+    }
+}
+
+/// Process the uploads in a worker of their own, so the page keeps responding in the meantime.
+/// Cancelling terminates the worker right away, failing with [SplitError::Cancelled].
+pub async fn process(
+    files: Vec<File>,
+    options: &ProcessOptions,
+    on_progress: impl Fn(Progress) + 'static,
+    cancel: &CancelHandle,
+    text: &'static Strings,
+) -> Result<Outputs, SplitError> {
+    let worker = Worker::new(WORKER_SCRIPT)
+        .map_err(|e| anyhow::anyhow!((text.worker_start_failed)(&js::error_message(&e))))?;
+    let (sender, receiver) = oneshot::channel::<Result<Outputs, SplitError>>();
+    // Whichever of the worker and the user finishes first sends the result
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let finish = move |result: Result<Outputs, SplitError>| {
+        if let Some(sender) = sender.borrow_mut().take() {
+            let _ = sender.send(result);
+        }
+    };
+
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new({
+        let finish = finish.clone();
+        move |event: MessageEvent| match Response::from_js(&event.data()) {
+            Ok(Response::Progress(progress)) => on_progress(progress),
+            Ok(Response::Done(outputs)) => finish(Ok(outputs)),
+            Ok(Response::Failed(error)) => finish(Err(error)),
+            Err(e) => finish(Err(e.into())),
+        }
+    });
+    let on_error = Closure::<dyn FnMut(ErrorEvent)>::new({
+        let finish = finish.clone();
+        move |event: ErrorEvent| {
+            finish(Err(
+                anyhow::anyhow!((text.worker_failed)(&event.message())).into()
+            ))
+        }
+    });
+    worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    cancel.on_cancel({
+        let worker = worker.clone();
+        let finish = finish.clone();
+        move || {
+            worker.terminate();
+            finish(Err(SplitError::Cancelled));
+        }
+    });
+
+    let (message, transfer) = Request {
+        files,
+        options: options.clone(),
+    }
+    .to_js()?;
+    if let Err(e) = worker.post_message_with_transfer(&message, &transfer) {
+        finish(Err(anyhow::anyhow!((text.worker_send_failed)(
+            &js::error_message(&e)
+        ))
+        .into()));
+    }
+
+    let result = receiver
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!(text.worker_no_result).into()));
+    // A worker is started for every run, so it is not needed any more
+    worker.terminate();
+    cancel.0.borrow_mut().take();
+    result
+}
@@ -8,7 +8,20 @@ module.exports = {
 			sans: ["Graphik", "sans-serif"],
 			serif: ["Merriweather", "serif"],
 		},
-		extend: {},
+		extend: {
+			// Set by the theme in input.css
+			colors: {
+				page: "rgb(var(--color-page) / <alpha-value>)",
+				surface: "rgb(var(--color-surface) / <alpha-value>)",
+				sunken: "rgb(var(--color-sunken) / <alpha-value>)",
+				content: "rgb(var(--color-content) / <alpha-value>)",
+				accent: "rgb(var(--color-accent) / <alpha-value>)",
+				"accent-hover": "rgb(var(--color-accent-hover) / <alpha-value>)",
+				"on-accent": "rgb(var(--color-on-accent) / <alpha-value>)",
+				danger: "rgb(var(--color-danger) / <alpha-value>)",
+				"link-hover": "rgb(var(--color-link-hover) / <alpha-value>)",
+			},
+		},
 	},
 	plugins: [],
 };